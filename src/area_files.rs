@@ -13,6 +13,7 @@
 use crate::context;
 use crate::stats;
 use crate::util;
+use std::collections::HashMap;
 
 #[cfg(not(test))]
 use log::info;
@@ -100,6 +101,34 @@ impl RelationFiles {
         Ok(ret)
     }
 
+    /// Opens the OSM interpolation list of a relation for reading.
+    pub fn get_osm_json_interpolations(
+        &self,
+        ctx: &context::Context,
+    ) -> anyhow::Result<Vec<util::OsmInterpolation>> {
+        let mut ret: Vec<util::OsmInterpolation> = Vec::new();
+        let conn = ctx.get_database_connection()?;
+        let mut stmt = conn.prepare(
+            "select osm_id, street, interpolation, start_number, end_number from osm_interpolations where relation = ?1",
+        )?;
+        let mut rows = stmt.query([&self.name])?;
+        while let Some(row) = rows.next()? {
+            let id: String = row.get(0).unwrap();
+            let street: String = row.get(1).unwrap();
+            let interpolation: String = row.get(2).unwrap();
+            let start: String = row.get(3).unwrap();
+            let end: String = row.get(4).unwrap();
+            ret.push(util::OsmInterpolation::new(
+                id.parse()?,
+                &street,
+                &interpolation,
+                &start,
+                &end,
+            ));
+        }
+        Ok(ret)
+    }
+
     /// Writes the result for overpass of Relation.get_osm_streets_json_query().
     pub fn write_osm_json_streets(
         &self,
@@ -181,12 +210,96 @@ impl RelationFiles {
         // Insert or update the mtime for the osm housenumbers of this relation.
         stats::set_sql_mtime(ctx, &format!("housenumbers/{}", self.name))?;
 
+        // Resolve the endpoints of interpolation ways before the elements are consumed.
+        let mut node_housenumbers: HashMap<u64, (String, String)> = HashMap::new();
+        for element in &overpass.elements {
+            if element.osm_type == "node"
+                && let Some(ref housenumber) = element.tags.housenumber
+            {
+                let street = element.tags.street.clone().unwrap_or("".into());
+                node_housenumbers.insert(element.id, (housenumber.to_string(), street));
+            }
+        }
+        let mut interpolations: Vec<util::OsmInterpolation> = Vec::new();
+        for element in &overpass.elements {
+            let (Some(interpolation), Some(nodes)) = (&element.tags.interpolation, &element.nodes)
+            else {
+                continue;
+            };
+            let (Some(first), Some(last)) = (nodes.first(), nodes.last()) else {
+                continue;
+            };
+            let (Some((start, start_street)), Some((end, _))) =
+                (node_housenumbers.get(first), node_housenumbers.get(last))
+            else {
+                continue;
+            };
+            let street = element.tags.street.clone().unwrap_or(start_street.clone());
+            interpolations.push(util::OsmInterpolation::new(
+                element.id,
+                &street,
+                interpolation,
+                start,
+                end,
+            ));
+        }
+
+        // Remember the area of the house number nodes, so new objects from diffs can be placed.
+        let mut boundary: Option<(f64, f64, f64, f64)> = None;
+        for element in &overpass.elements {
+            let (Some(lat), Some(lon)) = (element.lat, element.lon) else {
+                continue;
+            };
+            boundary = Some(match boundary {
+                Some((min_lat, min_lon, max_lat, max_lon)) => (
+                    min_lat.min(lat),
+                    min_lon.min(lon),
+                    max_lat.max(lat),
+                    max_lon.max(lon),
+                ),
+                None => (lat, lon, lat, lon),
+            });
+        }
+
         let mut conn = ctx.get_database_connection()?;
         let tx = conn.transaction()?;
         tx.execute(
             "delete from osm_housenumbers where relation = ?1",
             [self.name.to_string()],
         )?;
+        tx.execute(
+            "delete from osm_boundaries where relation = ?1",
+            [self.name.to_string()],
+        )?;
+        if let Some((min_lat, min_lon, max_lat, max_lon)) = boundary {
+            tx.execute(
+                "insert into osm_boundaries (relation, min_lat, min_lon, max_lat, max_lon) values (?1, ?2, ?3, ?4, ?5)",
+                [
+                    self.name.to_string(),
+                    min_lat.to_string(),
+                    min_lon.to_string(),
+                    max_lat.to_string(),
+                    max_lon.to_string(),
+                ],
+            )?;
+        }
+        tx.execute(
+            "delete from osm_interpolations where relation = ?1",
+            [self.name.to_string()],
+        )?;
+        for interpolation in interpolations {
+            tx.execute(
+                "insert into osm_interpolations (relation, osm_id, street, interpolation, start_number, end_number) values (?1, ?2, ?3, ?4, ?5, ?6)",
+                [
+                    self.name.to_string(),
+                    interpolation.id.to_string(),
+                    interpolation.street,
+                    interpolation.interpolation,
+                    interpolation.start,
+                    interpolation.end,
+                ],
+            )?;
+        }
         for element in overpass.elements {
             let relation = self.name.to_string();
             let osm_id = element.id.to_string();
@@ -77,6 +77,92 @@ fn test_write_osm_json_housenumbers_duplicate() {
         .unwrap();
 }
 
+/// Tests RelationFiles::write_osm_json_housenumbers(), when the json has interpolation ways.
+#[test]
+fn test_write_osm_json_housenumbers_interpolation() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "gazdagret": {
+                "osmrelation": 2713748,
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let mut file_system = context::tests::TestFileSystem::new();
+    file_system.set_files(&files);
+    let file_system_rc: Rc<dyn context::FileSystem> = Rc::new(file_system);
+    ctx.set_file_system(&file_system_rc);
+    let mut relations = areas::Relations::new(&ctx).unwrap();
+    let relation = relations.get_relation("gazdagret").unwrap();
+    let result =
+        std::fs::read_to_string("src/fixtures/network/overpass-housenumbers-interpolation.json")
+            .unwrap();
+
+    relation
+        .get_files()
+        .write_osm_json_housenumbers(&ctx, &result)
+        .unwrap();
+
+    let interpolations = relation
+        .get_files()
+        .get_osm_json_interpolations(&ctx)
+        .unwrap();
+    // Way 5 has no nodes and the last node of way 6 has no house number.
+    assert_eq!(interpolations.len(), 1);
+    let interpolation = &interpolations[0];
+    assert_eq!(interpolation.id, 3);
+    // The street comes from the first node.
+    assert_eq!(interpolation.street, "Törökugrató utca");
+    assert_eq!(interpolation.interpolation, "odd");
+    assert_eq!(interpolation.start, "1");
+    assert_eq!(interpolation.end, "9");
+}
+
+/// Tests RelationFiles::write_osm_json_housenumbers(): the boundary of the nodes is stored.
+#[test]
+fn test_write_osm_json_housenumbers_boundary() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "gazdagret": {
+                "osmrelation": 2713748,
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    let mut relations = areas::Relations::new(&ctx).unwrap();
+    let relation = relations.get_relation("gazdagret").unwrap();
+    let result =
+        std::fs::read_to_string("src/fixtures/network/overpass-housenumbers-interpolation.json")
+            .unwrap();
+
+    relation
+        .get_files()
+        .write_osm_json_housenumbers(&ctx, &result)
+        .unwrap();
+
+    let conn = ctx.get_database_connection().unwrap();
+    let boundary: (f64, f64, f64, f64) = conn
+        .query_row(
+            "select min_lat, min_lon, max_lat, max_lon from osm_boundaries where relation = 'gazdagret'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .unwrap();
+    assert_eq!(boundary, (47.4701, 19.0061, 47.4725, 19.0103));
+}
+
 /// Tests write_whole_country(), when it gets non-JSON input.
 #[test]
 fn test_write_whole_country_non_json_input() {
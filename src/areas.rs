@@ -13,6 +13,7 @@
 use crate::area_files;
 use crate::cache;
 use crate::context;
+use crate::country;
 use crate::i18n::translate as tr;
 use crate::ranges;
 use crate::stats;
@@ -20,6 +21,7 @@ use crate::util;
 use crate::yattag;
 use anyhow::Context;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::fmt::Write as _;
 use std::ops::DerefMut;
 
@@ -28,6 +30,8 @@ use std::ops::DerefMut;
 #[serde(deny_unknown_fields)]
 pub struct RelationRangesDict {
     pub end: String,
+    /// House numbers which are excluded, despite being in the range.
+    pub except: Option<Vec<String>>,
     refsettlement: Option<String>,
     pub start: String,
 }
@@ -52,6 +56,7 @@ pub struct RelationFiltersDict {
 pub struct RelationDict {
     additional_housenumbers: Option<bool>,
     pub alias: Option<Vec<String>>,
+    conscriptionnumber: Option<bool>,
     pub filters: Option<HashMap<String, RelationFiltersDict>>,
     housenumber_letters: Option<bool>,
     inactive: Option<bool>,
@@ -71,6 +76,7 @@ impl Default for RelationDict {
     fn default() -> Self {
         let additional_housenumbers = None;
         let alias = None;
+        let conscriptionnumber = None;
         let filters = None;
         let housenumber_letters = None;
         let inactive = None;
@@ -86,6 +92,7 @@ impl Default for RelationDict {
         RelationDict {
             additional_housenumbers,
             alias,
+            conscriptionnumber,
             filters,
             housenumber_letters,
             inactive,
@@ -117,6 +124,11 @@ impl RelationConfig {
         }
     }
 
+    /// Replaces the relation-specific part of the config, e.g. with a not yet cached yaml file.
+    pub fn set_dict(&mut self, my_config: &RelationDict) {
+        self.dict = my_config.clone();
+    }
+
     /// Gets the typed value of a property transparently.
     fn get_property<T: Clone>(parent_value: &Option<T>, my_value: &Option<T>) -> Option<T> {
         if let Some(value) = my_value {
@@ -161,6 +173,11 @@ impl RelationConfig {
         RelationConfig::get_property(&self.parent.refsettlement, &self.dict.refsettlement).unwrap()
     }
 
+    /// Determines if the relation has a refsettlement identifier, i.e. if it has reference data.
+    fn has_refsettlement(&self) -> bool {
+        RelationConfig::get_property(&self.parent.refsettlement, &self.dict.refsettlement).is_some()
+    }
+
     /// Gets the alias(es) of the relation: alternative names which are also accepted.
     fn get_alias(&self) -> Vec<String> {
         RelationConfig::get_property(&self.parent.alias, &self.dict.alias).unwrap_or_default()
@@ -189,6 +206,15 @@ impl RelationConfig {
         RelationConfig::get_property(&self.parent.addr_unit, &self.dict.addr_unit).unwrap_or(false)
     }
 
+    /// Do we accept addr:conscriptionnumber as a replacement for addr:housenumber?
+    fn should_check_conscriptionnumber(&self) -> bool {
+        RelationConfig::get_property(
+            &self.parent.conscriptionnumber,
+            &self.dict.conscriptionnumber,
+        )
+        .unwrap_or(false)
+    }
+
     /// Do we care if 42 is in OSM when it's not in the ref?
     pub fn should_check_additional_housenumbers(&self) -> bool {
         RelationConfig::get_property(
@@ -325,6 +351,9 @@ impl RelationConfig {
 pub struct MissingHousenumbers {
     pub ongoing_streets: util::NumberedStreets,
     pub done_streets: util::NumberedStreets,
+    /// Subset of done_streets: reference house numbers only covered by addr:interpolation ways.
+    #[serde(default)]
+    pub interpolated_streets: util::NumberedStreets,
 }
 
 #[derive(Clone, Debug, Ord, PartialOrd, derivative::Derivative)]
@@ -341,12 +370,82 @@ pub struct RelationLint {
     pub id: u64,
     #[derivative(PartialEq = "ignore")]
     pub object_type: String,
+    /// The street the object probably belongs to, for the misattributed street reason.
+    pub other_street: String,
+}
+
+/// An OSM house number with a postcode which differs from the expected one of its street.
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PostcodeMismatch {
+    pub street_name: String,
+    pub housenumber: String,
+    /// The addr:postcode of the OSM object.
+    pub postcode: String,
+    /// Postcodes implied by the reference or by the ZIP count data, may be empty.
+    pub expected_postcodes: Vec<String>,
+    pub id: u64,
+    pub object_type: String,
+}
+
+/// An OSM object with an address.
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct AddressObject {
+    pub id: u64,
+    pub object_type: String,
+    /// The addr:housenumber of the OSM object, before normalization.
+    pub housenumber: String,
+}
+
+/// The same street and house number, used by multiple OSM objects.
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DuplicateAddress {
+    pub street_name: String,
+    /// The house number, with letter suffixes normalized, e.g. '12/A' for both '12a' and '12/A'.
+    pub housenumber: String,
+    pub objects: Vec<AddressObject>,
+}
+
+/// An addr:street value which is neither a named highway, nor a refstreets key in the relation.
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct OrphanStreet {
+    pub street_name: String,
+    pub objects: Vec<AddressObject>,
+    /// Names of highways with similar names, closest first.
+    pub closest_streets: Vec<String>,
+}
+
+/// Ranges of a street, inferred from the OSM house numbers, not yet in the relation's filters.
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ProposedFilter {
+    pub street_name: String,
+    /// Start and end of the odd and the even range.
+    pub ranges: Vec<(i64, i64)>,
+    /// Reference house numbers which would be filtered out by the ranges.
+    pub filtered_out: Vec<String>,
+}
+
+/// 'invalid' items of a street which are redundant, as the ranges of the street already filter
+/// them out.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RedundantInvalids {
+    pub street_name: String,
+    pub housenumbers: Vec<String>,
 }
 
 #[derive(Clone, Debug, Eq, Ord, PartialOrd, PartialEq)]
 pub enum RelationLintSource {
     Range,
     Invalid,
+    Conscriptionnumber,
+    LetterRange,
+    Street,
+    Address,
+    Place,
 }
 
 impl TryFrom<&str> for RelationLintSource {
@@ -356,6 +455,11 @@ impl TryFrom<&str> for RelationLintSource {
         match value {
             "range" => Ok(RelationLintSource::Range),
             "invalid" => Ok(RelationLintSource::Invalid),
+            "conscriptionnumber" => Ok(RelationLintSource::Conscriptionnumber),
+            "letter-range" => Ok(RelationLintSource::LetterRange),
+            "street" => Ok(RelationLintSource::Street),
+            "address" => Ok(RelationLintSource::Address),
+            "place" => Ok(RelationLintSource::Place),
             _ => Err(anyhow::anyhow!("invalid value: {value}")),
         }
     }
@@ -366,6 +470,11 @@ impl std::fmt::Display for RelationLintSource {
         match self {
             RelationLintSource::Range => write!(f, "range"),
             RelationLintSource::Invalid => write!(f, "invalid"),
+            RelationLintSource::Conscriptionnumber => write!(f, "conscriptionnumber"),
+            RelationLintSource::LetterRange => write!(f, "letter-range"),
+            RelationLintSource::Street => write!(f, "street"),
+            RelationLintSource::Address => write!(f, "address"),
+            RelationLintSource::Place => write!(f, "place"),
         }
     }
 }
@@ -375,6 +484,12 @@ pub enum RelationLintReason {
     CreatedInOsm,
     DeletedFromRef,
     OutOfRange,
+    ConscriptionnumberMismatch,
+    Malformed,
+    MisattributedStreet,
+    Duplicate,
+    PlaceIsStreet,
+    StreetIsPlace,
 }
 
 impl TryFrom<&str> for RelationLintReason {
@@ -385,6 +500,12 @@ impl TryFrom<&str> for RelationLintReason {
             "created-in-osm" => Ok(RelationLintReason::CreatedInOsm),
             "deleted-from-ref" => Ok(RelationLintReason::DeletedFromRef),
             "out-of-range" => Ok(RelationLintReason::OutOfRange),
+            "conscriptionnumber-mismatch" => Ok(RelationLintReason::ConscriptionnumberMismatch),
+            "malformed" => Ok(RelationLintReason::Malformed),
+            "misattributed-street" => Ok(RelationLintReason::MisattributedStreet),
+            "duplicate" => Ok(RelationLintReason::Duplicate),
+            "place-is-street" => Ok(RelationLintReason::PlaceIsStreet),
+            "street-is-place" => Ok(RelationLintReason::StreetIsPlace),
             _ => Err(anyhow::anyhow!("invalid value: {value}")),
         }
     }
@@ -396,6 +517,14 @@ impl std::fmt::Display for RelationLintReason {
             RelationLintReason::CreatedInOsm => write!(f, "created-in-osm"),
             RelationLintReason::DeletedFromRef => write!(f, "deleted-from-ref"),
             RelationLintReason::OutOfRange => write!(f, "out-of-range"),
+            RelationLintReason::ConscriptionnumberMismatch => {
+                write!(f, "conscriptionnumber-mismatch")
+            }
+            RelationLintReason::Malformed => write!(f, "malformed"),
+            RelationLintReason::MisattributedStreet => write!(f, "misattributed-street"),
+            RelationLintReason::Duplicate => write!(f, "duplicate"),
+            RelationLintReason::PlaceIsStreet => write!(f, "place-is-street"),
+            RelationLintReason::StreetIsPlace => write!(f, "street-is-place"),
         }
     }
 }
@@ -408,6 +537,9 @@ pub struct Relation<'a> {
     file: area_files::RelationFiles,
     config: RelationConfig,
     osm_housenumbers: HashMap<String, Vec<util::HouseNumber>>,
+    osm_conscriptionnumbers: HashMap<String, Vec<util::HouseNumber>>,
+    osm_interpolated_housenumbers: HashMap<String, Vec<util::HouseNumber>>,
+    additional_housenumbers: Option<util::NumberedStreets>,
     lints: Vec<RelationLint>,
 }
 
@@ -431,6 +563,10 @@ impl<'a> Relation<'a> {
         // osm street name -> house number list map, so we don't have to read the on-disk list of the
         // relation again and again for each street.
         let osm_housenumbers: HashMap<String, Vec<util::HouseNumber>> = HashMap::new();
+        let osm_conscriptionnumbers: HashMap<String, Vec<util::HouseNumber>> = HashMap::new();
+        let osm_interpolated_housenumbers: HashMap<String, Vec<util::HouseNumber>> = HashMap::new();
+        // Both the missing and the additional house numbers need this, only calculate it once.
+        let additional_housenumbers: Option<util::NumberedStreets> = None;
         let lints: Vec<RelationLint> = Vec::new();
         Ok(Relation {
             ctx,
@@ -438,6 +574,9 @@ impl<'a> Relation<'a> {
             file,
             config,
             osm_housenumbers,
+            osm_conscriptionnumbers,
+            osm_interpolated_housenumbers,
+            additional_housenumbers,
             lints,
         })
     }
@@ -484,17 +623,19 @@ impl<'a> Relation<'a> {
             let mut i: Vec<ranges::Range> = Vec::new();
             if let Some(ref value) = filter.ranges {
                 for range in value {
-                    let start = range
-                        .start
-                        .trim()
-                        .parse::<i64>()
-                        .context("failed to parse() 'start'")?;
-                    let end = range
-                        .end
-                        .trim()
-                        .parse::<i64>()
-                        .context("failed to parse() 'end'")?;
-                    i.push(ranges::Range::new(start, end, interpolation));
+                    let (start, start_letter) =
+                        ranges::parse_bound(&range.start).context("failed to parse 'start'")?;
+                    let (end, end_letter) =
+                        ranges::parse_bound(&range.end).context("failed to parse 'end'")?;
+                    let mut except: Vec<(i64, Option<char>)> = Vec::new();
+                    for item in range.except.iter().flatten() {
+                        except.push(ranges::parse_bound(item).context("failed to parse 'except'")?);
+                    }
+                    i.push(
+                        ranges::Range::new(start, end, interpolation)
+                            .with_letters(start_letter, end_letter)
+                            .with_except(except),
+                    );
                 }
                 filter_dict.insert(street.into(), ranges::Ranges::new(i));
             }
@@ -608,9 +749,13 @@ impl<'a> Relation<'a> {
             // once.
             let street_ranges = self.get_street_ranges()?;
             let mut house_numbers: HashMap<String, Vec<util::HouseNumber>> = HashMap::new();
+            let mut conscription_numbers: HashMap<String, Vec<util::HouseNumber>> = HashMap::new();
+            let check_conscriptionnumber = self.config.should_check_conscriptionnumber();
+            // Conscription numbers have their own numbering, the ranges of the street don't apply.
+            let no_ranges: HashMap<String, ranges::Ranges> = HashMap::new();
             let osm_housenumbers = self.file.get_osm_json_housenumbers(self.ctx)?;
             let mut lints: Vec<RelationLint> = Vec::new();
-            for row in osm_housenumbers {
+            for row in &osm_housenumbers {
                 let mut street = &row.street;
                 if street.is_empty()
                     && let Some(ref value) = row.place
@@ -619,7 +764,7 @@ impl<'a> Relation<'a> {
                 }
                 for house_number in row.housenumber.split(&[';', ',']) {
                     let mut lints = Some(&mut lints);
-                    let row = Some(&row);
+                    let row = Some(row);
                     let mut normalized =
                         normalize(self, house_number, street, &street_ranges, &mut lints, row)?;
                     house_numbers
@@ -627,8 +772,60 @@ impl<'a> Relation<'a> {
                         .or_default()
                         .append(&mut normalized)
                 }
+                if check_conscriptionnumber {
+                    for conscription_number in row.conscriptionnumber.split(&[';', ',']) {
+                        let normalized =
+                            simple_normalize(self, conscription_number, street, &no_ranges)?;
+                        conscription_numbers
+                            .entry(street.to_string())
+                            .or_default()
+                            .extend(normalized.into_iter().map(|mut i| {
+                                i.set_id(row.id);
+                                i.set_object_type(&row.object_type);
+                                i
+                            }));
+                    }
+                }
             }
             self.lints.append(&mut lints);
+            for duplicate in self.find_duplicate_addresses(&osm_housenumbers, &street_ranges)? {
+                for object in duplicate.objects {
+                    let mut housenumber = duplicate.housenumber.to_string();
+                    if object.housenumber != duplicate.housenumber {
+                        housenumber = format!("{} ({})", duplicate.housenumber, object.housenumber);
+                    }
+                    self.lints.push(RelationLint {
+                        relation_name: self.get_name(),
+                        street_name: duplicate.street_name.to_string(),
+                        source: RelationLintSource::Address,
+                        housenumber,
+                        reason: RelationLintReason::Duplicate,
+                        id: object.id,
+                        object_type: object.object_type,
+                        other_street: String::new(),
+                    });
+                }
+            }
+            self.lint_places(&osm_housenumbers)?;
+            self.osm_conscriptionnumbers = conscription_numbers;
+            let mut interpolated_numbers: HashMap<String, Vec<util::HouseNumber>> = HashMap::new();
+            for interpolation in self.file.get_osm_json_interpolations(self.ctx)? {
+                let normalizer = util::get_normalizer(&interpolation.street, &street_ranges);
+                let source = format!("{}-{}", interpolation.start, interpolation.end);
+                for number in interpolation.get_numbers() {
+                    if !normalizer.contains(number) {
+                        continue;
+                    }
+                    let mut house_number = util::HouseNumber::new(&number.to_string(), &source, "");
+                    house_number.set_id(interpolation.id);
+                    house_number.set_object_type("way");
+                    interpolated_numbers
+                        .entry(interpolation.street.to_string())
+                        .or_default()
+                        .push(house_number);
+                }
+            }
+            self.osm_interpolated_housenumbers = interpolated_numbers;
             for (key, mut value) in house_numbers {
                 value.sort_unstable();
                 value.dedup();
@@ -672,6 +869,7 @@ impl<'a> Relation<'a> {
                             reason,
                             id,
                             object_type,
+                            other_street: String::new(),
                         };
                         self.lints.push(lint);
                     }
@@ -687,12 +885,153 @@ impl<'a> Relation<'a> {
         })
     }
 
-    /// Determines what suffix should the Nth reference use for hours numbers.
-    pub fn get_ref_suffix(index: usize) -> &'static str {
-        match index {
-            0 => "",
-            _ => "*",
+    /// Finds house numbers where addr:place is used, but the place is a street in the reference,
+    /// and the reverse: addr:street is used, but it's not a highway, while it is a place.
+    ///
+    /// Place names are only known from the addr:place values used in the same relation, OSM
+    /// objects with a place=* tag are not fetched. So an addr:street referring to a place which no
+    /// other address of the relation uses via addr:place is not reported.
+    fn lint_places(&mut self, osm_housenumbers: &[util::OsmHouseNumber]) -> anyhow::Result<()> {
+        let mut ref_streets: HashSet<String> = HashSet::new();
+        if self.config.has_refsettlement() {
+            ref_streets = self.get_ref_streets()?.into_iter().collect();
+        }
+        let highways: HashSet<String> = self
+            .file
+            .get_osm_json_streets(self.ctx)?
+            .into_iter()
+            .map(|i| i.name)
+            .collect();
+        let places: HashSet<&String> = osm_housenumbers
+            .iter()
+            .filter_map(|i| i.place.as_ref())
+            .filter(|i| !i.is_empty())
+            .collect();
+        for row in osm_housenumbers {
+            let (street_name, reason) = if row.street.is_empty() {
+                let place = match row.place {
+                    Some(ref value) if !value.is_empty() => value,
+                    _ => continue,
+                };
+                let ref_street = self.config.get_ref_street_from_osm_street(place);
+                if !ref_streets.contains(&ref_street) {
+                    continue;
+                }
+                (place, RelationLintReason::PlaceIsStreet)
+            } else {
+                if highways.contains(&row.street) || !places.contains(&row.street) {
+                    continue;
+                }
+                (&row.street, RelationLintReason::StreetIsPlace)
+            };
+            self.lints.push(RelationLint {
+                relation_name: self.get_name(),
+                street_name: street_name.to_string(),
+                source: RelationLintSource::Place,
+                housenumber: row.housenumber.to_string(),
+                reason,
+                id: row.id,
+                object_type: row.object_type.to_string(),
+                other_street: String::new(),
+            });
         }
+        Ok(())
+    }
+
+    /// Finds addresses which are used by multiple OSM objects, also considering the house numbers
+    /// after normalization, e.g. '12a' and '12/A' is the same address.
+    fn find_duplicate_addresses(
+        &self,
+        osm_housenumbers: &[util::OsmHouseNumber],
+        street_ranges: &HashMap<String, ranges::Ranges>,
+    ) -> anyhow::Result<Vec<DuplicateAddress>> {
+        let mut addresses: HashMap<(String, String), Vec<AddressObject>> = HashMap::new();
+        for row in osm_housenumbers {
+            let mut street = &row.street;
+            if street.is_empty()
+                && let Some(ref value) = row.place
+            {
+                street = value;
+            }
+            for house_number in row.housenumber.split(&[';', ',']) {
+                let house_number = house_number.trim();
+                if simple_normalize(self, house_number, street, street_ranges)?.is_empty() {
+                    // Not a house number or out of range.
+                    continue;
+                }
+                let key = util::HouseNumber::normalize_letter_suffix(house_number, "")
+                    .unwrap_or_else(|_| house_number.to_string());
+                let objects = addresses.entry((street.to_string(), key)).or_default();
+                if objects
+                    .iter()
+                    .any(|i| i.id == row.id && i.object_type == row.object_type)
+                {
+                    continue;
+                }
+                objects.push(AddressObject {
+                    id: row.id,
+                    object_type: row.object_type.to_string(),
+                    housenumber: house_number.to_string(),
+                });
+            }
+        }
+
+        let mut ret: Vec<DuplicateAddress> = addresses
+            .into_iter()
+            .filter(|(_, objects)| objects.len() > 1)
+            .map(|((street_name, housenumber), mut objects)| {
+                objects.sort_by_key(|i| (i.object_type.to_string(), i.id));
+                DuplicateAddress {
+                    street_name,
+                    housenumber,
+                    objects,
+                }
+            })
+            .collect();
+        ret.sort_by_key(|i| {
+            (
+                i.street_name.to_string(),
+                util::split_house_number(&i.housenumber),
+            )
+        });
+        Ok(ret)
+    }
+
+    /// Gets the addresses which are used by multiple OSM objects in the relation.
+    pub fn get_duplicate_addresses(&self) -> anyhow::Result<Vec<DuplicateAddress>> {
+        let street_ranges = self.get_street_ranges()?;
+        let osm_housenumbers = self.file.get_osm_json_housenumbers(self.ctx)?;
+        self.find_duplicate_addresses(&osm_housenumbers, &street_ranges)
+    }
+
+    /// Gets the 'invalid' items which are redundant, as the ranges of the same street already
+    /// filter them out. Such items show up as out of range lints, in case the reference has them.
+    pub fn get_redundant_invalids(&self) -> anyhow::Result<Vec<RedundantInvalids>> {
+        let street_ranges = self.get_street_ranges()?;
+        let no_ranges: HashMap<String, ranges::Ranges> = HashMap::new();
+        let mut ret: Vec<RedundantInvalids> = Vec::new();
+        for (street, invalids) in self.get_street_invalid() {
+            if !street_ranges.contains_key(&street) {
+                continue;
+            }
+
+            let mut housenumbers: Vec<String> = Vec::new();
+            for invalid in invalids {
+                let filtered = normalize(self, &invalid, &street, &street_ranges, &mut None, None)?;
+                let unfiltered = normalize(self, &invalid, &street, &no_ranges, &mut None, None)?;
+                if filtered.is_empty() && !unfiltered.is_empty() {
+                    housenumbers.push(invalid);
+                }
+            }
+            if !housenumbers.is_empty() {
+                ret.push(RedundantInvalids {
+                    street_name: street,
+                    housenumbers,
+                });
+            }
+        }
+        ret.sort_by_key(|i| util::get_sort_key(&i.street_name));
+        Ok(ret)
     }
 
     /// Normalizes an 'invalid' list.
@@ -724,6 +1063,7 @@ impl<'a> Relation<'a> {
     ) -> anyhow::Result<HashMap<String, Vec<util::HouseNumber>>> {
         let mut ret: HashMap<String, Vec<util::HouseNumber>> = HashMap::new();
         let mut lines: HashMap<String, Vec<String>> = HashMap::new();
+        let profile = self.ctx.get_profile();
         let conn = self.ctx.get_database_connection()?;
         let mut stmt = conn.prepare(
             "select housenumber, comment from ref_housenumbers where county_code = ?1 and settlement_code = ?2 and street = ?3 order by housenumber")?;
@@ -736,11 +1076,9 @@ impl<'a> Relation<'a> {
                     stmt.query([&self.config.get_refcounty(), &refsettlement, &street])?;
                 while let Some(row) = rows.next()? {
                     let housenumber: String = row.get(0).unwrap();
-                    let mut comment: String = row.get(1).unwrap();
-                    let suffix = Relation::get_ref_suffix(if comment.is_empty() { 0 } else { 1 });
-                    if comment == " " {
-                        comment = "".into();
-                    }
+                    let comment: String = row.get(1).unwrap();
+                    let suffix = profile.get_ref_suffix(&comment);
+                    let comment = profile.get_display_comment(&comment);
                     let value = housenumber + suffix + "\t" + &comment;
                     lines.entry(street.to_string()).or_default().push(value);
                 }
@@ -795,6 +1133,18 @@ impl<'a> Relation<'a> {
                     .collect();
 
                 for house_number in hns {
+                    if self.config.should_check_housenumber_letters() {
+                        let number = house_number.split('\t').next().unwrap();
+                        let suffix = if number.ends_with('*') { "*" } else { "" };
+                        if util::HouseNumber::parse_letter_range(number, suffix).is_err() {
+                            self.lints.push(make_letter_range_lint(
+                                &self.get_name(),
+                                osm_street_name,
+                                number,
+                                None,
+                            ));
+                        }
+                    }
                     let normalized =
                         simple_normalize(self, house_number, osm_street_name, &street_ranges)?;
                     house_numbers.append(
@@ -848,6 +1198,7 @@ impl<'a> Relation<'a> {
                             reason,
                             id,
                             object_type,
+                            other_street: String::new(),
                         };
                         self.lints.push(lint);
                     }
@@ -870,17 +1221,40 @@ impl<'a> Relation<'a> {
     pub fn get_missing_housenumbers(&mut self) -> anyhow::Result<MissingHousenumbers> {
         let mut ongoing_streets = Vec::new();
         let mut done_streets = Vec::new();
+        let mut interpolated_streets = Vec::new();
 
         let osm_street_names = self.get_osm_streets(/*sorted_result=*/ true)?;
         let all_ref_house_numbers = self
             .get_ref_housenumbers(&osm_street_names)
             .context("get_ref_housenumbers() failed")?;
-        for osm_street in osm_street_names {
+        for osm_street in &osm_street_names {
             let osm_street_name = osm_street.get_osm_name();
             let ref_house_numbers = &all_ref_house_numbers[osm_street_name];
             let osm_house_numbers = self.get_osm_housenumbers(osm_street_name)?;
-            let only_in_reference = util::get_only_in_first(ref_house_numbers, &osm_house_numbers);
-            let in_both = util::get_in_both(ref_house_numbers, &osm_house_numbers);
+            let mut only_in_reference =
+                util::get_only_in_first(ref_house_numbers, &osm_house_numbers);
+            let mut in_both = util::get_in_both(ref_house_numbers, &osm_house_numbers);
+            if self.config.should_check_conscriptionnumber() {
+                let osm_conscription_numbers = self
+                    .osm_conscriptionnumbers
+                    .get(osm_street_name)
+                    .cloned()
+                    .unwrap_or_default();
+                self.lint_conscriptionnumbers(
+                    osm_street_name,
+                    ref_house_numbers,
+                    &osm_house_numbers,
+                    &osm_conscription_numbers,
+                );
+                // A matching conscription number covers the reference house number, too.
+                let mut covered = util::get_in_both(&only_in_reference, &osm_conscription_numbers);
+                if !covered.is_empty() {
+                    only_in_reference =
+                        util::get_only_in_first(&only_in_reference, &osm_conscription_numbers);
+                    in_both.append(&mut covered);
+                    in_both = util::sort_numerically(&in_both);
+                }
+            }
             let ref_street_name = self.config.get_ref_street_from_osm_street(osm_street_name);
             let street = util::Street::new(
                 osm_street_name,
@@ -888,6 +1262,24 @@ impl<'a> Relation<'a> {
                 self.config.should_show_ref_street(osm_street_name),
                 /*osm_id=*/ 0,
             );
+            // An addr:interpolation way covers the reference house number, too, but track these
+            // separately, so they can be told apart from individually mapped house numbers.
+            let osm_interpolated_numbers = self
+                .osm_interpolated_housenumbers
+                .get(osm_street_name)
+                .cloned()
+                .unwrap_or_default();
+            let interpolated = util::get_in_both(&only_in_reference, &osm_interpolated_numbers);
+            if !interpolated.is_empty() {
+                only_in_reference =
+                    util::get_only_in_first(&only_in_reference, &osm_interpolated_numbers);
+                in_both.append(&mut interpolated.clone());
+                in_both = util::sort_numerically(&in_both);
+                interpolated_streets.push(util::NumberedStreet {
+                    street: street.clone(),
+                    house_numbers: interpolated,
+                });
+            }
             if !only_in_reference.is_empty() {
                 ongoing_streets.push(util::NumberedStreet {
                     street: street.clone(),
@@ -901,15 +1293,123 @@ impl<'a> Relation<'a> {
                 });
             }
         }
+        let additional_streets =
+            self.get_additional_housenumbers_for(&osm_street_names, &all_ref_house_numbers)?;
+        self.lint_misattributed_streets(
+            &ongoing_streets,
+            &additional_streets,
+            &all_ref_house_numbers,
+        );
         // Sort by length, reverse.
         ongoing_streets.sort_by_key(|b| std::cmp::Reverse(b.house_numbers.len()));
 
         Ok(MissingHousenumbers {
             ongoing_streets,
             done_streets,
+            interpolated_streets,
         })
     }
 
+    /// Finds OSM objects where the conscription number matches the reference, but the street
+    /// number does not.
+    fn lint_conscriptionnumbers(
+        &mut self,
+        osm_street_name: &str,
+        ref_house_numbers: &[util::HouseNumber],
+        osm_house_numbers: &[util::HouseNumber],
+        osm_conscription_numbers: &[util::HouseNumber],
+    ) {
+        let ref_numbers: Vec<&str> = ref_house_numbers
+            .iter()
+            .map(|i| i.get_number().trim_end_matches('*'))
+            .collect();
+        for conscription_number in osm_conscription_numbers {
+            if !ref_numbers.contains(&conscription_number.get_number()) {
+                continue;
+            }
+
+            let street_numbers: Vec<&str> = osm_house_numbers
+                .iter()
+                .filter(|i| i.get_id() == conscription_number.get_id())
+                .map(|i| i.get_number())
+                .collect();
+            if street_numbers.is_empty() || street_numbers.iter().any(|i| ref_numbers.contains(i)) {
+                continue;
+            }
+
+            let lint = RelationLint {
+                relation_name: self.get_name(),
+                street_name: osm_street_name.to_string(),
+                source: RelationLintSource::Conscriptionnumber,
+                housenumber: format!(
+                    "{} ({})",
+                    street_numbers.join(", "),
+                    conscription_number.get_number()
+                ),
+                reason: RelationLintReason::ConscriptionnumberMismatch,
+                id: conscription_number.get_id().unwrap_or(0),
+                object_type: conscription_number.get_object_type().unwrap_or_default(),
+                other_street: String::new(),
+            };
+            self.lints.push(lint);
+        }
+    }
+
+    /// Finds OSM objects which are additional on their tagged street, while the same number is
+    /// missing on an other street: this is typically a corner building, tagged with the cross
+    /// street. There is no geometry at hand to decide which streets are adjacent, so only report
+    /// the cases where the number is missing on exactly one other street.
+    fn lint_misattributed_streets(
+        &mut self,
+        ongoing_streets: &[util::NumberedStreet],
+        additional_streets: &[util::NumberedStreet],
+        all_ref_house_numbers: &HashMap<String, Vec<util::HouseNumber>>,
+    ) {
+        let streets_invalid = self.get_street_invalid();
+        for additional_street in additional_streets {
+            let osm_street_name = additional_street.street.get_osm_name();
+            if all_ref_house_numbers[osm_street_name].is_empty() {
+                // The tagged street is not in the reference, the other lints cover this.
+                continue;
+            }
+            let invalids = streets_invalid
+                .get(osm_street_name)
+                .cloned()
+                .unwrap_or_default();
+            for house_number in &additional_street.house_numbers {
+                if invalids.iter().any(|i| i == house_number.get_number()) {
+                    // Explicitly silenced on the tagged street.
+                    continue;
+                }
+                let candidates: Vec<&str> = ongoing_streets
+                    .iter()
+                    .filter(|i| i.street.get_osm_name() != osm_street_name)
+                    .filter(|i| {
+                        i.house_numbers.iter().any(|j| {
+                            j.get_number().trim_end_matches('*') == house_number.get_number()
+                        })
+                    })
+                    .map(|i| i.street.get_osm_name().as_str())
+                    .collect();
+                if candidates.len() != 1 {
+                    continue;
+                }
+
+                let lint = RelationLint {
+                    relation_name: self.get_name(),
+                    street_name: osm_street_name.to_string(),
+                    source: RelationLintSource::Street,
+                    housenumber: house_number.get_number().to_string(),
+                    reason: RelationLintReason::MisattributedStreet,
+                    id: house_number.get_id().unwrap_or(0),
+                    object_type: house_number.get_object_type().unwrap_or_default(),
+                    other_street: candidates[0].to_string(),
+                };
+                self.lints.push(lint);
+            }
+        }
+    }
+
     /// Tries to find missing streets in a relation.
     pub fn get_missing_streets(&self) -> anyhow::Result<(Vec<String>, Vec<String>)> {
         let reference_streets: Vec<util::Street> = self
@@ -966,6 +1466,180 @@ impl<'a> Relation<'a> {
         Ok(only_in_osm)
     }
 
+    /// Pairs additional (OSM-only) streets with missing (reference-only) streets with similar
+    /// names, producing OSM name -> reference name suggestions for refstreets.
+    pub fn get_refstreet_suggestions(&self) -> anyhow::Result<Vec<(String, String)>> {
+        let profile = self.ctx.get_profile();
+        let (missing_streets, _) = self.get_missing_streets()?;
+        let additional_streets = self.get_additional_streets(/*sorted_result=*/ true)?;
+
+        let ref_keys: Vec<String> = missing_streets
+            .iter()
+            .map(|i| get_street_match_key(&*profile, i))
+            .collect();
+        let mut candidates: Vec<(usize, usize, String, String)> = Vec::new();
+        for osm_street in additional_streets {
+            let osm_name = osm_street.get_osm_name();
+            let osm_key = get_street_match_key(&*profile, osm_name);
+            for (distance, index) in find_similar_streets(&osm_key, &ref_keys) {
+                let ref_name = &missing_streets[index];
+                // Tie-breaker: distance without folding accents and expanding abbreviations.
+                let raw_distance =
+                    util::get_edit_distance(&osm_name.to_lowercase(), &ref_name.to_lowercase());
+                candidates.push((
+                    distance,
+                    raw_distance,
+                    osm_name.to_string(),
+                    ref_name.to_string(),
+                ));
+            }
+        }
+
+        // refstreets is a 1:1 mapping, so prefer the closest matches and use each name once.
+        candidates.sort_by_key(|(distance, raw_distance, osm_name, ref_name)| {
+            (
+                *distance,
+                *raw_distance,
+                util::get_sort_key(osm_name),
+                util::get_sort_key(ref_name),
+            )
+        });
+        let mut ret: Vec<(String, String)> = Vec::new();
+        for (_distance, _raw_distance, osm_name, ref_name) in candidates {
+            if ret
+                .iter()
+                .any(|(osm, reference)| *osm == osm_name || *reference == ref_name)
+            {
+                continue;
+            }
+            ret.push((osm_name, ref_name));
+        }
+        ret.sort_by_key(|(osm_name, _ref_name)| util::get_sort_key(osm_name));
+        Ok(ret)
+    }
+
+    /// Finds addr:street values which have no matching named highway in the relation, and are
+    /// not refstreets keys either. These are typically typos, so similar highway names are
+    /// suggested.
+    pub fn get_orphan_streets(&self) -> anyhow::Result<Vec<OrphanStreet>> {
+        let profile = self.ctx.get_profile();
+        let mut highways: Vec<String> = self
+            .file
+            .get_osm_json_streets(self.ctx)?
+            .into_iter()
+            .map(|i| i.name)
+            .collect();
+        highways.sort_by_key(|i| util::get_sort_key(i));
+        highways.dedup();
+        let highway_set: HashSet<&String> = highways.iter().collect();
+        let refstreets = self.config.get_refstreets();
+
+        let mut streets: HashMap<String, Vec<AddressObject>> = HashMap::new();
+        for row in self.file.get_osm_json_housenumbers(self.ctx)? {
+            if row.street.is_empty()
+                || highway_set.contains(&row.street)
+                || refstreets.contains_key(&row.street)
+            {
+                continue;
+            }
+            streets
+                .entry(row.street.to_string())
+                .or_default()
+                .push(AddressObject {
+                    id: row.id,
+                    object_type: row.object_type,
+                    housenumber: row.housenumber,
+                });
+        }
+
+        let highway_keys: Vec<String> = highways
+            .iter()
+            .map(|i| get_street_match_key(&*profile, i))
+            .collect();
+        let mut ret: Vec<OrphanStreet> = Vec::new();
+        for (street_name, mut objects) in streets {
+            objects.sort_by_key(|i| (i.object_type.to_string(), i.id));
+            let key = get_street_match_key(&*profile, &street_name);
+            let closest_streets = find_similar_streets(&key, &highway_keys)
+                .into_iter()
+                .take(3)
+                .map(|(_distance, index)| highways[index].to_string())
+                .collect();
+            ret.push(OrphanStreet {
+                street_name,
+                objects,
+                closest_streets,
+            });
+        }
+        ret.sort_by_key(|i| util::get_sort_key(&i.street_name));
+        Ok(ret)
+    }
+
+    /// Infers odd and even ranges for streets without ranges from the OSM house numbers. Only
+    /// proposes ranges which would filter out some reference house numbers. In case a street has
+    /// no OSM house numbers on one side, then that side gets the default range, since there is no
+    /// data to infer from.
+    pub fn get_proposed_filters(&mut self) -> anyhow::Result<Vec<ProposedFilter>> {
+        let osm_street_names = self.get_osm_streets(/*sorted_result=*/ true)?;
+        let all_ref_house_numbers = self.get_ref_housenumbers(&osm_street_names)?;
+        let street_ranges = self.get_street_ranges()?;
+        let mut ret: Vec<ProposedFilter> = Vec::new();
+        for osm_street in &osm_street_names {
+            let osm_street_name = osm_street.get_osm_name();
+            if street_ranges.contains_key(osm_street_name) {
+                // Already has ranges, presumably from a survey.
+                continue;
+            }
+
+            let osm_numbers: Vec<i64> = self
+                .get_osm_housenumbers(osm_street_name)?
+                .iter()
+                .map(|i| util::split_house_number(i.get_number()).0 as i64)
+                .filter(|i| *i > 0)
+                .collect();
+            if osm_numbers.is_empty() {
+                continue;
+            }
+
+            let mut ranges: Vec<(i64, i64)> = Vec::new();
+            for is_odd in [true, false] {
+                let side: Vec<i64> = osm_numbers
+                    .iter()
+                    .filter(|i| (*i % 2 == 1) == is_odd)
+                    .cloned()
+                    .collect();
+                if let (Some(min), Some(max)) = (side.iter().min(), side.iter().max()) {
+                    ranges.push((*min, *max));
+                }
+            }
+            if ranges.len() < 2 {
+                // No observed range for one side: guessing one would be noise.
+                continue;
+            }
+            let normalizer = ranges::Ranges::new(
+                ranges
+                    .iter()
+                    .map(|(start, end)| ranges::Range::new(*start, *end, ""))
+                    .collect(),
+            );
+            let filtered_out: Vec<String> = all_ref_house_numbers[osm_street_name]
+                .iter()
+                .filter(|i| !normalizer.contains(util::split_house_number(i.get_number()).0 as i64))
+                .map(|i| i.get_number().to_string())
+                .collect();
+            if filtered_out.is_empty() {
+                continue;
+            }
+
+            ret.push(ProposedFilter {
+                street_name: osm_street_name.to_string(),
+                ranges,
+                filtered_out,
+            });
+        }
+        Ok(ret)
+    }
+
     /// Calculate and write stat for the street coverage of a relation.
     pub fn write_missing_streets(&self) -> anyhow::Result<(usize, usize, f64, Vec<String>)> {
         let (todo_streets, done_streets) = self.get_missing_streets()?;
@@ -993,6 +1667,7 @@ impl<'a> Relation<'a> {
         let table = "additional_streets_counts";
         let count = additional_streets.len();
         stats::set_sql_count(self.ctx, table, &self.name, count)?;
+        stats::set_sql_mtime(self.ctx, &format!("additional-streets/{}", self.name))?;
 
         Ok(additional_streets)
     }
@@ -1105,14 +1780,61 @@ impl<'a> Relation<'a> {
         ))
     }
 
+    /// Produces a HTML table of the reference house numbers which are only covered by
+    /// addr:interpolation ways. Returns the number of such house numbers and the table.
+    pub fn get_interpolated_housenumbers_table(
+        &mut self,
+    ) -> anyhow::Result<(usize, yattag::HtmlTable)> {
+        let json = cache::get_missing_housenumbers_json(self)
+            .context("get_missing_housenumbers_json() failed")?;
+        let missing_housenumbers: MissingHousenumbers = serde_json::from_str(&json)?;
+
+        let mut count = 0;
+        let mut table = vec![vec![
+            yattag::Doc::from_text(&tr("Street name")),
+            yattag::Doc::from_text(&tr("House numbers")),
+        ]];
+        for result in missing_housenumbers.interpolated_streets {
+            let number_ranges = util::get_housenumber_ranges(&result.house_numbers);
+            count += number_ranges.len();
+            let numbers: Vec<_> = number_ranges
+                .iter()
+                .map(|i| i.get_number().to_string())
+                .collect();
+            table.push(vec![
+                result.street.to_html(),
+                yattag::Doc::from_text(&numbers.join(", ")),
+            ]);
+        }
+        Ok((count, table))
+    }
+
     /// Compares ref and osm house numbers, prints the ones which are in osm, but not in ref.
     /// Return value is a list of streets.
     /// Each of of these is a pair of a street name and a house number list.
     pub fn get_additional_housenumbers(&mut self) -> anyhow::Result<util::NumberedStreets> {
-        let mut additional = Vec::new();
+        if let Some(ref value) = self.additional_housenumbers {
+            return Ok(value.clone());
+        }
 
         let osm_street_names = self.get_osm_streets(/*sorted_result=*/ true)?;
         let all_ref_house_numbers = self.get_ref_housenumbers(&osm_street_names)?;
+        self.get_additional_housenumbers_for(&osm_street_names, &all_ref_house_numbers)
+    }
+
+    /// Same as get_additional_housenumbers(), but the OSM streets and the reference house numbers
+    /// are already known.
+    fn get_additional_housenumbers_for(
+        &mut self,
+        osm_street_names: &[util::Street],
+        all_ref_house_numbers: &HashMap<String, Vec<util::HouseNumber>>,
+    ) -> anyhow::Result<util::NumberedStreets> {
+        if let Some(ref value) = self.additional_housenumbers {
+            return Ok(value.clone());
+        }
+
+        let mut additional = Vec::new();
+
         let streets_valid = self.get_street_valid();
         for osm_street in osm_street_names {
             let osm_street_name = osm_street.get_osm_name();
@@ -1153,6 +1875,7 @@ impl<'a> Relation<'a> {
         // Sort by length, reverse.
         additional.sort_by_key(|b| std::cmp::Reverse(b.house_numbers.len()));
 
+        self.additional_housenumbers = Some(additional.clone());
         Ok(additional)
     }
 
@@ -1278,6 +2001,114 @@ impl<'a> Relation<'a> {
         Ok(())
     }
 
+    /// Gets the expected postcodes of a street from the reference.
+    fn get_ref_postcodes(&self, osm_street_name: &str) -> anyhow::Result<Vec<String>> {
+        let mut ret: Vec<String> = Vec::new();
+        let conn = self.ctx.get_database_connection()?;
+        let mut stmt = conn.prepare(
+            "select distinct postcode from ref_housenumbers where county_code = ?1 and settlement_code = ?2 and street = ?3 and postcode != ''",
+        )?;
+        let street = self.config.get_ref_street_from_osm_street(osm_street_name);
+        for refsettlement in self.config.get_street_refsettlement(&street) {
+            let mut rows = stmt.query([&self.config.get_refcounty(), &refsettlement, &street])?;
+            while let Some(row) = rows.next()? {
+                let postcode: String = row.get(0).unwrap();
+                ret.push(postcode);
+            }
+        }
+        ret.sort();
+        ret.dedup();
+        Ok(ret)
+    }
+
+    /// Finds OSM house numbers where addr:postcode differs from the postcode the reference implies
+    /// for the street. If the reference has no postcodes for the street, then the most common
+    /// postcode of the street is expected, as long as the ZIP count data knows it.
+    pub fn get_postcode_mismatches(&self) -> anyhow::Result<Vec<PostcodeMismatch>> {
+        let mut valid_postcodes: HashSet<String> = HashSet::new();
+        {
+            let stream = self
+                .ctx
+                .get_file_system()
+                .open_read(&self.ctx.get_ini().get_reference_zipcounts_path()?)?;
+            let mut guard = stream.borrow_mut();
+            let mut read = guard.deref_mut();
+            let mut csv_reader = util::make_csv_reader(&mut read);
+            for result in csv_reader.deserialize() {
+                let row: util::ZipCount = result?;
+                valid_postcodes.insert(row.zip);
+            }
+        }
+
+        // street -> (postcode, housenumber, id, object_type) list
+        let mut streets: HashMap<String, Vec<(String, String, u64, String)>> = HashMap::new();
+        {
+            let conn = self.ctx.get_database_connection()?;
+            let mut stmt = conn.prepare(
+                "select osm_id, street, place, housenumber, postcode, osm_type from osm_housenumbers where relation = ?1 and postcode != ''",
+            )?;
+            let mut rows = stmt.query([&self.name])?;
+            while let Some(row) = rows.next()? {
+                let id: String = row.get(0).unwrap();
+                let mut street: String = row.get(1).unwrap();
+                if street.is_empty() {
+                    street = row.get(2).unwrap();
+                }
+                let housenumber: String = row.get(3).unwrap();
+                let postcode: String = row.get(4).unwrap();
+                let object_type: String = row.get(5).unwrap();
+                streets.entry(street).or_default().push((
+                    postcode,
+                    housenumber,
+                    id.parse()?,
+                    object_type,
+                ));
+            }
+        }
+
+        let mut ret: Vec<PostcodeMismatch> = Vec::new();
+        for (street_name, rows) in streets {
+            let mut expected_postcodes = self.get_ref_postcodes(&street_name)?;
+            if expected_postcodes.is_empty() {
+                let mut counts: HashMap<&str, usize> = HashMap::new();
+                for (postcode, ..) in rows.iter().filter(|i| valid_postcodes.contains(&i.0)) {
+                    *counts.entry(postcode).or_default() += 1;
+                }
+                // Most common first, then the smaller postcode in case of a tie.
+                if let Some((postcode, _)) = counts
+                    .into_iter()
+                    .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(a.0)))
+                {
+                    expected_postcodes.push(postcode.to_string());
+                }
+            }
+            if expected_postcodes.is_empty() {
+                // Nothing is known about the street, so no postcode is wrong.
+                continue;
+            }
+            for (postcode, housenumber, id, object_type) in rows {
+                if expected_postcodes.contains(&postcode) {
+                    continue;
+                }
+                ret.push(PostcodeMismatch {
+                    street_name: street_name.to_string(),
+                    housenumber,
+                    postcode,
+                    expected_postcodes: expected_postcodes.clone(),
+                    id,
+                    object_type,
+                });
+            }
+        }
+        ret.sort_by_cached_key(|i| {
+            (
+                util::get_sort_key(&i.street_name),
+                util::split_house_number(&i.housenumber),
+            )
+        });
+        Ok(ret)
+    }
+
     pub fn write_lints(&mut self) -> anyhow::Result<()> {
         let conn = self.ctx.get_database_connection()?;
         conn.execute(
@@ -1288,8 +2119,8 @@ impl<'a> Relation<'a> {
         self.lints.dedup();
         for lint in self.lints.iter() {
             conn.execute(
-                r#"insert into relation_lints (relation_name, street_name, source, housenumber, reason, object_id, object_type) values (?1, ?2, ?3, ?4, ?5, ?6, ?7)"#,
-                 [&lint.relation_name, &lint.street_name, &lint.source.to_string(), &lint.housenumber, &lint.reason.to_string(), &lint.id.to_string(), &lint.object_type],
+                r#"insert into relation_lints (relation_name, street_name, source, housenumber, reason, object_id, object_type, other_street) values (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"#,
+                 [&lint.relation_name, &lint.street_name, &lint.source.to_string(), &lint.housenumber, &lint.reason.to_string(), &lint.id.to_string(), &lint.object_type, &lint.other_street],
                  )?;
         }
         Ok(())
@@ -1396,16 +2227,16 @@ impl<'a> Relations<'a> {
         let activate_all = false;
         let activate_new = false;
         let activate_invalid = false;
-        let refcounty_names: HashMap<String, String> = match yaml_cache.get("refcounty-names.yaml")
-        {
+        let levels = ctx.get_profile().get_hierarchy_levels();
+        let refcounty_names: HashMap<String, String> = match yaml_cache.get(&levels.county_names) {
             Some(value) => serde_json::from_value(value.clone())
-                .context("failed to parse refcounty-names.yaml")?,
+                .context(format!("failed to parse {}", levels.county_names))?,
             None => HashMap::new(),
         };
         let refsettlement_names: HashMap<String, HashMap<String, String>> =
-            match yaml_cache.get("refsettlement-names.yaml") {
+            match yaml_cache.get(&levels.settlement_names) {
                 Some(value) => serde_json::from_value(value.clone())
-                    .context("failed to parse refsettlement-names.yaml")?,
+                    .context(format!("failed to parse {}", levels.settlement_names))?,
                 None => HashMap::new(),
             };
         Ok(Relations {
@@ -1639,13 +2470,17 @@ impl<'a> Relations<'a> {
 
 pub fn normalizer_contains(
     number: i64,
+    letter: Option<char>,
     normalizer: &ranges::Ranges,
     relation_name: &str,
     street_name: &str,
     lints: &mut Option<&mut Vec<RelationLint>>,
     osm_housenumber: Option<&util::OsmHouseNumber>,
 ) -> bool {
-    let ret = normalizer.contains(number);
+    let ret = match letter {
+        Some(letter) => normalizer.contains_letter(number, letter),
+        None => normalizer.contains(number),
+    };
     // number not in the ranges: raise a lint in case the problem is actionable (has street name,
     // has an actual number).
     if !ret
@@ -1656,7 +2491,10 @@ pub fn normalizer_contains(
         let relation_name = relation_name.to_string();
         let street_name = street_name.to_string();
         let source = RelationLintSource::Range;
-        let housenumber = number.to_string();
+        let housenumber = match letter {
+            Some(letter) => format!("{number}/{letter}"),
+            None => number.to_string(),
+        };
         let reason = RelationLintReason::CreatedInOsm;
         let id: u64 = match osm_housenumber {
             Some(value) => value.id,
@@ -1674,12 +2512,36 @@ pub fn normalizer_contains(
             reason,
             id,
             object_type,
+            other_street: String::new(),
         };
         lints.push(lint);
     }
     ret
 }
 
+/// Creates a lint for a letter range which is malformed, e.g. the letters are not increasing.
+fn make_letter_range_lint(
+    relation_name: &str,
+    street_name: &str,
+    housenumber: &str,
+    osm_housenumber: Option<&util::OsmHouseNumber>,
+) -> RelationLint {
+    let (id, object_type) = match osm_housenumber {
+        Some(value) => (value.id, value.object_type.to_string()),
+        None => (0, "".to_string()),
+    };
+    RelationLint {
+        relation_name: relation_name.into(),
+        street_name: street_name.into(),
+        source: RelationLintSource::LetterRange,
+        housenumber: housenumber.into(),
+        reason: RelationLintReason::Malformed,
+        id,
+        object_type,
+        other_street: String::new(),
+    }
+}
+
 struct LintedHouseNumber<'a> {
     lints: &'a mut Option<&'a mut Vec<RelationLint>>,
     osm_housenumber: Option<&'a util::OsmHouseNumber>,
@@ -1711,6 +2573,7 @@ fn normalize_expand(
                 .filter(|number| {
                     normalizer_contains(
                         **number,
+                        /*letter=*/ None,
                         normalizer,
                         relation_name,
                         street_name,
@@ -1728,6 +2591,7 @@ fn normalize_expand(
                 .filter(|number| {
                     normalizer_contains(
                         *number,
+                        /*letter=*/ None,
                         normalizer,
                         relation_name,
                         street_name,
@@ -1742,6 +2606,7 @@ fn normalize_expand(
                 .filter(|number| {
                     normalizer_contains(
                         *number,
+                        /*letter=*/ None,
                         normalizer,
                         relation_name,
                         street_name,
@@ -1823,6 +2688,81 @@ fn normalize<'a>(
 
     let normalizer = util::get_normalizer(street_name, normalizers);
 
+    if separator == "-" && relation.config.should_check_housenumber_letters() {
+        match util::HouseNumber::parse_letter_range(&house_numbers, &suffix) {
+            Ok(Some((number, first, last))) => {
+                // Expand 12/A-12/D to 12/A, 12/B, 12/C and 12/D. Check each letter, the bare
+                // number may be out of range, e.g. for a 12/B start. A single lint is enough if
+                // the whole range is out of range.
+                if !(first..=last).any(|letter| normalizer.contains_letter(number, letter)) {
+                    normalizer_contains(
+                        number,
+                        Some(first),
+                        &normalizer,
+                        &relation.get_name(),
+                        street_name,
+                        lints,
+                        osm_housenumber,
+                    );
+                    return Ok(vec![]);
+                }
+                let ret: Vec<util::HouseNumber> = (first..=last)
+                    .filter(|letter| {
+                        normalizer_contains(
+                            number,
+                            Some(*letter),
+                            &normalizer,
+                            &relation.get_name(),
+                            street_name,
+                            lints,
+                            osm_housenumber,
+                        )
+                    })
+                    .map(|letter| {
+                        util::HouseNumber::new(
+                            &format!("{number}/{letter}{suffix}"),
+                            &house_numbers,
+                            &comment,
+                        )
+                    })
+                    .collect();
+                return Ok(set_osm_info(ret, osm_housenumber));
+            }
+            Ok(None) => (),
+            Err(_) => {
+                if let Some(lints) = lints {
+                    lints.push(make_letter_range_lint(
+                        &relation.get_name(),
+                        street_name,
+                        &house_numbers,
+                        osm_housenumber,
+                    ));
+                }
+            }
+        }
+    }
+
+    // A number with a letter, e.g. 20/C: check the number and the letter together, the bare
+    // number may be out of range, e.g. for a 20/B start.
+    if relation.config.should_check_housenumber_letters()
+        && let Ok(normalized) = util::HouseNumber::normalize_letter_suffix(&house_numbers, &suffix)
+        && let Ok((number, Some(letter))) = ranges::parse_bound(normalized.trim_end_matches(star))
+    {
+        if !normalizer_contains(
+            number,
+            Some(letter),
+            &normalizer,
+            &relation.get_name(),
+            street_name,
+            lints,
+            osm_housenumber,
+        ) {
+            return Ok(vec![]);
+        }
+        let ret = normalize_housenumber_letters(&house_numbers, &suffix, &comment)?;
+        return Ok(set_osm_info(ret, osm_housenumber));
+    }
+
     let (mut ret_numbers, ret_numbers_nofilter) = util::split_house_number_by_separator(
         &house_numbers,
         separator,
@@ -1865,7 +2805,15 @@ fn normalize<'a>(
     };
     // Finally annotate the result in case we got OSM info, regardless of the value of
     // check_housenumber_letters.
-    Ok(ret
+    Ok(set_osm_info(ret, osm_housenumber))
+}
+
+/// Annotates house numbers with the OSM object they come from, if there is one.
+fn set_osm_info(
+    house_numbers: Vec<util::HouseNumber>,
+    osm_housenumber: Option<&util::OsmHouseNumber>,
+) -> Vec<util::HouseNumber> {
+    house_numbers
         .iter()
         .map(|number| {
             let mut housenumber = number.clone();
@@ -1875,7 +2823,7 @@ fn normalize<'a>(
             }
             housenumber
         })
-        .collect())
+        .collect()
 }
 
 /// Handles the part of normalize() that deals with housenumber letters.
@@ -1892,6 +2840,48 @@ fn normalize_housenumber_letters(
     )])
 }
 
+/// Builds a street name key for fuzzy matching: folds accents, expands street type abbreviations
+/// and ignores case.
+fn get_street_match_key(profile: &dyn country::CountryProfile, name: &str) -> String {
+    let abbreviations = profile.get_street_type_abbreviations();
+    let folded = unidecode::unidecode(name).to_lowercase().replace('-', " ");
+    let tokens: Vec<&str> = folded
+        .split_whitespace()
+        .map(|token| {
+            match abbreviations
+                .iter()
+                .find(|(abbreviation, _full)| abbreviation == token)
+            {
+                Some((_abbreviation, full)) => full,
+                None => token,
+            }
+        })
+        .collect();
+    tokens.join(" ")
+}
+
+/// Finds the street names similar to a street name, given their keys from
+/// get_street_match_key(). Returns the distances and the indexes of the matching keys, closest
+/// first. The sort is stable, so matches with the same distance keep the order of the keys.
+fn find_similar_streets(key: &str, keys: &[String]) -> Vec<(usize, usize)> {
+    let mut ret: Vec<(usize, usize)> = keys
+        .iter()
+        .enumerate()
+        .filter_map(|(index, other_key)| {
+            let distance = util::get_edit_distance(key, other_key);
+            // Allow a typo per 5 characters.
+            let limit = key.chars().count().max(other_key.chars().count()) / 5;
+            if distance <= limit {
+                Some((distance, index))
+            } else {
+                None
+            }
+        })
+        .collect();
+    ret.sort_by_key(|(distance, _index)| *distance);
+    ret
+}
+
 /// Creates an overpass query that shows all streets from a missing housenumbers table.
 /// Creates an overpass query that shows the streets from a list, without the overpass-turbo
 /// specific MapCSS style block, so it can be sent to the raw overpass API.
@@ -1923,6 +2913,66 @@ way{color:blue; width:4;}
     query
 }
 
+/// Creates an overpass query that shows the objects of postcode mismatches.
+pub fn make_turbo_query_for_postcode_mismatches(mismatches: &[PostcodeMismatch]) -> String {
+    let mut query = r#"[out:json][timeout:425];
+(
+"#
+    .to_string();
+    for mismatch in mismatches {
+        query += &format!("{}({});\n", mismatch.object_type, mismatch.id);
+    }
+    query += r#");
+out body;
+>;
+out skel qt;
+{{style:
+node{color:red}
+way{color:red; width:4;}
+}}"#;
+    query
+}
+
+/// Produces a yaml snippet from proposed filters: the per-street entries, which can be merged into
+/// the filters of the relation's yaml file.
+pub fn format_proposed_filters_yaml(filters: &[ProposedFilter]) -> String {
+    let mut ret = String::new();
+    for filter in filters {
+        ret.push_str(&format!("'{}':\n", filter.street_name.replace('\'', "''")));
+        ret.push_str(&format!(
+            "  # filters out: {}\n",
+            filter.filtered_out.join(", ")
+        ));
+        ret.push_str("  ranges:\n");
+        for (start, end) in &filter.ranges {
+            ret.push_str(&format!("    - {{start: '{start}', end: '{end}'}}\n"));
+        }
+    }
+    ret
+}
+
+/// Creates an overpass query that shows the objects of orphan streets.
+pub fn make_turbo_query_for_orphan_streets(orphan_streets: &[OrphanStreet]) -> String {
+    let mut query = r#"[out:json][timeout:425];
+(
+"#
+    .to_string();
+    for orphan_street in orphan_streets {
+        for object in &orphan_street.objects {
+            query += &format!("{}({});\n", object.object_type, object.id);
+        }
+    }
+    query += r#");
+out body;
+>;
+out skel qt;
+{{style:
+node{color:red}
+way{color:red; width:4;}
+}}"#;
+    query
+}
+
 /// Creates an overpass query that shows all settlements without any house numbers.
 pub fn make_turbo_query_for_housenumberless(ctx: &context::Context) -> anyhow::Result<String> {
     let mut query = r#"[out:json][timeout:425];
@@ -519,6 +519,288 @@ fn test_normalize_separator_comma() {
     assert_eq!(actual, vec!["2", "6"]);
 }
 
+/// Tests normalize: the case when a letter range is expanded.
+#[test]
+fn test_normalize_letter_range() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "gazdagret": {
+                "osmrelation": 2713748,
+            },
+        },
+        "relation-gazdagret.yaml": {
+            "filters": {
+                "Budaörs út": {
+                    "ranges": [
+                        {
+                            "start": "2",
+                            "end": "12",
+                        }
+                    ],
+                },
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    let mut relations = Relations::new(&ctx).unwrap();
+    let mut relation = relations.get_relation("gazdagret").unwrap();
+    let normalizers = relation.get_street_ranges().unwrap();
+
+    // Default case: housenumber-letters=false, only the number is kept.
+    let house_numbers = normalize(
+        &relation,
+        "12/A-12/C",
+        "Budaörs út",
+        &normalizers,
+        &mut None,
+        None,
+    )
+    .unwrap();
+    let actual: Vec<_> = house_numbers.iter().map(|i| i.get_number()).collect();
+    assert_eq!(actual, vec!["12"]);
+
+    // Opt-in case: housenumber-letters=true.
+    let mut config = relation.get_config().clone();
+    set_config_housenumber_letters(&mut config, true);
+    relation.set_config(&config);
+    let house_numbers = normalize(
+        &relation,
+        "12/A-12/C",
+        "Budaörs út",
+        &normalizers,
+        &mut None,
+        None,
+    )
+    .unwrap();
+    let actual: Vec<_> = house_numbers.iter().map(|i| i.get_number()).collect();
+    assert_eq!(actual, vec!["12/A", "12/B", "12/C"]);
+    let house_numbers = normalize(
+        &relation,
+        "12a-b*",
+        "Budaörs út",
+        &normalizers,
+        &mut None,
+        None,
+    )
+    .unwrap();
+    let actual: Vec<_> = house_numbers.iter().map(|i| i.get_number()).collect();
+    assert_eq!(actual, vec!["12/A*", "12/B*"]);
+
+    // Out of range: dropped, with a lint.
+    let osm_housenumber =
+        util::OsmHouseNumber::new(42, "14a-b", "", "Budaörs út", &None, "way", "");
+    let mut lints: Vec<RelationLint> = Vec::new();
+    let house_numbers = normalize(
+        &relation,
+        "14a-b",
+        "Budaörs út",
+        &normalizers,
+        &mut Some(&mut lints),
+        Some(&osm_housenumber),
+    )
+    .unwrap();
+    assert_eq!(house_numbers.is_empty(), true);
+    assert_eq!(lints.len(), 1);
+    assert_eq!(lints[0].source, RelationLintSource::Range);
+
+    // Malformed: a lint and the number is kept.
+    let osm_housenumber =
+        util::OsmHouseNumber::new(43, "12/C-12/A", "", "Budaörs út", &None, "node", "");
+    let mut lints: Vec<RelationLint> = Vec::new();
+    let house_numbers = normalize(
+        &relation,
+        "12/C-12/A",
+        "Budaörs út",
+        &normalizers,
+        &mut Some(&mut lints),
+        Some(&osm_housenumber),
+    )
+    .unwrap();
+    let actual: Vec<_> = house_numbers.iter().map(|i| i.get_number()).collect();
+    assert_eq!(actual, vec!["12"]);
+    assert_eq!(lints.len(), 1);
+    assert_eq!(lints[0].source, RelationLintSource::LetterRange);
+    assert_eq!(lints[0].reason, RelationLintReason::Malformed);
+    assert_eq!(lints[0].housenumber, "12/C-12/A");
+    assert_eq!(lints[0].id, 43);
+    assert_eq!(lints[0].object_type, "node");
+}
+
+/// Tests normalize(): ranges with except items and lettered bounds.
+#[test]
+fn test_normalize_range_except_letters() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "gazdagret": {
+                "osmrelation": 2713748,
+            },
+        },
+        "relation-gazdagret.yaml": {
+            "filters": {
+                "Budaörs út": {
+                    "ranges": [
+                        {
+                            "start": "2",
+                            "end": "20/B",
+                            "except": ["6", "10/A"],
+                        }
+                    ],
+                },
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    let mut relations = Relations::new(&ctx).unwrap();
+    let mut relation = relations.get_relation("gazdagret").unwrap();
+    let normalizers = relation.get_street_ranges().unwrap();
+    let normalize_numbers = |relation: &Relation<'_>, house_numbers: &str| -> Vec<String> {
+        normalize(
+            relation,
+            house_numbers,
+            "Budaörs út",
+            &normalizers,
+            &mut None,
+            None,
+        )
+        .unwrap()
+        .iter()
+        .map(|i| i.get_number().to_string())
+        .collect()
+    };
+
+    // Default case: housenumber-letters=false, only the numeric part is checked.
+    assert_eq!(normalize_numbers(&relation, "6").is_empty(), true);
+    assert_eq!(normalize_numbers(&relation, "10/A"), vec!["10"]);
+    assert_eq!(normalize_numbers(&relation, "20/C"), vec!["20"]);
+
+    // Opt-in case: housenumber-letters=true.
+    let mut config = relation.get_config().clone();
+    set_config_housenumber_letters(&mut config, true);
+    relation.set_config(&config);
+    assert_eq!(normalize_numbers(&relation, "6").is_empty(), true);
+    assert_eq!(normalize_numbers(&relation, "6/A").is_empty(), true);
+    assert_eq!(normalize_numbers(&relation, "10/A").is_empty(), true);
+    assert_eq!(normalize_numbers(&relation, "10/B"), vec!["10/B"]);
+    assert_eq!(normalize_numbers(&relation, "20"), vec!["20"]);
+    assert_eq!(normalize_numbers(&relation, "20/B"), vec!["20/B"]);
+
+    // Out of range: dropped, with a lint.
+    let osm_housenumber =
+        util::OsmHouseNumber::new(42, "20/A-20/D", "", "Budaörs út", &None, "way", "");
+    let mut lints: Vec<RelationLint> = Vec::new();
+    let house_numbers = normalize(
+        &relation,
+        "20/A-20/D",
+        "Budaörs út",
+        &normalizers,
+        &mut Some(&mut lints),
+        Some(&osm_housenumber),
+    )
+    .unwrap();
+    let actual: Vec<_> = house_numbers.iter().map(|i| i.get_number()).collect();
+    assert_eq!(actual, vec!["20/A", "20/B"]);
+    let actual: Vec<_> = lints.iter().map(|i| i.housenumber.as_str()).collect();
+    assert_eq!(actual, vec!["20/C", "20/D"]);
+    let mut lints: Vec<RelationLint> = Vec::new();
+    let house_numbers = normalize(
+        &relation,
+        "20/C",
+        "Budaörs út",
+        &normalizers,
+        &mut Some(&mut lints),
+        Some(&osm_housenumber),
+    )
+    .unwrap();
+    assert_eq!(house_numbers.is_empty(), true);
+    assert_eq!(lints.len(), 1);
+    assert_eq!(lints[0].housenumber, "20/C");
+    assert_eq!(lints[0].source, RelationLintSource::Range);
+}
+
+/// Tests normalize(): lettered start and end bounds, the bare start number is out of range.
+#[test]
+fn test_normalize_range_letter_bounds() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "gazdagret": {
+                "osmrelation": 2713748,
+            },
+        },
+        "relation-gazdagret.yaml": {
+            "housenumber-letters": true,
+            "filters": {
+                "Budaörs út": {
+                    "ranges": [
+                        {
+                            "start": "2/B",
+                            "end": "6/C",
+                        }
+                    ],
+                },
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    let mut relations = Relations::new(&ctx).unwrap();
+    let relation = relations.get_relation("gazdagret").unwrap();
+    let normalizers = relation.get_street_ranges().unwrap();
+    let mut lints: Vec<RelationLint> = Vec::new();
+    let mut normalize_numbers = |house_numbers: &str| -> Vec<String> {
+        normalize(
+            &relation,
+            house_numbers,
+            "Budaörs út",
+            &normalizers,
+            &mut Some(&mut lints),
+            None,
+        )
+        .unwrap()
+        .iter()
+        .map(|i| i.get_number().to_string())
+        .collect()
+    };
+
+    // Kept.
+    assert_eq!(normalize_numbers("2/B"), vec!["2/B"]);
+    assert_eq!(normalize_numbers("2/C"), vec!["2/C"]);
+    assert_eq!(normalize_numbers("2/A-2/D"), vec!["2/B", "2/C", "2/D"]);
+    assert_eq!(normalize_numbers("4"), vec!["4"]);
+    assert_eq!(normalize_numbers("6/C"), vec!["6/C"]);
+    // Dropped.
+    assert!(normalize_numbers("2").is_empty());
+    assert!(normalize_numbers("2/A").is_empty());
+    assert!(normalize_numbers("6/D").is_empty());
+
+    let actual: Vec<_> = lints.iter().map(|i| i.housenumber.as_str()).collect();
+    assert_eq!(actual, vec!["2/A", "2", "2/A", "6/D"]);
+    assert!(
+        lints
+            .iter()
+            .all(|i| i.reason == RelationLintReason::CreatedInOsm)
+    );
+}
+
 /// Tests Relation.get_osm_streets().
 #[test]
 fn test_relation_get_osm_streets() {
@@ -609,6 +891,60 @@ fn test_relation_get_osm_streets_conscriptionnumber() {
     assert_eq!(streets.contains(&expected), true);
 }
 
+/// Tests Relation.get_osm_housenumbers(): the street ranges don't filter conscription numbers.
+#[test]
+fn test_relation_get_osm_housenumbers_conscriptionnumber_ranges() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "osmrelation": 42,
+            },
+        },
+        "relation-myrelation.yaml": {
+            "conscriptionnumber": true,
+            "filters": {
+                "mystreet": {
+                    "ranges": [
+                        {
+                            "start": "40",
+                            "end": "50",
+                        }
+                    ],
+                },
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '1', 'mystreet', '42', '', '', '', '3', '', '', '', '', '', 'node');
+             insert into mtimes (page, last_modified) values ('housenumbers/myrelation', '0')",
+        )
+        .unwrap();
+    }
+    let mut relations = Relations::new(&ctx).unwrap();
+    let mut relation = relations.get_relation("myrelation").unwrap();
+
+    let house_numbers = relation.get_osm_housenumbers("mystreet").unwrap();
+
+    let numbers: Vec<_> = house_numbers.iter().map(|i| i.get_number()).collect();
+    assert_eq!(numbers, ["42"]);
+    // 3 is outside the 40-50 range, but that only applies to house numbers.
+    let numbers: Vec<_> = relation.osm_conscriptionnumbers["mystreet"]
+        .iter()
+        .map(|i| i.get_number())
+        .collect();
+    assert_eq!(numbers, ["3"]);
+}
+
 /// Tests Relation.get_osm_streets_query().
 #[test]
 fn test_relation_get_osm_streets_query() {
@@ -1523,22 +1859,23 @@ fn test_relation_get_lints() {
     );
 }
 
-/// Tests Relation::get_lints(), the housenumber-letters=true case.
+/// Tests Relation::get_lints(), the misattributed street case.
 #[test]
-fn test_relation_get_lints_hn_letters() {
+fn test_relation_get_lints_misattributed_street() {
     let mut ctx = context::tests::make_test_context().unwrap();
     let yamls_cache = serde_json::json!({
         "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+            },
         },
         "relation-myrelation.yaml": {
-            "refcounty": "0",
-            "refsettlement": "0",
             "filters": {
-                "Tolvajos tanya": {
-                    "invalid": [ "52b" ],
+                "Fő utca": {
+                    "invalid": [ "11" ],
                 }
             },
-            "housenumber-letters": true,
         },
     });
     let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
@@ -1551,35 +1888,259 @@ fn test_relation_get_lints_hn_letters() {
     {
         let conn = ctx.get_database_connection().unwrap();
         conn.execute_batch(
-            "insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Tolvajos tanya', '52/b', '');
-             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '1', '', '52/b', '', 'Tolvajos tanya', '', '', '', '', '', '', '', 'node');
+            "insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Fő utca', '1', '');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Kossuth utca', '3', '');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Kossuth utca', '5', '');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Kossuth utca', '11', '');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Petőfi utca', '5', '');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '1', 'Fő utca', '1', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '2', 'Fő utca', '3', '', '', '', '', '', '', '', '', '', 'way');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '3', 'Fő utca', '5', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '4', 'Fő utca', '11', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '5', 'Only In OSM utca', '3', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('myrelation', '1', 'Kossuth utca', '', '', '', '', '');
+             insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('myrelation', '2', 'Petőfi utca', '', '', '', '', '');
              insert into mtimes (page, last_modified) values ('housenumbers/myrelation', '0');",
         )
         .unwrap();
     }
     let mut relations = Relations::new(&ctx).unwrap();
-    let relation_name = "myrelation";
-    let mut relation = relations.get_relation(relation_name).unwrap();
+    let mut relation = relations.get_relation("myrelation").unwrap();
     let _missing_housenumbers = relation.get_missing_housenumbers().unwrap();
 
-    let lints = relation.get_lints();
+    let lints: Vec<_> = relation
+        .get_lints()
+        .iter()
+        .filter(|i| i.reason == RelationLintReason::MisattributedStreet)
+        .cloned()
+        .collect();
 
-    // Previously this failed, lints was empty.
+    // 3 is only missing on Kossuth utca; 5 is missing on two streets, so it's ambiguous; 11 is
+    // silenced on Fő utca; Only In OSM utca has no reference data.
     assert_eq!(lints.len(), 1);
     let lint = lints[0].clone();
-    assert_eq!(lint.relation_name, "myrelation");
-    assert_eq!(lint.street_name, "Tolvajos tanya");
-    assert_eq!(lint.source, RelationLintSource::Invalid);
-    assert_eq!(format!("{:?}", lint.source), "Invalid");
-    assert_eq!(lint.housenumber, "52/B");
-    assert_eq!(lint.reason, RelationLintReason::CreatedInOsm);
-}
+    assert_eq!(lint.street_name, "Fő utca");
+    assert_eq!(lint.source, RelationLintSource::Street);
+    assert_eq!(lint.housenumber, "3");
+    assert_eq!(lint.other_street, "Kossuth utca");
+    assert_eq!(lint.reason, RelationLintReason::MisattributedStreet);
+    assert_eq!(lint.id, 2);
+    assert_eq!(lint.object_type, "way");
 
-/// Tests Relation::get_lints(), the out-of-range case.
-#[test]
-fn test_relation_get_lints_out_of_range() {
-    let mut ctx = context::tests::make_test_context().unwrap();
-    let yamls_cache = serde_json::json!({
+    // The additional house numbers are calculated only once: a reference change is not noticed.
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Only In OSM utca', '3', '');",
+        )
+        .unwrap();
+    }
+    relation.get_missing_housenumbers().unwrap();
+    let additional = relation.get_additional_housenumbers().unwrap();
+    let streets: Vec<_> = additional.iter().map(|i| i.street.get_osm_name()).collect();
+    assert!(streets.contains(&&"Only In OSM utca".to_string()));
+}
+
+/// Tests Relation::get_duplicate_addresses().
+#[test]
+fn test_relation_get_duplicate_addresses() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '1', 'Fő utca', '12/A', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '2', 'Fő utca', '12a', '', '', '', '', '', '', '', '', '', 'way');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '3', 'Fő utca', '12/B', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '4', 'Fő utca', '5;5', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '5', 'Fő utca', 'x', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '6', 'Fő utca', '5', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '7', 'Fő utca', 'x', '', '', '', '', '', '', '', '', '', 'way');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '8', '', '1', '', 'Kert', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '9', '', '1', '', 'Kert', '', '', '', '', '', '', '', 'node');
+             insert into mtimes (page, last_modified) values ('housenumbers/myrelation', '0');",
+        )
+        .unwrap();
+    }
+    let mut relations = Relations::new(&ctx).unwrap();
+    let mut relation = relations.get_relation("myrelation").unwrap();
+
+    let duplicates = relation.get_duplicate_addresses().unwrap();
+
+    // 12/B is not a duplicate of 12/A, node 4 is only counted once and 'x' is not a house number.
+    let addresses: Vec<_> = duplicates
+        .iter()
+        .map(|i| {
+            (
+                i.street_name.as_str(),
+                i.housenumber.as_str(),
+                i.objects.len(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        addresses,
+        vec![
+            ("Fő utca", "5", 2),
+            ("Fő utca", "12/A", 2),
+            ("Kert", "1", 2)
+        ]
+    );
+    let objects: Vec<_> = duplicates[1]
+        .objects
+        .iter()
+        .map(|i| (i.object_type.as_str(), i.id, i.housenumber.as_str()))
+        .collect();
+    assert_eq!(objects, vec![("node", 1, "12/A"), ("way", 2, "12a")]);
+
+    // The same is also available as lints.
+    let _missing_housenumbers = relation.get_missing_housenumbers().unwrap();
+    let lints: Vec<_> = relation
+        .get_lints()
+        .iter()
+        .filter(|i| i.reason == RelationLintReason::Duplicate)
+        .collect();
+    assert_eq!(lints.len(), 6);
+    assert_eq!(
+        lints
+            .iter()
+            .any(|i| i.source == RelationLintSource::Address && i.housenumber == "12/A (12a)"),
+        true
+    );
+}
+
+/// Tests Relation::get_lints(), the addr:place vs addr:street case.
+#[test]
+fn test_relation_get_lints_place() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into ref_streets (county_code, settlement_code, street) values ('0', '0', 'Kossuth utca');
+             insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('myrelation', '1', 'Fő utca', '', '', '', '', '');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '1', '', '1', '', 'Kossuth utca', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '2', '', '2', '', 'Kert', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '3', 'Kert', '3', '', '', '', '', '', '', '', '', '', 'way');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '4', 'Fő utca', '4', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '5', 'Egyéb', '5', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '6', '', '6', '', '', '', '', '', '', '', '', '', 'node');
+             insert into mtimes (page, last_modified) values ('housenumbers/myrelation', '0');",
+        )
+        .unwrap();
+    }
+    let mut relations = Relations::new(&ctx).unwrap();
+    let mut relation = relations.get_relation("myrelation").unwrap();
+    let _missing_housenumbers = relation.get_missing_housenumbers().unwrap();
+
+    let lints: Vec<_> = relation
+        .get_lints()
+        .iter()
+        .filter(|i| i.source == RelationLintSource::Place)
+        .map(|i| {
+            (
+                i.street_name.as_str(),
+                i.housenumber.as_str(),
+                i.reason.clone(),
+                i.id,
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        lints,
+        vec![
+            ("Kossuth utca", "1", RelationLintReason::PlaceIsStreet, 1),
+            ("Kert", "3", RelationLintReason::StreetIsPlace, 3),
+        ]
+    );
+}
+
+/// Tests Relation::get_lints(), the housenumber-letters=true case.
+#[test]
+fn test_relation_get_lints_hn_letters() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+        },
+        "relation-myrelation.yaml": {
+            "refcounty": "0",
+            "refsettlement": "0",
+            "filters": {
+                "Tolvajos tanya": {
+                    "invalid": [ "52b" ],
+                }
+            },
+            "housenumber-letters": true,
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Tolvajos tanya', '52/b', '');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '1', '', '52/b', '', 'Tolvajos tanya', '', '', '', '', '', '', '', 'node');
+             insert into mtimes (page, last_modified) values ('housenumbers/myrelation', '0');",
+        )
+        .unwrap();
+    }
+    let mut relations = Relations::new(&ctx).unwrap();
+    let relation_name = "myrelation";
+    let mut relation = relations.get_relation(relation_name).unwrap();
+    let _missing_housenumbers = relation.get_missing_housenumbers().unwrap();
+
+    let lints = relation.get_lints();
+
+    // Previously this failed, lints was empty.
+    assert_eq!(lints.len(), 1);
+    let lint = lints[0].clone();
+    assert_eq!(lint.relation_name, "myrelation");
+    assert_eq!(lint.street_name, "Tolvajos tanya");
+    assert_eq!(lint.source, RelationLintSource::Invalid);
+    assert_eq!(format!("{:?}", lint.source), "Invalid");
+    assert_eq!(lint.housenumber, "52/B");
+    assert_eq!(lint.reason, RelationLintReason::CreatedInOsm);
+}
+
+/// Tests Relation::get_lints(), the out-of-range case.
+#[test]
+fn test_relation_get_lints_out_of_range() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let yamls_cache = serde_json::json!({
         "relations.yaml": {
             "gh3073": {
                 "refcounty": "0",
@@ -1782,6 +2343,76 @@ fn test_relation_get_missing_housenumbers_letter_suffix() {
     assert_eq!(housenumber_range_names, expected);
 }
 
+/// Tests Relation::get_missing_housenumbers(): a matching conscription number covers the reference.
+#[test]
+fn test_relation_get_missing_housenumbers_conscriptionnumber() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+                "osmrelation": 42,
+            },
+        },
+        "relation-myrelation.yaml": {
+            "conscriptionnumber": true,
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Fő utca', '1', '');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Fő utca', '3', '');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Fő utca', '5', '');
+             insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('myrelation', '1', 'Fő utca', 'residential', '', '', '', 'way');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '2', 'Fő utca', '', '', '', '', '3', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '3', 'Fő utca', '42', '', '', '', '5', '', '', '', '', '', 'way');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '4', 'Fő utca', '44', '', '', '', '7', '', '', '', '', '', 'way');
+             insert into mtimes (page, last_modified) values ('housenumbers/myrelation', '0');",
+        )
+        .unwrap();
+    }
+    let mut relations = Relations::new(&ctx).unwrap();
+    let mut relation = relations.get_relation("myrelation").unwrap();
+
+    let missing_housenumbers = relation.get_missing_housenumbers().unwrap();
+
+    // 3 and 5 are covered by conscription numbers.
+    let ongoing_streets = missing_housenumbers.ongoing_streets;
+    assert_eq!(ongoing_streets.len(), 1);
+    let numbers: Vec<_> = ongoing_streets[0]
+        .house_numbers
+        .iter()
+        .map(|i| i.get_number())
+        .collect();
+    assert_eq!(numbers, ["1"]);
+    let done_streets = missing_housenumbers.done_streets;
+    let numbers: Vec<_> = done_streets[0]
+        .house_numbers
+        .iter()
+        .map(|i| i.get_number())
+        .collect();
+    assert_eq!(numbers, ["3", "5"]);
+    // The way has 5 as a conscription number, which is in the reference, but 42 as a street
+    // number, which is not.
+    let lints = relation.get_lints();
+    assert_eq!(lints.len(), 1);
+    let lint = lints[0].clone();
+    assert_eq!(lint.source, RelationLintSource::Conscriptionnumber);
+    assert_eq!(lint.housenumber, "42 (5)");
+    assert_eq!(lint.reason, RelationLintReason::ConscriptionnumberMismatch);
+    assert_eq!(lint.id, 3);
+    assert_eq!(lint.object_type, "way");
+}
+
 /// Tests Relation::get_missing_housenumbers(): how 'invalid' interacts with normalization.
 #[test]
 fn test_relation_get_missing_housenumbers_letter_suffix_invalid() {
@@ -1970,6 +2601,410 @@ fn test_relation_get_missing_housenumbers_letter_suffix_normalize() {
     assert_eq!(housenumber_range_names, expected);
 }
 
+/// Tests Relation::get_missing_housenumbers(): addr:interpolation ways cover reference house
+/// numbers.
+#[test]
+fn test_relation_get_missing_housenumbers_interpolation() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+                "osmrelation": 42,
+            },
+        },
+        "relation-myrelation.yaml": {
+            "filters": {
+                "Fő utca": {
+                    "ranges": [
+                        {"start": "1", "end": "5"},
+                    ],
+                },
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Fő utca', '1', '');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Fő utca', '3', '');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Fő utca', '5', '');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Fő utca', '7', '');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '1', 'Fő utca', '1', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_interpolations (relation, osm_id, street, interpolation, start_number, end_number) values ('myrelation', '2', 'Fő utca', 'odd', '1', '7');
+             insert into mtimes (page, last_modified) values ('housenumbers/myrelation', '0');",
+        )
+        .unwrap();
+    }
+    let mut relations = Relations::new(&ctx).unwrap();
+    let mut relation = relations.get_relation("myrelation").unwrap();
+
+    let missing_housenumbers = relation.get_missing_housenumbers().unwrap();
+
+    // 7 is out of range, so it's filtered out on both sides.
+    assert_eq!(missing_housenumbers.ongoing_streets.is_empty(), true);
+    let done_street = missing_housenumbers.done_streets[0].clone();
+    let actual: Vec<_> = done_street
+        .house_numbers
+        .iter()
+        .map(|i| i.get_number())
+        .collect();
+    assert_eq!(actual, vec!["1", "3", "5"]);
+    let interpolated_street = missing_housenumbers.interpolated_streets[0].clone();
+    let actual: Vec<_> = interpolated_street
+        .house_numbers
+        .iter()
+        .map(|i| i.get_number())
+        .collect();
+    // 1 is mapped as a node, so it's not interpolated.
+    assert_eq!(actual, vec!["3", "5"]);
+}
+
+/// Tests Relation::get_orphan_streets().
+#[test]
+fn test_relation_get_orphan_streets() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+            },
+        },
+        "relation-myrelation.yaml": {
+            "refstreets": {
+                "Régi utca": "Új utca",
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('myrelation', '1', 'Kossuth Lajos utca', '', '', '', '', 'way');
+             insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('myrelation', '2', 'Kossuth Lajos tér', '', '', '', '', 'way');
+             insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('myrelation', '3', 'Fő utca', '', '', '', '', 'way');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '1', 'Kossuth Lajos u.', '1', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '2', 'Kossuth Lajos u.', '3', '', '', '', '', '', '', '', '', '', 'way');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '3', 'Fő utca', '1', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '4', 'Régi utca', '1', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '5', '', '1', '', 'Kert', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '6', 'Zzz', '1', '', '', '', '', '', '', '', '', '', 'node');",
+        )
+        .unwrap();
+    }
+    let mut relations = Relations::new(&ctx).unwrap();
+    let relation = relations.get_relation("myrelation").unwrap();
+
+    let orphan_streets = relation.get_orphan_streets().unwrap();
+
+    // Fő utca is a highway, Régi utca is in refstreets, Kert is an addr:place.
+    assert_eq!(orphan_streets.len(), 2);
+    assert_eq!(orphan_streets[0].street_name, "Kossuth Lajos u.");
+    let objects: Vec<_> = orphan_streets[0]
+        .objects
+        .iter()
+        .map(|i| (i.object_type.as_str(), i.id, i.housenumber.as_str()))
+        .collect();
+    assert_eq!(objects, vec![("node", 1, "1"), ("way", 2, "3")]);
+    assert_eq!(
+        orphan_streets[0].closest_streets,
+        vec![
+            "Kossuth Lajos utca".to_string(),
+            "Kossuth Lajos tér".to_string()
+        ]
+    );
+    assert_eq!(orphan_streets[1].street_name, "Zzz");
+    assert_eq!(orphan_streets[1].closest_streets.is_empty(), true);
+}
+
+/// Tests Relation::get_proposed_filters().
+#[test]
+fn test_relation_get_proposed_filters() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+            },
+        },
+        "relation-myrelation.yaml": {
+            "filters": {
+                "A utca": {
+                    "ranges": [{"start": "1", "end": "9"}],
+                },
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('myrelation', '1', 'A utca', '', '', '', '', 'way');
+             insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('myrelation', '2', 'B utca', '', '', '', '', 'way');
+             insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('myrelation', '3', 'C utca', '', '', '', '', 'way');
+             insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('myrelation', '4', 'D utca', '', '', '', '', 'way');
+             insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('myrelation', '5', 'E utca', '', '', '', '', 'way');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '1', 'A utca', '1', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '2', 'C utca', '3', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '3', 'C utca', '7', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '4', 'D utca', '1', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '5', 'D utca', '2', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '6', 'E utca', '2', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '7', 'E utca', '4', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '8', 'E utca', '10', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '9', 'E utca', '5', '', '', '', '', '', '', '', '', '', 'node');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'A utca', '1', '');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'A utca', '101', '');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'B utca', '1', '');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'C utca', '1', '');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'C utca', '3', '');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'C utca', '4', '');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'C utca', '7', '');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'C utca', '11', '');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'D utca', '1', '');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'D utca', '2', '');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'E utca', '3', '');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'E utca', '4', '');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'E utca', '12', '');",
+        )
+        .unwrap();
+    }
+    let mut relations = Relations::new(&ctx).unwrap();
+    let mut relation = relations.get_relation("myrelation").unwrap();
+
+    let filters = relation.get_proposed_filters().unwrap();
+
+    // A utca already has ranges, B utca has no OSM house numbers, C utca has no even OSM house
+    // numbers, so no observed even range, D utca would filter out nothing.
+    assert_eq!(filters.len(), 1);
+    assert_eq!(filters[0].street_name, "E utca");
+    assert_eq!(filters[0].ranges, vec![(5, 5), (2, 10)]);
+    assert_eq!(
+        filters[0].filtered_out,
+        vec!["3".to_string(), "12".to_string()]
+    );
+    assert_eq!(
+        format_proposed_filters_yaml(&filters),
+        r#"'E utca':
+  # filters out: 3, 12
+  ranges:
+    - {start: '5', end: '5'}
+    - {start: '2', end: '10'}
+"#
+    );
+    assert_eq!(format_proposed_filters_yaml(&[]), "");
+}
+
+/// Tests Relation::get_redundant_invalids().
+#[test]
+fn test_relation_get_redundant_invalids() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+            },
+        },
+        "relation-myrelation.yaml": {
+            "filters": {
+                // Odd and even numbers out of the odd range, an expanded range out of it.
+                "A utca": {
+                    "ranges": [{"start": "1", "end": "9"}],
+                    "invalid": ["1-5", "11", "12", "13-15"],
+                },
+                // No ranges.
+                "B utca": {
+                    "invalid": ["1"],
+                },
+                // In range.
+                "C utca": {
+                    "ranges": [{"start": "1", "end": "9"}],
+                    "invalid": ["5"],
+                },
+                // Not a sane number, not because of the ranges.
+                "D utca": {
+                    "ranges": [{"start": "1", "end": "9"}],
+                    "invalid": ["2000"],
+                },
+                "E utca": {
+                    "ranges": [{"start": "2", "end": "10"}],
+                    "invalid": ["1"],
+                },
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    let mut relations = Relations::new(&ctx).unwrap();
+    let relation = relations.get_relation("myrelation").unwrap();
+
+    let redundant = relation.get_redundant_invalids().unwrap();
+
+    let expected = vec![
+        RedundantInvalids {
+            street_name: "A utca".into(),
+            housenumbers: vec!["11".into(), "12".into(), "13-15".into()],
+        },
+        RedundantInvalids {
+            street_name: "E utca".into(),
+            housenumbers: vec!["1".into()],
+        },
+    ];
+    assert_eq!(redundant, expected);
+}
+
+/// Tests Relation::get_postcode_mismatches().
+#[test]
+fn test_relation_get_postcode_mismatches() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+                "osmrelation": 42,
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        // Fő utca: the reference has a postcode; Kossuth utca: the most common valid OSM postcode
+        // wins; Tanya: no valid postcode at all, so it's not reported.
+        conn.execute_batch(
+            "insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment, postcode) values ('0', '0', 'Fő utca', '1', '', '1111');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '1', 'Fő utca', '1', '1111', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '2', 'Fő utca', '3', '1121', '', '', '', '', '', '', '', '', 'way');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '3', 'Fő utca', '5', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '4', 'Kossuth utca', '1', '1121', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '5', 'Kossuth utca', '3', '1121', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '6', 'Kossuth utca', '5', '1111', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '7', '', '2', '9999', 'Tanya', '', '', '', '', '', '', '', 'node');",
+        )
+        .unwrap();
+    }
+    let mut relations = Relations::new(&ctx).unwrap();
+    let relation = relations.get_relation("myrelation").unwrap();
+
+    let mismatches = relation.get_postcode_mismatches().unwrap();
+
+    let actual: Vec<_> = mismatches
+        .iter()
+        .map(|i| {
+            (
+                i.street_name.as_str(),
+                i.housenumber.as_str(),
+                i.postcode.as_str(),
+                i.expected_postcodes.join(","),
+                i.id,
+            )
+        })
+        .collect();
+    assert_eq!(
+        actual,
+        vec![
+            ("Fő utca", "3", "1121", "1111".to_string(), 2),
+            ("Kossuth utca", "5", "1111", "1121".to_string(), 6),
+        ]
+    );
+    let query = make_turbo_query_for_postcode_mismatches(&mismatches);
+    assert_eq!(query.contains("way(2);\nnode(6);\n"), true);
+}
+
+/// Tests Relation::get_missing_housenumbers(): letter ranges are expanded on both sides.
+#[test]
+fn test_relation_get_missing_housenumbers_letter_range() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+                "osmrelation": 42,
+            },
+        },
+        "relation-myrelation.yaml": {
+            "housenumber-letters": true,
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Fő utca', '12/A-12/D', '');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Fő utca', '14/B-14/A', '');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '1', 'Fő utca', '12a-b', '', '', '', '', '', '', '', '', '', 'way');
+             insert into mtimes (page, last_modified) values ('housenumbers/myrelation', '0');",
+        )
+        .unwrap();
+    }
+    let mut relations = Relations::new(&ctx).unwrap();
+    let mut relation = relations.get_relation("myrelation").unwrap();
+
+    let missing_housenumbers = relation.get_missing_housenumbers().unwrap();
+
+    let ongoing_street = missing_housenumbers.ongoing_streets[0].clone();
+    let actual: Vec<_> = ongoing_street
+        .house_numbers
+        .iter()
+        .map(|i| i.get_number())
+        .collect();
+    // 12/A and 12/B is in OSM, 14 is from the malformed range.
+    assert_eq!(actual, vec!["12/C", "12/D", "14"]);
+    let done_street = missing_housenumbers.done_streets[0].clone();
+    let actual: Vec<_> = done_street
+        .house_numbers
+        .iter()
+        .map(|i| i.get_number())
+        .collect();
+    assert_eq!(actual, vec!["12/A", "12/B"]);
+    let lints = relation.get_lints();
+    assert_eq!(lints.len(), 1);
+    assert_eq!(lints[0].source, RelationLintSource::LetterRange);
+    assert_eq!(lints[0].housenumber, "14/B-14/A");
+    assert_eq!(lints[0].id, 0);
+}
+
 /// Tests Relation::get_missing_housenumbers(): '42/A*' and '42/a' matches.
 #[test]
 fn test_relation_get_missing_housenumbers_letter_suffix_source_suffix() {
@@ -2178,6 +3213,60 @@ fn test_relation_get_additional_streets() {
     assert_eq!(osm_street_blacklist, ["Second Only In OSM utca"]);
 }
 
+/// Tests Relation::get_refstreet_suggestions().
+#[test]
+fn test_relation_get_refstreet_suggestions() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "osmrelation": 42,
+                "refcounty": "01",
+                "refsettlement": "011",
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into ref_streets (county_code, settlement_code, street) values ('01', '011', 'Rézsü utca');
+             insert into ref_streets (county_code, settlement_code, street) values ('01', '011', 'Petőfi Sándor körút');
+             insert into ref_streets (county_code, settlement_code, street) values ('01', '011', 'Kossuth tér');
+             insert into ref_streets (county_code, settlement_code, street) values ('01', '011', 'Common utca');
+             insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('myrelation', '1', 'Rézsű utca', '', '', '', '', 'way');
+             insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('myrelation', '5', 'Rezsu u.', '', '', '', '', 'way');
+             insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('myrelation', '2', 'Petőfi Sándor krt.', '', '', '', '', 'way');
+             insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('myrelation', '3', 'Deák Ferenc tér', '', '', '', '', 'way');
+             insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('myrelation', '4', 'Common utca', '', '', '', '', 'way');",
+        )
+        .unwrap();
+    }
+    let mut relations = Relations::new(&ctx).unwrap();
+    let relation = relations.get_relation("myrelation").unwrap();
+
+    let ret = relation.get_refstreet_suggestions().unwrap();
+
+    // Accents are folded, abbreviations are expanded; 'Deák Ferenc tér' and 'Kossuth tér' are too
+    // different. 'Rezsu u.' would be a match, but 'Rézsű utca' is a better one.
+    assert_eq!(
+        ret,
+        vec![
+            (
+                "Petőfi Sándor krt.".to_string(),
+                "Petőfi Sándor körút".to_string()
+            ),
+            ("Rézsű utca".to_string(), "Rézsü utca".to_string()),
+        ]
+    );
+}
+
 /// Tests Relation::get_additional_streets(): when the osm-street-filters key is missing.
 #[test]
 fn test_relation_get_additional_streets_no_osm_street_filters() {
@@ -3470,6 +4559,7 @@ fn test_relation_list_ord_source() {
             reason,
             id,
             object_type,
+            other_street: String::new(),
         }
     };
     let lint2 = {
@@ -3488,6 +4578,7 @@ fn test_relation_list_ord_source() {
             reason,
             id,
             object_type,
+            other_street: String::new(),
         }
     };
     assert_eq!(lint1.cmp(&lint2), std::cmp::Ordering::Less);
@@ -3516,6 +4607,7 @@ fn test_relation_list_ord_reason() {
             reason,
             id,
             object_type,
+            other_street: String::new(),
         }
     };
     let lint2 = {
@@ -3534,6 +4626,7 @@ fn test_relation_list_ord_reason() {
             reason,
             id,
             object_type,
+            other_street: String::new(),
         }
     };
     assert_eq!(lint1.cmp(&lint2), std::cmp::Ordering::Less);
@@ -3553,6 +4646,7 @@ fn test_normalizer_contains_osm_housenumber_none() {
     let mut lints: Vec<RelationLint> = Vec::new();
     assert!(!normalizer_contains(
         number,
+        /*letter=*/ None,
         &normalizer,
         relation_name,
         street_name,
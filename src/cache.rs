@@ -12,6 +12,7 @@
 
 use crate::areas;
 use crate::context;
+use crate::osm_change;
 use crate::stats;
 use anyhow::Context as _;
 
@@ -56,9 +57,13 @@ fn is_missing_housenumbers_json_cached(relation: &mut areas::Relation<'_>) -> an
     let datadir = relation.get_ctx().get_abspath("data");
     let relation_path = format!("{}/relation-{}.yaml", datadir, relation.get_name());
     let dependencies = vec![relation_path];
+    let streets = format!("streets/{}", relation.get_name());
+    let housenumbers = format!("housenumbers/{}", relation.get_name());
     let sql_dependencies = vec![
-        format!("streets/{}", relation.get_name()),
-        format!("housenumbers/{}", relation.get_name()),
+        osm_change::get_dirty_page(&streets),
+        osm_change::get_dirty_page(&housenumbers),
+        streets,
+        housenumbers,
     ];
     is_sql_cache_current(
         relation.get_ctx(),
@@ -72,17 +77,24 @@ fn is_missing_housenumbers_json_cached(relation: &mut areas::Relation<'_>) -> an
 /// Gets the cached json of the missing housenumbers for a relation.
 pub fn get_missing_housenumbers_json(relation: &mut areas::Relation<'_>) -> anyhow::Result<String> {
     let table = "missing_housenumbers_cache";
-    let output: String;
     if is_missing_housenumbers_json_cached(relation)
         .context("is_missing_housenumbers_json_cached() failed")?
     {
         let ctx = relation.get_ctx();
-        output = stats::get_sql_json(ctx, table, &relation.get_name())?;
-        return Ok(output);
+        return stats::get_sql_json(ctx, table, &relation.get_name());
     }
 
+    update_missing_housenumbers_json(relation)
+}
+
+/// Updates the cached json of the missing housenumbers and the lints for a relation, even if the
+/// cache is up to date.
+pub fn update_missing_housenumbers_json(
+    relation: &mut areas::Relation<'_>,
+) -> anyhow::Result<String> {
+    let table = "missing_housenumbers_cache";
     let missing_housenumbers = relation.get_missing_housenumbers()?;
-    output = serde_json::to_string(&missing_housenumbers)?;
+    let output = serde_json::to_string(&missing_housenumbers)?;
 
     let ctx = relation.get_ctx();
     stats::set_sql_json(ctx, table, &relation.get_name(), &output)?;
@@ -101,9 +113,13 @@ fn is_additional_housenumbers_json_cached(
     let datadir = relation.get_ctx().get_abspath("data");
     let relation_path = format!("{}/relation-{}.yaml", datadir, relation.get_name());
     let dependencies = vec![relation_path];
+    let streets = format!("streets/{}", relation.get_name());
+    let housenumbers = format!("housenumbers/{}", relation.get_name());
     let sql_dependencies = vec![
-        format!("streets/{}", relation.get_name()),
-        format!("housenumbers/{}", relation.get_name()),
+        osm_change::get_dirty_page(&streets),
+        osm_change::get_dirty_page(&housenumbers),
+        streets,
+        housenumbers,
     ];
     is_sql_cache_current(
         relation.get_ctx(),
@@ -118,15 +134,22 @@ pub fn get_additional_housenumbers_json(
     relation: &mut areas::Relation<'_>,
 ) -> anyhow::Result<String> {
     let table = "additional_housenumbers_cache";
-    let output: String;
     if is_additional_housenumbers_json_cached(relation)? {
         let ctx = relation.get_ctx();
-        output = stats::get_sql_json(ctx, table, &relation.get_name())?;
-        return Ok(output);
+        return stats::get_sql_json(ctx, table, &relation.get_name());
     }
 
+    update_additional_housenumbers_json(relation)
+}
+
+/// Updates the cached json of the additional housenumbers for a relation, even if the cache is up
+/// to date.
+pub fn update_additional_housenumbers_json(
+    relation: &mut areas::Relation<'_>,
+) -> anyhow::Result<String> {
+    let table = "additional_housenumbers_cache";
     let additional_housenumbers = relation.get_additional_housenumbers()?;
-    output = serde_json::to_string(&additional_housenumbers)?;
+    let output = serde_json::to_string(&additional_housenumbers)?;
 
     stats::set_sql_json(relation.get_ctx(), table, &relation.get_name(), &output)?;
     let table = format!("additional-housenumbers-cache/{}", relation.get_name());
@@ -22,6 +22,7 @@ use std::path::Path;
 use std::rc::Rc;
 use std::time::Duration;
 
+use crate::country;
 use crate::sql;
 
 /// File system interface.
@@ -78,6 +79,16 @@ pub trait Database {
 
 pub use system::StdDatabase;
 
+/// A request for Network::urlopen_many().
+pub struct NetworkRequest {
+    /// The request URL.
+    pub url: String,
+    /// Empty data means HTTP GET, otherwise it means a HTTP POST.
+    pub data: String,
+    /// The request headers.
+    pub headers: HashMap<String, String>,
+}
+
 /// Network interface.
 pub trait Network {
     /// Opens an URL. Empty data means HTTP GET, otherwise it means a HTTP POST.
@@ -87,6 +98,23 @@ pub trait Network {
         data: &str,
         headers: &HashMap<String, String>,
     ) -> anyhow::Result<String>;
+
+    /// Opens multiple URLs, possibly concurrently. The results are in the order of the requests.
+    fn urlopen_many(&self, requests: &[NetworkRequest]) -> Vec<anyhow::Result<String>> {
+        requests
+            .iter()
+            .map(|i| self.urlopen(&i.url, &i.data, &i.headers))
+            .collect()
+    }
+
+    /// Sends a mail via a plain SMTP server, e.g. localhost:25. The message contains the headers.
+    fn send_mail(
+        &self,
+        server: &str,
+        from: &str,
+        to: &[String],
+        message: &str,
+    ) -> anyhow::Result<()>;
 }
 
 pub use system::StdNetwork;
@@ -146,6 +174,15 @@ pub struct WsgiConfig {
     tcp_port: Option<String>,
     overpass_uri: Option<String>,
     cron_update_inactive: Option<String>,
+    country: Option<String>,
+    country_config: Option<String>,
+    overpass_max_parallel: Option<String>,
+    notify_webhook_url: Option<String>,
+    notify_smtp_server: Option<String>,
+    notify_smtp_from: Option<String>,
+    notify_smtp_to: Option<String>,
+    notify_interval: Option<String>,
+    stats_retention_days: Option<String>,
 }
 
 /// Configuration file reader.
@@ -242,12 +279,69 @@ impl Ini {
         let value = self.get_with_fallback(&self.config.wsgi.cron_update_inactive, "False");
         value == "True"
     }
+
+    /// Gets the maximum number of concurrent overpass queries, the server may allow less.
+    pub fn get_overpass_max_parallel(&self) -> anyhow::Result<usize> {
+        Ok(self
+            .get_with_fallback(&self.config.wsgi.overpass_max_parallel, "2")
+            .parse::<usize>()?)
+    }
+
+    /// Gets the name of the country profile, which describes the reference data.
+    pub fn get_country(&self) -> String {
+        self.get_with_fallback(&self.config.wsgi.country, "hu")
+    }
+
+    /// Gets the abs path of the yaml file which configures the generic country profile, if any.
+    pub fn get_country_config_path(&self) -> Option<String> {
+        let relpath = self.config.wsgi.country_config.as_ref()?;
+        Some(format!("{}/{}", self.root, relpath))
+    }
+
+    /// Gets the URL where failure notifications are posted as JSON, empty if disabled.
+    pub fn get_notify_webhook_url(&self) -> String {
+        self.get_with_fallback(&self.config.wsgi.notify_webhook_url, "")
+    }
+
+    /// Gets the host:port of the SMTP server which sends failure notifications.
+    pub fn get_notify_smtp_server(&self) -> String {
+        self.get_with_fallback(&self.config.wsgi.notify_smtp_server, "localhost:25")
+    }
+
+    /// Gets the sender address of failure notification mails.
+    pub fn get_notify_smtp_from(&self) -> String {
+        self.get_with_fallback(&self.config.wsgi.notify_smtp_from, "osm-gimmisn@localhost")
+    }
+
+    /// Gets the recipients of failure notification mails, empty if disabled.
+    pub fn get_notify_smtp_to(&self) -> Vec<String> {
+        self.get_with_fallback(&self.config.wsgi.notify_smtp_to, "")
+            .split_whitespace()
+            .map(|i| i.to_string())
+            .collect()
+    }
+
+    /// Gets the minimum number of minutes between two notifications of the same kind.
+    pub fn get_notify_interval(&self) -> anyhow::Result<i64> {
+        Ok(self
+            .get_with_fallback(&self.config.wsgi.notify_interval, "60")
+            .parse::<i64>()?)
+    }
+
+    /// Gets the number of days the daily stats are kept for, older ones are downsampled to monthly
+    /// snapshots. 0 means the daily stats are kept forever.
+    pub fn get_stats_retention_days(&self) -> anyhow::Result<i64> {
+        Ok(self
+            .get_with_fallback(&self.config.wsgi.stats_retention_days, "0")
+            .parse::<i64>()?)
+    }
 }
 
 /// Context owns global state which is set up once and then read everywhere.
 pub struct Context {
     root: String,
     ini: Rc<RefCell<Ini>>,
+    profile: Rc<RefCell<Box<dyn country::CountryProfile>>>,
     network: Rc<dyn Network>,
     time: Rc<dyn Time>,
     subprocess: Rc<dyn Subprocess>,
@@ -271,12 +365,15 @@ impl Context {
         let file_system: Rc<dyn FileSystem> = Rc::new(StdFileSystem {});
         let database: Rc<dyn Database> = Rc::new(StdDatabase {});
         let path = format!("{root}/workdir/wsgi.ini");
-        let ini = Rc::new(RefCell::new(Ini::new(&file_system, &path, &root)?));
+        let ini = Ini::new(&file_system, &path, &root)?;
+        let profile = Rc::new(RefCell::new(country::make_profile(&file_system, &ini)?));
+        let ini = Rc::new(RefCell::new(ini));
         let connection = OnceCell::new();
         let shutdown = Rc::new(RefCell::new(false));
         Ok(Context {
             root,
             ini,
+            profile,
             network,
             time,
             subprocess,
@@ -298,12 +395,19 @@ impl Context {
         self.ini.borrow()
     }
 
-    /// Re-reads the ini file.
+    /// Re-reads the ini file, and the country profile it selects.
     pub fn update_ini(&self) -> anyhow::Result<()> {
         self.ini.borrow_mut().update(
             &self.file_system,
             &format!("{}/workdir/wsgi.ini", self.root),
-        )
+        )?;
+        *self.profile.borrow_mut() = country::make_profile(&self.file_system, &self.ini.borrow())?;
+        Ok(())
+    }
+
+    /// Gets the country profile, selected in the ini file.
+    pub fn get_profile(&self) -> Ref<'_, dyn country::CountryProfile> {
+        Ref::map(self.profile.borrow(), |i| i.as_ref())
     }
 
     /// Gets the network implementation.
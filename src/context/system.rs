@@ -88,50 +88,135 @@ impl FileSystem for StdFileSystem {
     }
 }
 
-/// Network implementation, backed by a real HTTP library.
-pub struct StdNetwork {}
-
-// Real network is intentionally mocked.
-impl Network for StdNetwork {
-    fn urlopen(
-        &self,
-        url: &str,
-        data: &str,
-        headers: &HashMap<String, String>,
-    ) -> anyhow::Result<String> {
-        if !data.is_empty() {
-            let mut builder = isahc::Request::post(url);
-            for (key, value) in headers {
-                builder = builder.header(key, value);
-            }
-            let mut response = builder
-                .redirect_policy(isahc::config::RedirectPolicy::Limit(1))
-                .timeout(Duration::from_secs(425))
-                .body(data)?
-                .send()?;
-            let status = response.status();
-            if !status.is_success() {
-                return Err(anyhow::anyhow!("status is not success: {status}"));
-            }
-            let ret = response.text()?;
-            return Ok(ret);
-        }
-
-        let mut builder = isahc::Request::get(url);
+/// Opens an URL using a real HTTP library. Empty data means HTTP GET, otherwise it means a HTTP
+/// POST.
+fn std_urlopen(url: &str, data: &str, headers: &HashMap<String, String>) -> anyhow::Result<String> {
+    if !data.is_empty() {
+        let mut builder = isahc::Request::post(url);
         for (key, value) in headers {
             builder = builder.header(key, value);
         }
         let mut response = builder
             .redirect_policy(isahc::config::RedirectPolicy::Limit(1))
             .timeout(Duration::from_secs(425))
-            .body(())?
+            .body(data)?
             .send()?;
         let status = response.status();
         if !status.is_success() {
             return Err(anyhow::anyhow!("status is not success: {status}"));
         }
         let ret = response.text()?;
-        Ok(ret)
+        return Ok(ret);
+    }
+
+    let mut builder = isahc::Request::get(url);
+    for (key, value) in headers {
+        builder = builder.header(key, value);
+    }
+    let mut response = builder
+        .redirect_policy(isahc::config::RedirectPolicy::Limit(1))
+        .timeout(Duration::from_secs(425))
+        .body(())?
+        .send()?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(anyhow::anyhow!("status is not success: {status}"));
+    }
+    let ret = response.text()?;
+    Ok(ret)
+}
+
+/// Reads a reply of an SMTP server, which may span multiple lines, and fails on error replies.
+fn smtp_read_reply(reader: &mut dyn std::io::BufRead) -> anyhow::Result<()> {
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(anyhow::anyhow!("unexpected end of SMTP reply"));
+        }
+        // Example line: "250-PIPELINING", '-' means more lines follow.
+        if line.get(3..4) == Some("-") {
+            continue;
+        }
+        let code: u16 = line.get(..3).context("short SMTP reply")?.parse()?;
+        if code >= 400 {
+            return Err(anyhow::anyhow!("SMTP error: {}", line.trim_end()));
+        }
+        return Ok(());
+    }
+}
+
+/// Sends a mail using a minimal SMTP client, without authentication or TLS, so this is meant to
+/// talk to a local mail server.
+fn std_send_mail(server: &str, from: &str, to: &[String], message: &str) -> anyhow::Result<()> {
+    let stream = std::net::TcpStream::connect(server)?;
+    stream.set_read_timeout(Some(Duration::from_secs(60)))?;
+    stream.set_write_timeout(Some(Duration::from_secs(60)))?;
+    let mut reader = std::io::BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    smtp_read_reply(&mut reader)?;
+
+    let mut commands = vec!["HELO localhost".to_string(), format!("MAIL FROM:<{from}>")];
+    for recipient in to {
+        commands.push(format!("RCPT TO:<{recipient}>"));
+    }
+    commands.push("DATA".to_string());
+    // Escape lines starting with a dot, then end the data with a single dot.
+    let mut data = String::new();
+    for line in message.lines() {
+        if line.starts_with('.') {
+            data.push('.');
+        }
+        data.push_str(line);
+        data.push_str("\r\n");
+    }
+    data.push('.');
+    commands.push(data);
+    commands.push("QUIT".to_string());
+    for command in commands {
+        writer.write_all(format!("{command}\r\n").as_bytes())?;
+        smtp_read_reply(&mut reader)?;
+    }
+    Ok(())
+}
+
+/// Network implementation, backed by a real HTTP library.
+pub struct StdNetwork {}
+
+// Real network is intentionally mocked.
+impl Network for StdNetwork {
+    fn urlopen(
+        &self,
+        url: &str,
+        data: &str,
+        headers: &HashMap<String, String>,
+    ) -> anyhow::Result<String> {
+        std_urlopen(url, data, headers)
+    }
+
+    fn urlopen_many(&self, requests: &[NetworkRequest]) -> Vec<anyhow::Result<String>> {
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = requests
+                .iter()
+                .map(|i| scope.spawn(|| std_urlopen(&i.url, &i.data, &i.headers)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|i| match i.join() {
+                    Ok(value) => value,
+                    Err(_) => Err(anyhow::anyhow!("urlopen thread panicked")),
+                })
+                .collect()
+        })
+    }
+
+    fn send_mail(
+        &self,
+        server: &str,
+        from: &str,
+        to: &[String],
+        message: &str,
+    ) -> anyhow::Result<()> {
+        std_send_mail(server, from, to, message)
     }
 }
 
@@ -323,6 +323,18 @@ impl Network for TestNetwork {
         locked_routes.remove(remove.unwrap());
         Ok(ret)
     }
+
+    /// Sends a mail: routed like an URL, e.g. smtp://localhost:25, where the data is the message.
+    fn send_mail(
+        &self,
+        server: &str,
+        _from: &str,
+        _to: &[String],
+        message: &str,
+    ) -> anyhow::Result<()> {
+        self.urlopen(&format!("smtp://{server}"), message, &HashMap::new())?;
+        Ok(())
+    }
 }
 
 /// Unit implementation, which intentionally fails.
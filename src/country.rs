@@ -0,0 +1,281 @@
+/*
+ * Copyright 2025 Miklos Vajna
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+#![deny(warnings)]
+#![warn(clippy::all)]
+#![warn(missing_docs)]
+
+//! The country module contains the country-specific knowledge about the reference data.
+
+use crate::context;
+use anyhow::Context as _;
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+lazy_static! {
+    static ref NULL_END: regex::Regex = regex::Regex::new(r" null$").unwrap();
+}
+
+/// Column names of the reference TSV files.
+#[derive(Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RefColumns {
+    /// County code.
+    pub county: String,
+    /// Settlement code.
+    pub settlement: String,
+    /// Street name.
+    pub street: String,
+    /// House number.
+    pub housenumber: String,
+    /// Comment, optional.
+    pub comment: String,
+    /// Postcode, optional.
+    pub postcode: String,
+}
+
+impl Default for RefColumns {
+    /// The Hungarian layout.
+    fn default() -> Self {
+        RefColumns {
+            county: "COUNTY_CODE".into(),
+            settlement: "SETTLEMENT_CODE".into(),
+            street: "STREET".into(),
+            housenumber: "HOUSENUMBER".into(),
+            comment: "COMMENT".into(),
+            postcode: "POSTCODE".into(),
+        }
+    }
+}
+
+/// Hierarchy levels: the larger and the smaller container of streets, with the yaml files providing
+/// their UI names.
+#[derive(Clone, serde::Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct HierarchyLevels {
+    /// Name of the yaml file mapping refcounty codes to names.
+    pub county_names: String,
+    /// Name of the yaml file mapping refcounty -> refsettlement codes to names.
+    pub settlement_names: String,
+}
+
+impl Default for HierarchyLevels {
+    /// The Hungarian layout.
+    fn default() -> Self {
+        HierarchyLevels {
+            county_names: "refcounty-names.yaml".into(),
+            settlement_names: "refsettlement-names.yaml".into(),
+        }
+    }
+}
+
+/// Describes how the reference data of a country should be interpreted.
+pub trait CountryProfile {
+    /// Gets the column mapping of the reference TSV files.
+    fn get_columns(&self) -> RefColumns;
+
+    /// Gets the hierarchy levels of the reference data.
+    fn get_hierarchy_levels(&self) -> HierarchyLevels;
+
+    /// Determines the comment to store for a reference house number, given the file name and the
+    /// comment column.
+    fn get_comment(&self, file_name: &str, comment: Option<&str>) -> String;
+
+    /// Determines what suffix should a reference house number use, given its stored comment.
+    fn get_ref_suffix(&self, comment: &str) -> &'static str;
+
+    /// Turns a stored comment into a comment that can be shown to the user.
+    fn get_display_comment(&self, comment: &str) -> String;
+
+    /// Normalizes the street name of a reference street row.
+    fn normalize_street(&self, street: &str) -> String;
+
+    /// Gets abbreviation -> full form pairs of street types, without accents and in lowercase.
+    fn get_street_type_abbreviations(&self) -> Vec<(String, String)>;
+}
+
+/// The Hungarian profile: the default.
+pub struct HungaryProfile {}
+
+impl CountryProfile for HungaryProfile {
+    fn get_columns(&self) -> RefColumns {
+        RefColumns::default()
+    }
+
+    fn get_hierarchy_levels(&self) -> HierarchyLevels {
+        HierarchyLevels::default()
+    }
+
+    fn get_comment(&self, file_name: &str, comment: Option<&str>) -> String {
+        if file_name.starts_with("hazszamok_kieg") {
+            // All rows of the supplementary file are commercial, even if there is no comment.
+            return comment.unwrap_or(" ").into();
+        }
+
+        // Assume "hazszamok_".
+        "".into()
+    }
+
+    fn get_ref_suffix(&self, comment: &str) -> &'static str {
+        if comment.is_empty() { "" } else { "*" }
+    }
+
+    fn get_display_comment(&self, comment: &str) -> String {
+        if comment == " " {
+            return "".into();
+        }
+
+        comment.into()
+    }
+
+    fn normalize_street(&self, street: &str) -> String {
+        NULL_END.replace(street, "").to_string()
+    }
+
+    fn get_street_type_abbreviations(&self) -> Vec<(String, String)> {
+        [
+            ("u.", "utca"),
+            ("u", "utca"),
+            ("ut.", "ut"),
+            ("krt.", "korut"),
+            ("krt", "korut"),
+            ("sgt.", "sugarut"),
+            ("stny.", "setany"),
+            ("rkp.", "rakpart"),
+            ("ltp.", "lakotelep"),
+            ("hrsz.", "helyrajzi szam"),
+        ]
+        .iter()
+        .map(|(abbreviation, full)| (abbreviation.to_string(), full.to_string()))
+        .collect()
+    }
+}
+
+/// The configuration of the generic profile, see the `country_config` key in wsgi.ini. All keys are
+/// optional.
+#[derive(serde::Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct GenericConfig {
+    /// Column names of the reference TSV files, defaults to the Hungarian ones.
+    pub columns: RefColumns,
+    /// Hierarchy levels, defaults to the Hungarian ones.
+    pub hierarchy: HierarchyLevels,
+    /// Abbreviation -> full form map of street types, defaults to a few English ones.
+    pub street_types: HashMap<String, String>,
+}
+
+impl Default for GenericConfig {
+    fn default() -> Self {
+        let street_types = [
+            ("st.", "street"),
+            ("st", "street"),
+            ("rd.", "road"),
+            ("rd", "road"),
+            ("ave.", "avenue"),
+            ("ave", "avenue"),
+            ("sq.", "square"),
+        ]
+        .iter()
+        .map(|(abbreviation, full)| (abbreviation.to_string(), full.to_string()))
+        .collect();
+        GenericConfig {
+            columns: RefColumns::default(),
+            hierarchy: HierarchyLevels::default(),
+            street_types,
+        }
+    }
+}
+
+/// A profile for reference data which has no naming conventions for the files: a row is commercial
+/// if it has a non-empty comment. The columns, hierarchy levels and street types are configurable.
+#[derive(Default)]
+pub struct GenericProfile {
+    config: GenericConfig,
+}
+
+impl GenericProfile {
+    /// Creates a generic profile, reading the configuration from the yaml file set in wsgi.ini.
+    pub fn new(
+        file_system: &Rc<dyn context::FileSystem>,
+        ini: &context::Ini,
+    ) -> anyhow::Result<Self> {
+        let path = match ini.get_country_config_path() {
+            Some(value) => value,
+            None => return Ok(GenericProfile::default()),
+        };
+        let data = file_system.read_to_string(&path)?;
+        let config = serde_yaml::from_str(&data).context(format!("failed to parse {path}"))?;
+        Ok(GenericProfile { config })
+    }
+}
+
+impl CountryProfile for GenericProfile {
+    fn get_columns(&self) -> RefColumns {
+        self.config.columns.clone()
+    }
+
+    fn get_hierarchy_levels(&self) -> HierarchyLevels {
+        self.config.hierarchy.clone()
+    }
+
+    fn get_comment(&self, _file_name: &str, comment: Option<&str>) -> String {
+        comment.unwrap_or("").trim().into()
+    }
+
+    fn get_ref_suffix(&self, comment: &str) -> &'static str {
+        if comment.is_empty() { "" } else { "*" }
+    }
+
+    fn get_display_comment(&self, comment: &str) -> String {
+        comment.into()
+    }
+
+    fn normalize_street(&self, street: &str) -> String {
+        street.trim().into()
+    }
+
+    fn get_street_type_abbreviations(&self) -> Vec<(String, String)> {
+        let mut ret: Vec<(String, String)> = self
+            .config
+            .street_types
+            .iter()
+            .map(|(abbreviation, full)| {
+                (
+                    unidecode::unidecode(abbreviation).to_lowercase(),
+                    unidecode::unidecode(full).to_lowercase(),
+                )
+            })
+            .collect();
+        ret.sort();
+        ret
+    }
+}
+
+/// Looks up a profile by its name.
+pub fn get_profile_by_name(
+    file_system: &Rc<dyn context::FileSystem>,
+    ini: &context::Ini,
+    name: &str,
+) -> anyhow::Result<Box<dyn CountryProfile>> {
+    match name {
+        "hu" => Ok(Box::new(HungaryProfile {})),
+        "generic" => Ok(Box::new(GenericProfile::new(file_system, ini)?)),
+        _ => Err(anyhow::anyhow!("unknown country profile: '{name}'")),
+    }
+}
+
+/// Creates the profile selected in wsgi.ini. Use context::Context::get_profile() instead, which
+/// has this already.
+pub fn make_profile(
+    file_system: &Rc<dyn context::FileSystem>,
+    ini: &context::Ini,
+) -> anyhow::Result<Box<dyn CountryProfile>> {
+    get_profile_by_name(file_system, ini, &ini.get_country())
+}
+
+#[cfg(test)]
+mod tests;
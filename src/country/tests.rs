@@ -0,0 +1,230 @@
+/*
+ * Copyright 2025 Miklos Vajna
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+#![deny(warnings)]
+#![warn(clippy::all)]
+#![warn(missing_docs)]
+
+//! Tests for the country module.
+
+use super::*;
+use crate::util;
+use std::io::Write as _;
+
+/// Tests make_profile(): the default is the Hungarian profile.
+#[test]
+fn test_make_profile_default() {
+    let ctx = context::tests::make_test_context().unwrap();
+
+    let profile = ctx.get_profile();
+
+    assert_eq!(profile.get_display_comment(" "), "");
+}
+
+/// Tests get_profile_by_name(): an unknown name is an error.
+#[test]
+fn test_get_profile_by_name_unknown() {
+    let ctx = context::tests::make_test_context().unwrap();
+
+    let ret = get_profile_by_name(ctx.get_file_system(), &ctx.get_ini(), "xx");
+
+    assert_eq!(ret.is_err(), true);
+}
+
+/// Tests the comment and suffix semantics of the Hungarian profile.
+#[test]
+fn test_hungary_comments() {
+    let profile = HungaryProfile {};
+
+    assert_eq!(profile.get_comment("hazszamok_20190511.tsv", Some("x")), "");
+    let comment = profile.get_comment("hazszamok_kieg_20190808.tsv", None);
+    assert_eq!(comment, " ");
+    assert_eq!(profile.get_ref_suffix(&comment), "*");
+    assert_eq!(profile.get_display_comment(&comment), "");
+    assert_eq!(profile.get_ref_suffix(""), "");
+    assert_eq!(profile.normalize_street("Tűzkő utca null"), "Tűzkő utca");
+}
+
+/// Tests the comment and suffix semantics of the generic profile.
+#[test]
+fn test_generic_comments() {
+    let profile = GenericProfile::default();
+
+    assert_eq!(profile.get_comment("any.tsv", None), "");
+    let comment = profile.get_comment("any.tsv", Some("shop"));
+    assert_eq!(profile.get_ref_suffix(&comment), "*");
+    assert_eq!(profile.get_display_comment(&comment), "shop");
+    assert_eq!(
+        profile.normalize_street("Tűzkő utca null"),
+        "Tűzkő utca null"
+    );
+    assert_eq!(
+        profile.get_hierarchy_levels().county_names,
+        "refcounty-names.yaml"
+    );
+    assert_eq!(
+        profile
+            .get_street_type_abbreviations()
+            .contains(&("st.".to_string(), "street".to_string())),
+        true
+    );
+}
+
+/// Tests build_reference_index() with the generic profile selected in wsgi.ini.
+#[test]
+fn test_build_reference_index_generic() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let wsgi_ini = context::tests::TestFileSystem::make_file();
+    wsgi_ini
+        .borrow_mut()
+        .write_all(
+            b"[wsgi]\nreference_housenumbers = ''\nreference_street = ''\nreference_citycounts = ''\nreference_zipcounts = ''\ncountry = 'generic'\n",
+        )
+        .unwrap();
+    let files =
+        context::tests::TestFileSystem::make_files(&ctx, &[("workdir/wsgi.ini", &wsgi_ini)]);
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    ctx.update_ini().unwrap();
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.execute_batch("delete from ref_housenumbers").unwrap();
+    }
+    let refpaths = vec![
+        ctx.get_abspath("workdir/refs/hazszamok_20190511.tsv"),
+        ctx.get_abspath("workdir/refs/hazszamok_kieg_20190808.tsv"),
+    ];
+
+    util::build_reference_index(&ctx, &refpaths).unwrap();
+
+    let conn = ctx.get_database_connection().unwrap();
+    let mut stmt = conn
+        .prepare("select comment from ref_housenumbers where comment != ''")
+        .unwrap();
+    let mut rows = stmt.query([]).unwrap();
+    let mut comments: Vec<String> = Vec::new();
+    while let Some(row) = rows.next().unwrap() {
+        comments.push(row.get(0).unwrap());
+    }
+    // Only the row with an explicit comment is commercial.
+    assert_eq!(comments, vec!["comment".to_string()]);
+}
+
+/// Tests build_reference_index() with the generic profile, configured for a non-HU header.
+#[test]
+fn test_build_reference_index_generic_config() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let wsgi_ini = context::tests::TestFileSystem::make_file();
+    wsgi_ini
+        .borrow_mut()
+        .write_all(
+            b"[wsgi]\nreference_housenumbers = ''\nreference_street = ''\nreference_citycounts = ''\nreference_zipcounts = ''\ncountry = 'generic'\ncountry_config = 'data/country-generic.yaml'\n",
+        )
+        .unwrap();
+    let config = context::tests::TestFileSystem::make_file();
+    config
+        .borrow_mut()
+        .write_all(
+            r#"columns:
+  county: REGION
+  settlement: TOWN
+  street: STREET_NAME
+  housenumber: NUMBER
+  comment: NOTE
+hierarchy:
+  county-names: regions.yaml
+  settlement-names: towns.yaml
+street-types:
+  Str.: Straße
+"#
+            .as_bytes(),
+        )
+        .unwrap();
+    let tsv = context::tests::TestFileSystem::make_file();
+    tsv.borrow_mut()
+        .write_all("REGION\tTOWN\tSTREET_NAME\tNUMBER\tNOTE\n09\t162\tMainzer Str.\t1\t\n09\t162\tMainzer Str.\t3\tshop\n".as_bytes())
+        .unwrap();
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[
+            ("workdir/wsgi.ini", &wsgi_ini),
+            ("data/country-generic.yaml", &config),
+            ("workdir/refs/addresses.tsv", &tsv),
+        ],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    ctx.update_ini().unwrap();
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.execute_batch("delete from ref_housenumbers").unwrap();
+    }
+    let refpaths = vec![ctx.get_abspath("workdir/refs/addresses.tsv")];
+
+    util::build_reference_index(&ctx, &refpaths).unwrap();
+
+    let profile = ctx.get_profile();
+    assert_eq!(profile.get_hierarchy_levels().county_names, "regions.yaml");
+    assert_eq!(
+        profile.get_hierarchy_levels().settlement_names,
+        "towns.yaml"
+    );
+    assert_eq!(
+        profile.get_street_type_abbreviations(),
+        vec![("str.".to_string(), "strasse".to_string())]
+    );
+    let conn = ctx.get_database_connection().unwrap();
+    let mut stmt = conn
+        .prepare("select county_code, settlement_code, street, housenumber, comment from ref_housenumbers order by housenumber")
+        .unwrap();
+    let mut rows = stmt.query([]).unwrap();
+    let mut actual: Vec<Vec<String>> = Vec::new();
+    while let Some(row) = rows.next().unwrap() {
+        let mut cells: Vec<String> = Vec::new();
+        for index in 0..5 {
+            cells.push(row.get(index).unwrap());
+        }
+        actual.push(cells);
+    }
+    assert_eq!(
+        actual,
+        vec![
+            vec!["09", "162", "Mainzer Str.", "1", ""],
+            vec!["09", "162", "Mainzer Str.", "3", "shop"],
+        ]
+    );
+}
+
+/// Tests GenericProfile::new(): an unknown key in the configuration is an error.
+#[test]
+fn test_generic_profile_new_unknown_key() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let wsgi_ini = context::tests::TestFileSystem::make_file();
+    wsgi_ini
+        .borrow_mut()
+        .write_all(
+            b"[wsgi]\nreference_housenumbers = ''\nreference_street = ''\nreference_citycounts = ''\nreference_zipcounts = ''\ncountry = 'generic'\ncountry_config = 'data/country-generic.yaml'\n",
+        )
+        .unwrap();
+    let config = context::tests::TestFileSystem::make_file();
+    config
+        .borrow_mut()
+        .write_all(b"columns:\n  city: TOWN\n")
+        .unwrap();
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[
+            ("workdir/wsgi.ini", &wsgi_ini),
+            ("data/country-generic.yaml", &config),
+        ],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+
+    let ret = ctx.update_ini();
+
+    assert!(ret.is_err());
+}
@@ -12,7 +12,10 @@
 
 use crate::area_files;
 use crate::areas;
+use crate::cache;
 use crate::context;
+use crate::notify;
+use crate::osm_change;
 use crate::overpass_query;
 use crate::stats;
 use crate::util;
@@ -30,81 +33,212 @@ use log::{error, info, warn};
 #[cfg(test)]
 use std::{println as info, println as warn, println as error};
 
-/// Update the OSM street list of all relations.
-fn update_osm_streets(
-    ctx: &context::Context,
-    relations: &mut areas::Relations<'_>,
+/// The kind of OSM data which is fetched for a relation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OsmJson {
+    Streets,
+    Housenumbers,
+}
+
+impl OsmJson {
+    /// Gets the page name in the mtimes table, for a relation.
+    fn get_page(&self, relation_name: &str) -> String {
+        match self {
+            OsmJson::Streets => format!("streets/{relation_name}"),
+            OsmJson::Housenumbers => format!("housenumbers/{relation_name}"),
+        }
+    }
+}
+
+/// Gets the pages in the mtimes table which track the changes of the given OSM data of a
+/// relation: the update from overpass and the applied diffs.
+fn get_dependency_pages(relation_name: &str, kinds: &[OsmJson]) -> Vec<String> {
+    let mut pages: Vec<String> = Vec::new();
+    for kind in kinds {
+        let page = kind.get_page(relation_name);
+        pages.push(osm_change::get_dirty_page(&page));
+        pages.push(page);
+    }
+    pages
+}
+
+/// Decides which OSM data of relations gets updated, in what order and until when.
+#[derive(Clone)]
+struct UpdatePolicy {
+    /// Update relations which already have OSM data.
     update: bool,
-) -> anyhow::Result<()> {
-    let active_names = relations.get_active_names();
-    for relation_name in active_names.context("get_active_names() failed")? {
-        let relation = relations.get_relation(&relation_name)?;
-        if !update && stats::has_sql_mtime(ctx, &format!("streets/{relation_name}"))? {
-            continue;
+    /// If set, only update OSM data which is older than this.
+    max_age: Option<time::Duration>,
+    /// If set, don't start new updates after this point in time.
+    deadline: Option<time::OffsetDateTime>,
+    /// Frequently visited relations: these are updated first and at least hourly.
+    frequent: HashSet<String>,
+}
+
+impl UpdatePolicy {
+    /// Creates a policy which updates either all or only the missing OSM data.
+    fn new(update: bool) -> Self {
+        UpdatePolicy {
+            update,
+            max_age: None,
+            deadline: None,
+            frequent: HashSet::new(),
         }
-        info!("update_osm_streets, json: start: {relation_name}");
-        let query = relation.get_osm_streets_json_query()?;
-        let buf = match overpass_query::overpass_query_with_retry(ctx, &query) {
-            Ok(value) => value,
-            Err(err) => {
-                info!("update_osm_streets, json: http error: {err:?}");
-                continue;
-            }
-        };
-        relation
-            .get_files()
-            .write_osm_json_streets(ctx, &buf)
-            .context("write_osm_json_streets() failed")?;
-        info!("update_osm_streets, json: end: {relation_name}");
     }
 
-    Ok(())
+    /// Gets the maximum age of the OSM data of a relation, if there is one.
+    fn get_max_age(&self, relation_name: &str) -> Option<time::Duration> {
+        if self.frequent.contains(relation_name) {
+            return Some(time::Duration::hours(1));
+        }
+
+        self.max_age
+    }
+
+    /// Gets the names of the active relations, frequent ones first.
+    fn get_relation_names(
+        &self,
+        relations: &mut areas::Relations<'_>,
+    ) -> anyhow::Result<Vec<String>> {
+        let mut names = relations
+            .get_active_names()
+            .context("get_active_names() failed")?;
+        // Stable sort: the rest keeps its order.
+        names.sort_by_key(|i| !self.frequent.contains(i));
+        Ok(names)
+    }
+
+    /// Decides if the deadline is reached, so no new work should be started.
+    fn is_past_deadline(&self, ctx: &context::Context) -> bool {
+        match self.deadline {
+            Some(deadline) => ctx.get_time().now() >= deadline,
+            None => false,
+        }
+    }
+
+    /// Decides if existing stats of a relation, last written at `mtime`, have to be recomputed.
+    /// `pages` are the OSM data of the relation the stats depend on, e.g. streets/myrelation.
+    fn should_recompute(
+        &self,
+        ctx: &context::Context,
+        mtime: time::OffsetDateTime,
+        pages: &[String],
+    ) -> anyhow::Result<bool> {
+        if self.max_age.is_none() {
+            return Ok(self.update);
+        }
+
+        // Only some OSM data was updated: recompute if it's newer than the stats. This also
+        // catches up with relations where an earlier run reached its deadline.
+        for page in pages {
+            if stats::get_sql_mtime(ctx, page)? > mtime {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
 }
 
-/// Update the OSM housenumber list of all relations.
-fn update_osm_housenumbers(
+/// Update the OSM street and/or housenumber lists of all relations, frequent ones first, then oldest
+/// first. Returns the pages of the relations where the overpass query failed, e.g.
+/// streets/myrelation.
+fn update_osm_json(
     ctx: &context::Context,
     relations: &mut areas::Relations<'_>,
-    update: bool,
-) -> anyhow::Result<()> {
-    for relation_name in relations.get_active_names()? {
-        let relation = relations.get_relation(&relation_name)?;
-        if !update && stats::has_sql_mtime(ctx, &format!("housenumbers/{relation_name}"))? {
-            continue;
+    kinds: &[OsmJson],
+    policy: &UpdatePolicy,
+) -> anyhow::Result<Vec<String>> {
+    let now = ctx.get_time().now();
+    let mut jobs: Vec<(bool, time::OffsetDateTime, String, OsmJson)> = Vec::new();
+    let active_names = relations.get_active_names();
+    for relation_name in active_names.context("get_active_names() failed")? {
+        let frequent = policy.frequent.contains(&relation_name);
+        for kind in kinds {
+            let page = kind.get_page(&relation_name);
+            if stats::has_sql_mtime(ctx, &page)? {
+                let mtime = stats::get_sql_mtime(ctx, &page)?;
+                let stale = match policy.get_max_age(&relation_name) {
+                    Some(max_age) => now - mtime > max_age,
+                    None => policy.update,
+                };
+                if !stale {
+                    continue;
+                }
+                jobs.push((frequent, mtime, relation_name.to_string(), *kind));
+            } else {
+                jobs.push((
+                    frequent,
+                    time::OffsetDateTime::UNIX_EPOCH,
+                    relation_name.to_string(),
+                    *kind,
+                ));
+            }
         }
-        info!("update_osm_housenumbers, json: start: {relation_name}");
-        let query = relation.get_osm_housenumbers_json_query()?;
-        let buf = match overpass_query::overpass_query_with_retry(ctx, &query) {
+    }
+    // Stable sort: frequent relations come first, then missing data, then the oldest data.
+    jobs.sort_by_key(|(frequent, mtime, _, _)| (!*frequent, *mtime));
+
+    let mut queries: Vec<String> = Vec::new();
+    for (_, _, relation_name, kind) in &jobs {
+        let relation = relations.get_relation(relation_name)?;
+        let query = match kind {
+            OsmJson::Streets => relation.get_osm_streets_json_query()?,
+            OsmJson::Housenumbers => relation.get_osm_housenumbers_json_query()?,
+        };
+        info!("update_osm_json: start: {}", kind.get_page(relation_name));
+        queries.push(query);
+    }
+
+    // Queries run concurrently, but the results are written one by one.
+    let deadline = policy.deadline;
+    let mut failed: Vec<String> = Vec::new();
+    overpass_query::overpass_query_many(ctx, &queries, deadline, &mut |index, result| {
+        let (_, _, relation_name, kind) = &jobs[index];
+        let page = kind.get_page(relation_name);
+        let buf = match result {
             Ok(value) => value,
             Err(err) => {
-                info!("update_osm_housenumbers, json: http error: {err:?}");
-                continue;
+                info!("update_osm_json: http error: {page}: {err:?}");
+                failed.push(page);
+                return Ok(());
             }
         };
-        relation
-            .get_files()
-            .write_osm_json_housenumbers(ctx, &buf)?;
-        info!("update_osm_housenumbers, json: end: {relation_name}");
-    }
-
-    Ok(())
+        let relation = relations.get_relation(relation_name)?;
+        let files = relation.get_files();
+        match kind {
+            OsmJson::Streets => files
+                .write_osm_json_streets(ctx, &buf)
+                .context("write_osm_json_streets() failed")?,
+            OsmJson::Housenumbers => files.write_osm_json_housenumbers(ctx, &buf)?,
+        }
+        info!("update_osm_json: end: {page}");
+        Ok(())
+    })?;
+    Ok(failed)
 }
 
 /// Update the relation's house number coverage stats.
 fn update_missing_housenumbers(
+    ctx: &context::Context,
     relations: &mut areas::Relations<'_>,
-    update: bool,
+    policy: &UpdatePolicy,
 ) -> anyhow::Result<()> {
     info!("update_missing_housenumbers: start");
-    let active_names = relations
-        .get_active_names()
-        .context("get_active_names() failed")?;
-    for relation_name in active_names {
+    for relation_name in policy.get_relation_names(relations)? {
+        if policy.is_past_deadline(ctx) {
+            info!("update_missing_housenumbers: deadline reached");
+            break;
+        }
         let mut relation = relations
             .get_relation(&relation_name)
             .context("get_relation() failed")?;
-        if !update && relation.has_osm_housenumber_coverage()? {
-            continue;
+        if relation.has_osm_housenumber_coverage()? {
+            let mtime = relation.get_osm_housenumber_coverage_mtime()?;
+            let kinds = [OsmJson::Streets, OsmJson::Housenumbers];
+            let pages = get_dependency_pages(&relation_name, &kinds);
+            if !policy.should_recompute(ctx, mtime, &pages)? {
+                continue;
+            }
         }
         let streets = relation.get_config().should_check_missing_streets();
         if streets == "only" {
@@ -120,16 +254,59 @@ fn update_missing_housenumbers(
     Ok(())
 }
 
+/// Update the relation's missing and additional house number caches, which also rewrites the
+/// lints of the relation.
+fn update_caches(
+    ctx: &context::Context,
+    relations: &mut areas::Relations<'_>,
+    policy: &UpdatePolicy,
+) -> anyhow::Result<()> {
+    info!("update_caches: start");
+    // With a max age, only the caches which depend on updated OSM data are outdated.
+    let update = policy.update && policy.max_age.is_none();
+    for relation_name in policy.get_relation_names(relations)? {
+        if policy.is_past_deadline(ctx) {
+            info!("update_caches: deadline reached");
+            break;
+        }
+        let mut relation = relations.get_relation(&relation_name)?;
+        let streets = relation.get_config().should_check_missing_streets();
+        if streets == "only" {
+            continue;
+        }
+
+        if update {
+            cache::update_missing_housenumbers_json(&mut relation)?;
+            cache::update_additional_housenumbers_json(&mut relation)?;
+        } else {
+            cache::get_missing_housenumbers_json(&mut relation)?;
+            cache::get_additional_housenumbers_json(&mut relation)?;
+        }
+    }
+    info!("update_caches: end");
+
+    Ok(())
+}
+
 /// Update the relation's street coverage stats.
 fn update_missing_streets(
+    ctx: &context::Context,
     relations: &mut areas::Relations<'_>,
-    update: bool,
+    policy: &UpdatePolicy,
 ) -> anyhow::Result<()> {
     info!("update_missing_streets: start");
-    for relation_name in relations.get_active_names()? {
+    for relation_name in policy.get_relation_names(relations)? {
+        if policy.is_past_deadline(ctx) {
+            info!("update_missing_streets: deadline reached");
+            break;
+        }
         let relation = relations.get_relation(&relation_name)?;
-        if !update && relation.has_osm_street_coverage()? {
-            continue;
+        if relation.has_osm_street_coverage()? {
+            let mtime = relation.get_osm_street_coverage_mtime()?;
+            let pages = get_dependency_pages(&relation_name, &[OsmJson::Streets]);
+            if !policy.should_recompute(ctx, mtime, &pages)? {
+                continue;
+            }
         }
         let streets = relation.get_config().should_check_missing_streets();
         if streets == "no" {
@@ -147,13 +324,22 @@ fn update_missing_streets(
 fn update_additional_streets(
     ctx: &context::Context,
     relations: &mut areas::Relations<'_>,
-    update: bool,
+    policy: &UpdatePolicy,
 ) -> anyhow::Result<()> {
     info!("update_additional_streets: start");
-    for relation_name in relations.get_active_names()? {
+    for relation_name in policy.get_relation_names(relations)? {
+        if policy.is_past_deadline(ctx) {
+            info!("update_additional_streets: deadline reached");
+            break;
+        }
         let relation = relations.get_relation(&relation_name)?;
-        if !update && stats::has_sql_count(ctx, "additional_streets_counts", &relation_name)? {
-            continue;
+        if stats::has_sql_count(ctx, "additional_streets_counts", &relation_name)? {
+            let mtime = stats::get_sql_mtime(ctx, &format!("additional-streets/{relation_name}"))?;
+            let kinds = [OsmJson::Streets, OsmJson::Housenumbers];
+            let pages = get_dependency_pages(&relation_name, &kinds);
+            if !policy.should_recompute(ctx, mtime, &pages)? {
+                continue;
+            }
         }
         let streets = relation.get_config().should_check_missing_streets();
         if streets == "no" {
@@ -461,6 +647,10 @@ fn clean_osm_data(
             "delete from osm_housenumbers where relation = ?1",
             [relation_name],
         )?;
+        tx.execute(
+            "delete from osm_interpolations where relation = ?1",
+            [relation_name],
+        )?;
     }
     tx.commit()?;
 
@@ -471,27 +661,172 @@ fn clean_osm_data(
     Ok(())
 }
 
+/// Formats a duration as hours, minutes and seconds.
+pub fn format_duration(duration: time::Duration) -> String {
+    let seconds = duration.whole_seconds() % 60;
+    let minutes = duration.whole_minutes() % 60;
+    let hours = duration.whole_hours();
+    format!("{hours}:{minutes:0>2}:{seconds:0>2}")
+}
+
+/// One stage of a cron run.
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct CronStage {
+    /// Name of the stage, e.g. update_stats.
+    pub name: String,
+    /// Duration of the stage.
+    pub seconds: f64,
+}
+
+/// One run of the cron job, stored in the cron_runs table.
+#[derive(serde::Serialize)]
+pub struct CronRun {
+    /// Start of the run.
+    #[serde(with = "time::serde::rfc3339")]
+    pub start: time::OffsetDateTime,
+    /// End of the run.
+    #[serde(with = "time::serde::rfc3339")]
+    pub end: time::OffsetDateTime,
+    /// The sub-task(s) performed.
+    pub mode: String,
+    /// Filters limiting the list of relations, e.g. "refcounty=01".
+    pub filters: String,
+    /// Durations of the stages.
+    pub stages: Vec<CronStage>,
+    /// Pages of the relations where the overpass query failed, e.g. streets/myrelation.
+    pub failed_relations: Vec<String>,
+    /// Peak memory usage, e.g. "123456 kB".
+    pub vm_peak: String,
+    /// The error which stopped the run, empty on success.
+    pub error: String,
+}
+
+impl CronRun {
+    /// Starts recording a new run.
+    fn new(ctx: &context::Context, mode: &str, filters: &str) -> Self {
+        let now = ctx.get_time().now();
+        CronRun {
+            start: now,
+            end: now,
+            mode: mode.to_string(),
+            filters: filters.to_string(),
+            stages: Vec::new(),
+            failed_relations: Vec::new(),
+            vm_peak: String::new(),
+            error: String::new(),
+        }
+    }
+
+    /// Runs one stage, recording its duration.
+    fn run_stage<T>(
+        &mut self,
+        ctx: &context::Context,
+        name: &str,
+        stage: impl FnOnce() -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        let start = ctx.get_time().now();
+        let ret = stage();
+        let seconds = (ctx.get_time().now() - start).as_seconds_f64();
+        let name = name.to_string();
+        self.stages.push(CronStage { name, seconds });
+        ret
+    }
+
+    /// Stores the run in the cron_runs table.
+    fn save(&self, ctx: &context::Context) -> anyhow::Result<()> {
+        let params = [
+            self.start.unix_timestamp_nanos().to_string(),
+            self.end.unix_timestamp_nanos().to_string(),
+            self.mode.to_string(),
+            self.filters.to_string(),
+            serde_json::to_string(&self.stages)?,
+            serde_json::to_string(&self.failed_relations)?,
+            self.vm_peak.to_string(),
+            self.error.to_string(),
+        ];
+        let sql = "insert into cron_runs (start, end, mode, filters, stages, failed_relations, vm_peak, error) values (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)";
+        let conn = ctx.get_database_connection()?;
+        conn.execute(sql, params)?;
+        Ok(())
+    }
+}
+
+/// Gets the last 100 cron runs, the latest first.
+pub fn get_cron_runs(ctx: &context::Context) -> anyhow::Result<Vec<CronRun>> {
+    let conn = ctx.get_database_connection()?;
+    let mut stmt = conn.prepare(
+        "select start, end, mode, filters, stages, failed_relations, vm_peak, error from cron_runs order by id desc limit 100",
+    )?;
+    let mut rows = stmt.query([])?;
+    let offset = ctx.get_time().now().offset();
+    let mut runs: Vec<CronRun> = Vec::new();
+    while let Some(row) = rows.next()? {
+        let start: String = row.get(0)?;
+        let start = time::OffsetDateTime::from_unix_timestamp_nanos(start.parse()?)?;
+        let end: String = row.get(1)?;
+        let end = time::OffsetDateTime::from_unix_timestamp_nanos(end.parse()?)?;
+        let stages: String = row.get(4)?;
+        let failed_relations: String = row.get(5)?;
+        runs.push(CronRun {
+            start: start.to_offset(offset),
+            end: end.to_offset(offset),
+            mode: row.get(2)?,
+            filters: row.get(3)?,
+            stages: serde_json::from_str(&stages)?,
+            failed_relations: serde_json::from_str(&failed_relations)?,
+            vm_peak: row.get(6)?,
+            error: row.get(7)?,
+        });
+    }
+    Ok(runs)
+}
+
 /// Performs the actual nightly task.
 fn our_main_inner(
     ctx: &context::Context,
     relations: &mut areas::Relations<'_>,
-    mode: &String,
-    update: bool,
+    run: &mut CronRun,
+    policy: &UpdatePolicy,
     overpass: bool,
     limited: bool,
 ) -> anyhow::Result<()> {
+    let mode = run.mode.to_string();
     if mode == "all" || mode == "stats" {
-        update_stats(ctx, overpass).context("update_stats failed")?;
+        run.run_stage(ctx, "update_stats", || update_stats(ctx, overpass))
+            .context("update_stats failed")?;
+        if ctx.get_ini().get_stats_retention_days()? > 0 {
+            run.run_stage(ctx, "downsample_stats", || {
+                let removed = stats::downsample_stats(ctx)?;
+                info!("downsample_stats: removed {removed} rows");
+                Ok(())
+            })?;
+        }
     }
     if mode == "all" || mode == "relations" {
         if !limited {
-            clean_osm_data(ctx, relations)?;
+            run.run_stage(ctx, "clean_osm_data", || clean_osm_data(ctx, relations))?;
         }
-        update_osm_streets(ctx, relations, update)?;
-        update_osm_housenumbers(ctx, relations, update)?;
-        update_missing_streets(relations, update)?;
-        update_missing_housenumbers(relations, update)?;
-        update_additional_streets(ctx, relations, update)?;
+        let kinds = [OsmJson::Streets, OsmJson::Housenumbers];
+        run.failed_relations = run.run_stage(ctx, "update_osm_json", || {
+            update_osm_json(ctx, relations, &kinds, policy)
+        })?;
+        run.run_stage(ctx, "update_missing_streets", || {
+            update_missing_streets(ctx, relations, policy)
+        })?;
+    }
+    // Before the house number coverage, which can then use the updated caches.
+    if mode == "all" || mode == "caches" {
+        run.run_stage(ctx, "update_caches", || {
+            update_caches(ctx, relations, policy)
+        })?;
+    }
+    if mode == "all" || mode == "relations" {
+        run.run_stage(ctx, "update_missing_housenumbers", || {
+            update_missing_housenumbers(ctx, relations, policy)
+        })?;
+        run.run_stage(ctx, "update_additional_streets", || {
+            update_additional_streets(ctx, relations, policy)
+        })?;
     }
 
     let pid = std::process::id();
@@ -499,9 +834,9 @@ fn our_main_inner(
     let reader = std::io::BufReader::new(stream);
     for line in reader.lines() {
         let line = line?.to_string();
-        if line.starts_with("VmPeak:") {
-            let vm_peak = line.trim();
-            info!("our_main: {vm_peak}");
+        if let Some(vm_peak) = line.strip_prefix("VmPeak:") {
+            run.vm_peak = vm_peak.trim().to_string();
+            info!("our_main: VmPeak: {}", run.vm_peak);
             break;
         }
     }
@@ -531,10 +866,24 @@ pub fn our_main(
         .long("no-update")
         .action(clap::ArgAction::SetTrue)
         .help("don't update existing state of relations");
+    let max_age = clap::Arg::new("max-age")
+        .long("max-age")
+        .value_parser(clap::value_parser!(u64))
+        .conflicts_with("no-update")
+        .help("only update the OSM data of relations which is older than the given hours");
+    let budget = clap::Arg::new("budget")
+        .long("budget")
+        .value_parser(clap::value_parser!(u64))
+        .help("don't start new OSM data updates after the given minutes");
+    let priority = clap::Arg::new("priority")
+        .long("priority")
+        .value_parser(["visits"])
+        .conflicts_with("no-update")
+        .help("update the OSM data of frequently visited relations first and hourly, the rest daily [visits]");
     let mode = clap::Arg::new("mode")
         .long("mode")
         .default_value("relations")
-        .help("only perform the given sub-task or all of them [all, stats or relations]");
+        .help("only perform the given sub-task or all of them [all, stats, relations or caches]");
     let no_overpass = clap::Arg::new("no-overpass") // default: true
         .long("no-overpass")
         .action(clap::ArgAction::SetTrue)
@@ -544,6 +893,9 @@ pub fn our_main(
         refsettlement,
         refarea,
         no_update,
+        max_age,
+        budget,
+        priority,
         mode,
         no_overpass,
     ];
@@ -564,23 +916,43 @@ pub fn our_main(
     relations.limit_to_refsettlement(&refsettlement)?;
     let refarea: Option<&String> = args.get_one("refarea");
     relations.limit_to_refarea(&refarea)?;
-    let update = !args.get_one::<bool>("no-update").unwrap();
+    let mut policy = UpdatePolicy::new(!args.get_one::<bool>("no-update").unwrap());
+    if let Some(hours) = args.get_one::<u64>("max-age") {
+        policy.max_age = Some(time::Duration::hours(*hours as i64));
+    }
+    if args.get_one::<String>("priority").is_some() {
+        // Only "visits" is accepted by the parser.
+        let visits = stats::get_relation_visits(ctx)?;
+        policy.frequent = stats::get_frequent_relations(&visits);
+        if policy.max_age.is_none() {
+            policy.max_age = Some(time::Duration::hours(24));
+        }
+    }
+    if let Some(minutes) = args.get_one::<u64>("budget") {
+        policy.deadline = Some(start + time::Duration::minutes(*minutes as i64));
+    }
     let overpass = !args.get_one::<bool>("no-overpass").unwrap();
     let limited = refcounty.is_some() || refsettlement.is_some() || refarea.is_some();
-    our_main_inner(
-        ctx,
-        &mut relations,
-        args.get_one("mode").unwrap(),
-        update,
-        overpass,
-        limited,
-    )
-    .context("our_main_inner failed")?;
-    let duration = ctx.get_time().now() - start;
-    let seconds = duration.whole_seconds() % 60;
-    let minutes = duration.whole_minutes() % 60;
-    let hours = duration.whole_hours();
-    let duration = format!("{hours}:{minutes:0>2}:{seconds:0>2}");
+    let filters: Vec<String> = [
+        ("refcounty", refcounty),
+        ("refsettlement", refsettlement),
+        ("refarea", refarea),
+    ]
+    .iter()
+    .filter_map(|(key, value)| value.map(|value| format!("{key}={value}")))
+    .collect();
+    // Failures of web requests are only queued, send them now.
+    notify::send_queued(ctx);
+    let mode: &String = args.get_one("mode").unwrap();
+    let mut run = CronRun::new(ctx, mode, &filters.join(", "));
+    let ret = our_main_inner(ctx, &mut relations, &mut run, &policy, overpass, limited);
+    run.end = ctx.get_time().now();
+    if let Err(ref err) = ret {
+        run.error = format!("{err:?}");
+    }
+    run.save(ctx)?;
+    ret.context("our_main_inner failed")?;
+    let duration = format_duration(ctx.get_time().now() - start);
     info!("main: finished in {duration}");
 
     Ok(())
@@ -592,6 +964,7 @@ pub fn main(argv: &[String], stream: &mut dyn Write, ctx: &context::Context) ->
         Ok(_) => 0,
         Err(err) => {
             error!("main: unhandled error: {err:?}");
+            notify::notify(ctx, notify::Event::Cron, &format!("{err:?}"));
             1
         }
     }
@@ -59,12 +59,14 @@ fn test_update_missing_housenumbers() {
     let expected: String = "0.00".into();
     let relation = relations.get_relation("myrelation").unwrap();
 
-    update_missing_housenumbers(&mut relations, /*update=*/ true).unwrap();
+    update_missing_housenumbers(&ctx, &mut relations, &UpdatePolicy::new(/*update=*/ true))
+        .unwrap();
 
     let expected_mtime = relation.get_osm_housenumber_coverage_mtime().unwrap();
     assert!(expected_mtime > time::OffsetDateTime::UNIX_EPOCH);
 
-    update_missing_housenumbers(&mut relations, /*update=*/ false).unwrap();
+    update_missing_housenumbers(&ctx, &mut relations, &UpdatePolicy::new(/*update=*/ false))
+        .unwrap();
 
     let actual_mtime = relation.get_osm_housenumber_coverage_mtime().unwrap();
     assert_eq!(actual_mtime, expected_mtime);
@@ -75,6 +77,98 @@ fn test_update_missing_housenumbers() {
     assert_eq!(relation2.has_osm_housenumber_coverage().unwrap(), false);
 }
 
+/// Tests update_caches().
+#[test]
+fn test_update_caches() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+            },
+            "myrelation2": {
+                "refcounty": "0",
+                "refsettlement": "0",
+            },
+        },
+        "relation-myrelation2.yaml": {
+            "missing-streets": "only",
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    let insert_lint = "insert into relation_lints (relation_name, street_name, source, housenumber, reason, object_id, object_type) values ('myrelation', 'oldstreet', 'invalid', '1', 'created-in-osm', '0', '')";
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'mystreet', '1', '');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '3', 'mystreet', '2', '', '', '', '', '', '', '', '', '', 'node');
+             insert into mtimes (page, last_modified) values ('housenumbers/myrelation', '0');",
+        )
+        .unwrap();
+        conn.execute(insert_lint, []).unwrap();
+    }
+    let get_lint_count = || -> i64 {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.query_row(
+            "select count(*) from relation_lints where street_name = 'oldstreet'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap()
+    };
+    let mut relations = areas::Relations::new(&ctx).unwrap();
+
+    // Missing caches are created, which rewrites the lints.
+    update_caches(&ctx, &mut relations, &UpdatePolicy::new(/*update=*/ false)).unwrap();
+
+    assert!(stats::has_sql_mtime(&ctx, "missing-housenumbers-cache/myrelation").unwrap());
+    assert!(stats::has_sql_mtime(&ctx, "additional-housenumbers-cache/myrelation").unwrap());
+    assert_eq!(get_lint_count(), 0);
+    // Make sure the caches are not created for the streets=only case.
+    assert!(!stats::has_sql_mtime(&ctx, "missing-housenumbers-cache/myrelation2").unwrap());
+
+    // Up to date caches are kept, unless an update is requested.
+    ctx.get_database_connection()
+        .unwrap()
+        .execute(insert_lint, [])
+        .unwrap();
+    update_caches(&ctx, &mut relations, &UpdatePolicy::new(/*update=*/ false)).unwrap();
+    assert_eq!(get_lint_count(), 1);
+    update_caches(&ctx, &mut relations, &UpdatePolicy::new(/*update=*/ true)).unwrap();
+    assert_eq!(get_lint_count(), 0);
+}
+
+/// Tests our_main_inner(): the caches mode only updates the caches.
+#[test]
+fn test_our_main_inner_caches() {
+    let ctx = context::tests::make_test_context().unwrap();
+    let mut relations = areas::Relations::new(&ctx).unwrap();
+    relations
+        .limit_to_refarea(&Some(&"nosuchrefarea".to_string()))
+        .unwrap();
+    let mut run = CronRun::new(&ctx, /*mode=*/ "caches", /*filters=*/ "");
+
+    our_main_inner(
+        &ctx,
+        &mut relations,
+        &mut run,
+        &UpdatePolicy::new(/*update=*/ true),
+        /*overpass=*/ true,
+        /*limited=*/ true,
+    )
+    .unwrap();
+
+    let stages: Vec<&str> = run.stages.iter().map(|i| i.name.as_str()).collect();
+    assert_eq!(stages, vec!["update_caches"]);
+}
+
 /// Tests update_missing_streets().
 #[test]
 fn test_update_missing_streets() {
@@ -122,12 +216,12 @@ fn test_update_missing_streets() {
     let expected: String = "50.00".into();
     let relation = relations.get_relation("gazdagret").unwrap();
 
-    update_missing_streets(&mut relations, /*update=*/ true).unwrap();
+    update_missing_streets(&ctx, &mut relations, &UpdatePolicy::new(/*update=*/ true)).unwrap();
 
     let expected_mtime = relation.get_osm_street_coverage_mtime().unwrap();
     assert!(expected_mtime > time::OffsetDateTime::UNIX_EPOCH);
 
-    update_missing_streets(&mut relations, /*update=*/ false).unwrap();
+    update_missing_streets(&ctx, &mut relations, &UpdatePolicy::new(/*update=*/ false)).unwrap();
 
     let actual_mtime = relation.get_osm_street_coverage_mtime().unwrap();
     assert_eq!(actual_mtime, expected_mtime);
@@ -138,6 +232,146 @@ fn test_update_missing_streets() {
     assert_eq!(relation2.has_osm_street_coverage().unwrap(), false);
 }
 
+/// Tests the stat updates: with a max age, only stats older than the OSM data are updated.
+#[test]
+fn test_update_stats_max_age() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    {
+        let ref_streets = ctx.get_ini().get_reference_street_path().unwrap();
+        util::build_street_reference_index(&ctx, &ref_streets).unwrap();
+    }
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "gazdagret": {
+                "refcounty": "01",
+                "refsettlement": "011",
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('gazdagret', '1', 'Tűzkő utca', '', '', '', '', '');
+             insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('gazdagret', '2', 'Törökugrató utca', '', '', '', '', '');
+             insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('gazdagret', '4', 'Hamzsabégi út', '', '', '', '', '');
+             insert into osm_street_coverages (relation_name, coverage, last_modified) values ('gazdagret', '0.00', '1');
+             insert into osm_housenumber_coverages (relation_name, coverage, last_modified) values ('gazdagret', '0.00', '1');
+             insert into additional_streets_counts (relation, count) values ('gazdagret', '5');
+             insert into mtimes (page, last_modified) values ('additional-streets/gazdagret', '1');
+             insert into mtimes (page, last_modified) values ('streets/gazdagret', '1');"
+        )
+        .unwrap();
+    }
+    let mut relations = areas::Relations::new(&ctx).unwrap();
+    let relation = relations.get_relation("gazdagret").unwrap();
+    let mut policy = UpdatePolicy::new(/*update=*/ true);
+    policy.max_age = Some(time::Duration::hours(24));
+
+    let epoch = time::OffsetDateTime::UNIX_EPOCH;
+
+    // The OSM data is not newer than the stats: no update.
+    update_missing_streets(&ctx, &mut relations, &policy).unwrap();
+    update_missing_housenumbers(&ctx, &mut relations, &policy).unwrap();
+    update_additional_streets(&ctx, &mut relations, &policy).unwrap();
+
+    assert_eq!(relation.get_osm_street_coverage().unwrap(), "0.00");
+    let mtime = relation.get_osm_housenumber_coverage_mtime().unwrap();
+    assert_eq!(mtime, epoch + time::Duration::nanoseconds(1));
+    let mtime = stats::get_sql_mtime(&ctx, "additional-streets/gazdagret").unwrap();
+    assert_eq!(mtime, epoch + time::Duration::nanoseconds(1));
+
+    // The OSM data was updated from overpass or by a diff: the stats follow.
+    stats::set_sql_mtime(&ctx, "streets/gazdagret").unwrap();
+    update_missing_streets(&ctx, &mut relations, &policy).unwrap();
+    stats::set_sql_mtime(&ctx, "dirty/housenumbers/gazdagret").unwrap();
+    update_missing_housenumbers(&ctx, &mut relations, &policy).unwrap();
+    update_additional_streets(&ctx, &mut relations, &policy).unwrap();
+
+    assert_eq!(relation.get_osm_street_coverage().unwrap(), "50.00");
+    let now = ctx.get_time().now();
+    assert_eq!(relation.get_osm_housenumber_coverage_mtime().unwrap(), now);
+    let mtime = stats::get_sql_mtime(&ctx, "additional-streets/gazdagret").unwrap();
+    assert_eq!(mtime, now);
+}
+
+/// Tests the stat updates: the deadline is reached, so nothing is updated.
+#[test]
+fn test_update_stats_deadline() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "gazdagret": {
+                "refcounty": "01",
+                "refsettlement": "011",
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    let mut relations = areas::Relations::new(&ctx).unwrap();
+    let mut policy = UpdatePolicy::new(/*update=*/ true);
+    policy.deadline = Some(ctx.get_time().now());
+
+    update_missing_streets(&ctx, &mut relations, &policy).unwrap();
+    update_caches(&ctx, &mut relations, &policy).unwrap();
+    update_missing_housenumbers(&ctx, &mut relations, &policy).unwrap();
+    update_additional_streets(&ctx, &mut relations, &policy).unwrap();
+
+    let relation = relations.get_relation("gazdagret").unwrap();
+    assert!(!relation.has_osm_street_coverage().unwrap());
+    assert!(!stats::has_sql_mtime(&ctx, "missing-housenumbers-cache/gazdagret").unwrap());
+    assert!(!relation.has_osm_housenumber_coverage().unwrap());
+    assert!(!stats::has_sql_count(&ctx, "additional_streets_counts", "gazdagret").unwrap());
+}
+
+/// Tests UpdatePolicy::get_relation_names(): frequent relations come first.
+#[test]
+fn test_update_policy_get_relation_names() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "budafok": {
+                "refcounty": "01",
+                "refsettlement": "011",
+            },
+            "gazdagret": {
+                "refcounty": "01",
+                "refsettlement": "011",
+            },
+            "ujbuda": {
+                "refcounty": "01",
+                "refsettlement": "011",
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    let mut relations = areas::Relations::new(&ctx).unwrap();
+    let mut policy = UpdatePolicy::new(/*update=*/ true);
+    policy.frequent.insert("ujbuda".into());
+
+    let names = policy.get_relation_names(&mut relations).unwrap();
+
+    assert_eq!(names, vec!["ujbuda", "budafok", "gazdagret"]);
+}
+
 /// Tests update_additional_streets().
 #[test]
 fn test_update_additional_streets() {
@@ -192,10 +426,10 @@ fn test_update_additional_streets() {
     }
     let mut relations = areas::Relations::new(&ctx).unwrap();
     let expected: String = "1".into();
-    update_additional_streets(&ctx, &mut relations, /*update=*/ true).unwrap();
+    update_additional_streets(&ctx, &mut relations, &UpdatePolicy::new(/*update=*/ true)).unwrap();
     let mtime = file_system_rc.getmtime(&path1).unwrap();
 
-    update_additional_streets(&ctx, &mut relations, /*update=*/ false).unwrap();
+    update_additional_streets(&ctx, &mut relations, &UpdatePolicy::new(/*update=*/ false)).unwrap();
 
     assert_eq!(file_system_rc.getmtime(&path1).unwrap(), mtime);
     let conn = ctx.get_database_connection().unwrap();
@@ -217,7 +451,7 @@ fn test_update_additional_streets() {
     assert!(rows.next().unwrap().is_none());
 }
 
-/// Tests update_osm_housenumbers().
+/// Tests update_osm_json(), housenumbers.
 #[test]
 fn test_update_osm_housenumbers() {
     let mut ctx = context::tests::make_test_context().unwrap();
@@ -286,11 +520,23 @@ fn test_update_osm_housenumbers() {
         .unwrap()
         .len();
 
-    update_osm_housenumbers(&ctx, &mut relations, /*update=*/ true).unwrap();
+    update_osm_json(
+        &ctx,
+        &mut relations,
+        &[OsmJson::Housenumbers],
+        &UpdatePolicy::new(/*update=*/ true),
+    )
+    .unwrap();
 
     let mtime = stats::get_sql_mtime(&ctx, "housenumbers/gazdagret").unwrap();
 
-    update_osm_housenumbers(&ctx, &mut relations, /*update=*/ false).unwrap();
+    update_osm_json(
+        &ctx,
+        &mut relations,
+        &[OsmJson::Housenumbers],
+        &UpdatePolicy::new(/*update=*/ false),
+    )
+    .unwrap();
 
     assert_eq!(
         stats::get_sql_mtime(&ctx, "housenumbers/gazdagret").unwrap(),
@@ -306,7 +552,7 @@ fn test_update_osm_housenumbers() {
     assert_eq!(actual, expected);
 }
 
-/// Tests update_osm_housenumbers(): the case when we keep getting HTTP errors.
+/// Tests update_osm_json(), housenumbers: the case when we keep getting HTTP errors.
 #[test]
 fn test_update_osm_housenumbers_http_error() {
     let mut ctx = context::tests::make_test_context().unwrap();
@@ -357,7 +603,13 @@ fn test_update_osm_housenumbers_http_error() {
     let file_system_rc: Rc<dyn FileSystem> = Rc::new(file_system);
     ctx.set_file_system(&file_system_rc);
     let mut relations = areas::Relations::new(&ctx).unwrap();
-    update_osm_housenumbers(&ctx, &mut relations, /*update=*/ true).unwrap();
+    update_osm_json(
+        &ctx,
+        &mut relations,
+        &[OsmJson::Housenumbers],
+        &UpdatePolicy::new(/*update=*/ true),
+    )
+    .unwrap();
     // Make sure that in case we keep getting errors we give up at some stage and
     // leave the last state unchanged.
     assert_eq!(
@@ -372,7 +624,7 @@ fn test_update_osm_housenumbers_http_error() {
     );
 }
 
-/// Tests update_osm_housenumbers(): the case when we ask for JSON but get XML.
+/// Tests update_osm_json(), housenumbers: the case when we ask for JSON but get XML.
 #[test]
 fn test_update_osm_housenumbers_xml_as_json() {
     let mut ctx = context::tests::make_test_context().unwrap();
@@ -425,7 +677,13 @@ fn test_update_osm_housenumbers_xml_as_json() {
         .unwrap();
     }
 
-    update_osm_housenumbers(&ctx, &mut relations, /*update=*/ true).unwrap();
+    update_osm_json(
+        &ctx,
+        &mut relations,
+        &[OsmJson::Housenumbers],
+        &UpdatePolicy::new(/*update=*/ true),
+    )
+    .unwrap();
 
     // Wanted JSON, got XML, make sure the db is left unchanged.
     let conn = ctx.get_database_connection().unwrap();
@@ -438,7 +696,7 @@ fn test_update_osm_housenumbers_xml_as_json() {
     assert_eq!(count, 1);
 }
 
-/// Tests update_osm_streets().
+/// Tests update_osm_json(), streets.
 #[test]
 fn test_update_osm_streets() {
     let mut ctx = context::tests::make_test_context().unwrap();
@@ -483,12 +741,24 @@ fn test_update_osm_streets() {
     ctx.set_file_system(&file_system_rc);
     let mut relations = areas::Relations::new(&ctx).unwrap();
 
-    update_osm_streets(&ctx, &mut relations, /*update=*/ true).unwrap();
+    update_osm_json(
+        &ctx,
+        &mut relations,
+        &[OsmJson::Streets],
+        &UpdatePolicy::new(/*update=*/ true),
+    )
+    .unwrap();
 
     let mtime = stats::get_sql_mtime(&ctx, "streets/gazdagret").unwrap();
     assert!(mtime > time::OffsetDateTime::UNIX_EPOCH);
 
-    update_osm_streets(&ctx, &mut relations, /*update=*/ false).unwrap();
+    update_osm_json(
+        &ctx,
+        &mut relations,
+        &[OsmJson::Streets],
+        &UpdatePolicy::new(/*update=*/ false),
+    )
+    .unwrap();
 
     assert_eq!(
         stats::get_sql_mtime(&ctx, "streets/gazdagret").unwrap(),
@@ -507,7 +777,230 @@ fn test_update_osm_streets() {
     );
 }
 
-/// Tests update_osm_streets(): the case when we keep getting HTTP errors.
+/// Tests update_osm_json(), streets: only stale data is updated, oldest first.
+#[test]
+fn test_update_osm_json_max_age() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let routes = vec![
+        context::tests::URLRoute::new(
+            /*url=*/ "https://overpass-api.de/api/interpreter",
+            /*data_path=*/ "",
+            /*result_path=*/ "src/fixtures/network/overpass-streets-gazdagret.json",
+        ),
+        context::tests::URLRoute::new(
+            /*url=*/ "https://overpass-api.de/api/interpreter",
+            /*data_path=*/ "",
+            /*result_path=*/ "src/fixtures/network/overpass-streets-ujbuda.json",
+        ),
+    ];
+    let network = context::tests::TestNetwork::new(&routes);
+    let network_rc: Rc<dyn context::Network> = Rc::new(network);
+    ctx.set_network(network_rc);
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "budafok": {
+                "osmrelation": 42,
+            },
+            "gazdagret": {
+                "osmrelation": 43,
+            },
+            "ujbuda": {
+                "osmrelation": 44,
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let template_value = context::tests::TestFileSystem::make_file();
+    template_value
+        .borrow_mut()
+        .write_all(b"aaa @RELATION@ bbb @AREA@ ccc\n")
+        .unwrap();
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[
+            ("data/yamls.cache", &yamls_cache_value),
+            ("data/streets-template.overpassql", &template_value),
+        ],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    let now = ctx.get_time().now();
+    let fresh = now - time::Duration::hours(1);
+    let stale = now - time::Duration::hours(48);
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.execute(
+            "insert into mtimes (page, last_modified) values ('streets/gazdagret', ?1), ('streets/ujbuda', ?2)",
+            [
+                fresh.unix_timestamp_nanos().to_string(),
+                stale.unix_timestamp_nanos().to_string(),
+            ],
+        )
+        .unwrap();
+    }
+    let mut relations = areas::Relations::new(&ctx).unwrap();
+    let mut policy = UpdatePolicy::new(/*update=*/ true);
+    policy.max_age = Some(time::Duration::hours(24));
+
+    update_osm_json(&ctx, &mut relations, &[OsmJson::Streets], &policy).unwrap();
+
+    // Fresh data is not updated.
+    let mtime = stats::get_sql_mtime(&ctx, "streets/gazdagret").unwrap();
+    assert_eq!(mtime, fresh);
+    // Missing data is updated first, then the stale one.
+    let mtime = stats::get_sql_mtime(&ctx, "streets/ujbuda").unwrap();
+    assert_eq!(mtime, now);
+    let budafok = relations.get_relation("budafok").unwrap();
+    let streets = budafok.get_files().get_osm_json_streets(&ctx).unwrap();
+    assert_eq!(streets.len(), 4);
+}
+
+/// Tests update_osm_json(), streets: frequent relations are updated first and more often.
+#[test]
+fn test_update_osm_json_frequent() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let routes = vec![
+        context::tests::URLRoute::new(
+            /*url=*/ "https://overpass-api.de/api/interpreter",
+            /*data_path=*/ "",
+            /*result_path=*/ "src/fixtures/network/overpass-streets-gazdagret.json",
+        ),
+        context::tests::URLRoute::new(
+            /*url=*/ "https://overpass-api.de/api/interpreter",
+            /*data_path=*/ "",
+            /*result_path=*/ "src/fixtures/network/overpass-streets-ujbuda.json",
+        ),
+    ];
+    let network = context::tests::TestNetwork::new(&routes);
+    let network_rc: Rc<dyn context::Network> = Rc::new(network);
+    ctx.set_network(network_rc);
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "budafok": {
+                "osmrelation": 42,
+            },
+            "gazdagret": {
+                "osmrelation": 43,
+            },
+            "ujbuda": {
+                "osmrelation": 44,
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let template_value = context::tests::TestFileSystem::make_file();
+    template_value
+        .borrow_mut()
+        .write_all(b"aaa @RELATION@ bbb @AREA@ ccc\n")
+        .unwrap();
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[
+            ("data/yamls.cache", &yamls_cache_value),
+            ("data/streets-template.overpassql", &template_value),
+        ],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    let now = ctx.get_time().now();
+    let two_hours_ago = now - time::Duration::hours(2);
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.execute(
+            "insert into mtimes (page, last_modified) values ('streets/gazdagret', ?1), ('streets/ujbuda', ?1)",
+            [two_hours_ago.unix_timestamp_nanos().to_string()],
+        )
+        .unwrap();
+    }
+    let mut relations = areas::Relations::new(&ctx).unwrap();
+    let mut policy = UpdatePolicy::new(/*update=*/ true);
+    policy.max_age = Some(time::Duration::hours(24));
+    policy.frequent = ["gazdagret".to_string()].into_iter().collect();
+
+    update_osm_json(&ctx, &mut relations, &[OsmJson::Streets], &policy).unwrap();
+
+    // Frequent data is updated hourly, even before missing data.
+    let mtime = stats::get_sql_mtime(&ctx, "streets/gazdagret").unwrap();
+    assert_eq!(mtime, now);
+    let gazdagret = relations.get_relation("gazdagret").unwrap();
+    let streets = gazdagret.get_files().get_osm_json_streets(&ctx).unwrap();
+    assert_eq!(streets.len(), 4);
+    // Not frequent data is updated daily.
+    let mtime = stats::get_sql_mtime(&ctx, "streets/ujbuda").unwrap();
+    assert_eq!(mtime, two_hours_ago);
+    // Missing data is still updated.
+    let mtime = stats::get_sql_mtime(&ctx, "streets/budafok").unwrap();
+    assert_eq!(mtime, now);
+}
+
+/// Tests main(): the visits priority without an explicit max age.
+#[test]
+fn test_main_priority() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {},
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    stats::add_relation_visit(&ctx, "gazdagret").unwrap();
+    let argv = vec![
+        "".to_string(),
+        "--mode".to_string(),
+        "relations".to_string(),
+        "--priority".to_string(),
+        "visits".to_string(),
+    ];
+    let mut buf: std::io::Cursor<Vec<u8>> = std::io::Cursor::new(Vec::new());
+
+    let ret = main(&argv, &mut buf, &ctx);
+
+    assert_eq!(ret, 0);
+    let runs = get_cron_runs(&ctx).unwrap();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].error, "");
+}
+
+/// Tests update_osm_json(), streets: the deadline is reached, so nothing is updated.
+#[test]
+fn test_update_osm_json_deadline() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "gazdagret": {
+                "osmrelation": 42,
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let template_value = context::tests::TestFileSystem::make_file();
+    template_value
+        .borrow_mut()
+        .write_all(b"aaa @RELATION@ bbb @AREA@ ccc\n")
+        .unwrap();
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[
+            ("data/yamls.cache", &yamls_cache_value),
+            ("data/streets-template.overpassql", &template_value),
+        ],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    let mut relations = areas::Relations::new(&ctx).unwrap();
+    let mut policy = UpdatePolicy::new(/*update=*/ true);
+    policy.deadline = Some(ctx.get_time().now());
+
+    update_osm_json(&ctx, &mut relations, &[OsmJson::Streets], &policy).unwrap();
+
+    assert!(!stats::has_sql_mtime(&ctx, "streets/gazdagret").unwrap());
+}
+
+/// Tests update_osm_json(), streets: the case when we keep getting HTTP errors.
 #[test]
 fn test_update_osm_streets_http_error() {
     let mut ctx = context::tests::make_test_context().unwrap();
@@ -555,8 +1048,15 @@ fn test_update_osm_streets_http_error() {
     ctx.set_file_system(&file_system_rc);
     let mut relations = areas::Relations::new(&ctx).unwrap();
 
-    update_osm_streets(&ctx, &mut relations, /*update=*/ true).unwrap();
+    let failed = update_osm_json(
+        &ctx,
+        &mut relations,
+        &[OsmJson::Streets],
+        &UpdatePolicy::new(/*update=*/ true),
+    )
+    .unwrap();
 
+    assert_eq!(failed, vec!["streets/myrelation".to_string()]);
     // Make sure that in case we keep getting errors we give up at some stage and
     // leave the last state unchanged.
     assert_eq!(
@@ -571,7 +1071,7 @@ fn test_update_osm_streets_http_error() {
     );
 }
 
-/// Tests update_osm_streets(): the case when we ask for JSON but get XML.
+/// Tests update_osm_json(), streets: the case when we ask for JSON but get XML.
 #[test]
 fn test_update_osm_streets_xml_as_json() {
     let mut ctx = context::tests::make_test_context().unwrap();
@@ -621,7 +1121,13 @@ fn test_update_osm_streets_xml_as_json() {
         .unwrap();
     }
 
-    update_osm_streets(&ctx, &mut relations, /*update=*/ true).unwrap();
+    update_osm_json(
+        &ctx,
+        &mut relations,
+        &[OsmJson::Streets],
+        &UpdatePolicy::new(/*update=*/ true),
+    )
+    .unwrap();
 
     // Wanted JSON, got XML, make sure the db is left unchanged.
     let conn = ctx.get_database_connection().unwrap();
@@ -889,7 +1395,7 @@ fn test_our_main() {
             /*data_path=*/ "",
             /*result_path=*/ "src/fixtures/network/overpass-streets-gazdagret.json",
         ),
-        // For update_osm_housenumbers().
+        // For update_osm_json(), housenumbers.
         context::tests::URLRoute::new(
             /*url=*/ "https://overpass-api.de/api/interpreter",
             /*data_path=*/ "",
@@ -947,23 +1453,35 @@ fn test_our_main() {
     }
     let mut relations = areas::Relations::new(&ctx).unwrap();
     let relation = relations.get_relation("gazdagret").unwrap();
+    let mut run = CronRun::new(&ctx, /*mode=*/ "relations", /*filters=*/ "");
 
     our_main_inner(
         &ctx,
         &mut relations,
-        /*mode=*/ &"relations".to_string(),
-        /*update=*/ true,
+        &mut run,
+        &UpdatePolicy::new(/*update=*/ true),
         /*overpass=*/ true,
         /*limited=*/ false,
     )
     .unwrap();
 
-    // update_osm_streets() is called.
+    let stages: Vec<&str> = run.stages.iter().map(|i| i.name.as_str()).collect();
+    assert_eq!(
+        stages,
+        vec![
+            "clean_osm_data",
+            "update_osm_json",
+            "update_missing_streets",
+            "update_missing_housenumbers",
+            "update_additional_streets"
+        ]
+    );
+    // update_osm_json() is called, streets.
     {
         let mtime = stats::get_sql_mtime(&ctx, "streets/gazdagret").unwrap();
         assert!(mtime > time::OffsetDateTime::UNIX_EPOCH);
     }
-    // update_osm_housenumbers() is called.
+    // update_osm_json() is called, housenumbers.
     assert_eq!(
         relation
             .get_files()
@@ -1006,8 +1524,8 @@ fn test_our_main_limited() {
     our_main_inner(
         &ctx,
         &mut relations,
-        /*mode=*/ &"relations".to_string(),
-        /*update=*/ true,
+        &mut CronRun::new(&ctx, /*mode=*/ "relations", /*filters=*/ ""),
+        &UpdatePolicy::new(/*update=*/ true),
         /*overpass=*/ true,
         /*limited=*/ true,
     )
@@ -1079,8 +1597,8 @@ fn test_our_main_stats() {
     our_main_inner(
         &ctx,
         &mut relations,
-        /*mode=*/ &"stats".to_string(),
-        /*update=*/ false,
+        &mut CronRun::new(&ctx, /*mode=*/ "stats", /*filters=*/ ""),
+        &UpdatePolicy::new(/*update=*/ false),
         /*overpass=*/ true,
         /*limited=*/ false,
     )
@@ -1090,6 +1608,13 @@ fn test_our_main_stats() {
     assert_eq!(guard.seek(SeekFrom::Current(0)).unwrap() > 0, true);
 }
 
+/// Tests format_duration().
+#[test]
+fn test_format_duration() {
+    let duration = time::Duration::seconds(3 * 3600 + 4 * 60 + 5);
+    assert_eq!(format_duration(duration), "3:04:05");
+}
+
 /// Tests main().
 #[test]
 fn test_main() {
@@ -1115,6 +1640,12 @@ fn test_main() {
         "--mode".to_string(),
         "stats".to_string(),
         "--no-overpass".to_string(),
+        "--refcounty".to_string(),
+        "01".to_string(),
+        "--max-age".to_string(),
+        "24".to_string(),
+        "--budget".to_string(),
+        "60".to_string(),
     ];
     let mut buf: std::io::Cursor<Vec<u8>> = std::io::Cursor::new(Vec::new());
 
@@ -1135,6 +1666,84 @@ fn test_main() {
     let actual: i64 = count.parse().unwrap();
     // Same as in test_update_stats().
     assert_eq!(actual, 300);
+    drop(counts);
+    drop(stmt);
+    drop(conn);
+
+    // The run is recorded.
+    let runs = get_cron_runs(&ctx).unwrap();
+    assert_eq!(runs.len(), 1);
+    let run = &runs[0];
+    assert_eq!(run.start, ctx.get_time().now());
+    assert_eq!(run.end, run.start);
+    assert_eq!(run.mode, "stats");
+    assert_eq!(run.filters, "refcounty=01");
+    let stages: Vec<&str> = run.stages.iter().map(|i| i.name.as_str()).collect();
+    assert_eq!(stages, vec!["update_stats"]);
+    assert_eq!(run.stages[0].seconds, 0_f64);
+    assert!(run.failed_relations.is_empty());
+    assert!(!run.vm_peak.is_empty());
+    assert_eq!(run.error, "");
+}
+
+/// Tests main(): the stats are downsampled when a retention window is configured.
+#[test]
+fn test_main_downsample_stats() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let stats_value = context::tests::TestFileSystem::make_file();
+    let overpass_template = context::tests::TestFileSystem::make_file();
+    let wsgi_ini = context::tests::TestFileSystem::make_file();
+    {
+        let mut guard = wsgi_ini.borrow_mut();
+        let data = std::fs::read_to_string("tests/workdir/wsgi.ini").unwrap();
+        guard.write_all(data.as_bytes()).unwrap();
+        guard.write_all(b"stats_retention_days = '365'\n").unwrap();
+    }
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[
+            ("workdir/stats/stats.json", &stats_value),
+            (
+                "data/street-housenumbers-hungary.overpassql",
+                &overpass_template,
+            ),
+            ("workdir/wsgi.ini", &wsgi_ini),
+        ],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    ctx.update_ini().unwrap();
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into stats_counts (date, count) values ('2019-04-01', '199000');
+             insert into stats_counts (date, count) values ('2019-04-02', '199001');",
+        )
+        .unwrap();
+    }
+    let argv = vec![
+        "".to_string(),
+        "--mode".to_string(),
+        "stats".to_string(),
+        "--no-overpass".to_string(),
+    ];
+    let mut buf: std::io::Cursor<Vec<u8>> = std::io::Cursor::new(Vec::new());
+
+    let ret = main(&argv, &mut buf, &ctx);
+
+    assert_eq!(ret, 0);
+    let runs = get_cron_runs(&ctx).unwrap();
+    let stages: Vec<&str> = runs[0].stages.iter().map(|i| i.name.as_str()).collect();
+    assert_eq!(stages, vec!["update_stats", "downsample_stats"]);
+    let conn = ctx.get_database_connection().unwrap();
+    let count: i64 = conn
+        .query_row(
+            "select count(*) from stats_counts where date like '2019-04-%'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(count, 1);
 }
 
 /// Tests main(): the path when our_main() returns an error.
@@ -1165,6 +1774,10 @@ fn test_main_error() {
     let ret = main(&argv, &mut buf, &mut ctx);
 
     assert_eq!(ret, 1);
+    // The failed run is recorded, too.
+    let runs = get_cron_runs(&ctx).unwrap();
+    assert_eq!(runs.len(), 1);
+    assert!(!runs[0].error.is_empty());
 }
 
 /// Tests update_stats_count().
@@ -16,15 +16,20 @@ mod areas;
 mod cache;
 pub mod cache_yamls;
 pub mod context;
+mod country;
 pub mod cron;
 mod i18n;
 pub mod missing_housenumbers;
+mod notify;
+pub mod osm_change;
 mod overpass_query;
 pub mod parse_access_log;
+pub mod propose_filters;
 mod ranges;
 mod serde;
 mod sql;
 mod stats;
+pub mod suggest_refstreets;
 pub mod sync_ref;
 pub mod util;
 pub mod validator;
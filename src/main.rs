@@ -114,11 +114,14 @@ fn cron_main(args: &[String], stream: &mut dyn Write, ctx: &osm_gimmisn::context
 lazy_static::lazy_static! {
     static ref HANDLERS: HashMap<String, Handler> = {
         let mut ret: HashMap<String, Handler> = HashMap::new();
+        ret.insert("apply-osc".into(), osm_gimmisn::osm_change::main);
         ret.insert("cache-yamls".into(), osm_gimmisn::cache_yamls::main);
         ret.insert("cron".into(), cron_main);
         ret.insert("missing-housenumbers".into(), osm_gimmisn::missing_housenumbers::main);
         ret.insert("parse-access-log".into(), osm_gimmisn::parse_access_log::main);
+        ret.insert("propose-filters".into(), osm_gimmisn::propose_filters::main);
         ret.insert("rouille".into(), rouille_main);
+        ret.insert("suggest-refstreets".into(), osm_gimmisn::suggest_refstreets::main);
         ret.insert("sync-ref".into(), osm_gimmisn::sync_ref::main);
         ret.insert("validator".into(), osm_gimmisn::validator::main);
         ret
@@ -128,6 +131,8 @@ lazy_static::lazy_static! {
 fn main() {
     let mut args: Vec<String> = std::env::args().collect();
     let ctx = osm_gimmisn::context::Context::new("").unwrap();
+    let apply_osc = clap::Command::new("apply-osc")
+        .about("Applies OSM replication diffs to the OSM streets and house numbers");
     let cache_yamls =
         clap::Command::new("cache-yamls").about("Caches YAML files from the data/ directory");
     let cron = clap::Command::new("cron").about("Performs nightly tasks");
@@ -135,16 +140,23 @@ fn main() {
         .about("Compares reference house numbers with OSM ones and shows the diff");
     let parse_access_log = clap::Command::new("parse-access-log")
         .about("Parses the Apache access log of osm-gimmisn for 1 month");
+    let propose_filters = clap::Command::new("propose-filters")
+        .about("Proposes filter ranges for a relation, based on the OSM house numbers");
     let rouille = clap::Command::new("rouille").about("Starts the web interface");
+    let suggest_refstreets = clap::Command::new("suggest-refstreets")
+        .about("Suggests refstreets entries, pairing missing and additional streets");
     let sync_ref = clap::Command::new("sync-ref")
         .about("Synchronizes the reference data from a public instance to a local dev instance");
     let validator = clap::Command::new("validator").about("Validates yaml files under data/");
     let subcommands = vec![
+        apply_osc,
         cache_yamls,
         cron,
         missing_housenumbers,
         parse_access_log,
+        propose_filters,
         rouille,
+        suggest_refstreets,
         sync_ref,
         validator,
     ];
@@ -0,0 +1,235 @@
+/*
+ * Copyright 2025 Miklos Vajna
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+#![deny(warnings)]
+#![warn(clippy::all)]
+#![warn(missing_docs)]
+
+//! Sends notifications about failures to a webhook and/or via mail, as configured in wsgi.ini.
+
+use crate::context;
+use std::collections::HashMap;
+
+#[cfg(not(test))]
+use log::warn;
+
+#[cfg(test)]
+use std::println as warn;
+
+/// The kind of failure a notification is about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// The cron job failed.
+    Cron,
+    /// An overpass query failed, even after retries.
+    Overpass,
+    /// A web request failed with an unhandled error.
+    Web,
+}
+
+impl Event {
+    /// Gets the name of the event, used for rate limiting and in the notification itself.
+    fn get_name(&self) -> &'static str {
+        match self {
+            Event::Cron => "cron",
+            Event::Overpass => "overpass",
+            Event::Web => "web",
+        }
+    }
+}
+
+/// Decides if a notification for the event can be sent now. Returns the number of notifications
+/// suppressed since the last sent one if it can be sent, None if it's rate limited.
+fn check_rate_limit(ctx: &context::Context, event: Event) -> anyhow::Result<Option<u64>> {
+    let now = ctx.get_time().now();
+    let interval = time::Duration::minutes(ctx.get_ini().get_notify_interval()?);
+    let conn = ctx.get_database_connection()?;
+    let mut stmt =
+        conn.prepare("select last_sent, suppressed from notifications where kind = ?1")?;
+    let mut rows = stmt.query([event.get_name()])?;
+    let mut previous: Option<(String, String)> = None;
+    if let Some(row) = rows.next()? {
+        previous = Some((row.get(0)?, row.get(1)?));
+    }
+    drop(rows);
+    let mut suppressed: u64 = 0;
+    if let Some((last_sent, count)) = previous {
+        let last_sent = time::OffsetDateTime::from_unix_timestamp_nanos(last_sent.parse()?)?;
+        suppressed = count.parse()?;
+        if now - last_sent < interval {
+            let sql = "update notifications set suppressed = ?1 where kind = ?2";
+            conn.execute(sql, [&(suppressed + 1).to_string(), event.get_name()])?;
+            return Ok(None);
+        }
+    }
+
+    let sql = r#"insert into notifications (kind, last_sent, suppressed) values (?1, ?2, '0')
+             on conflict(kind) do update set last_sent = excluded.last_sent, suppressed = '0'"#;
+    let params = [event.get_name(), &now.unix_timestamp_nanos().to_string()];
+    conn.execute(sql, params)?;
+    Ok(Some(suppressed))
+}
+
+/// Sends a notification to all configured targets.
+fn send(
+    ctx: &context::Context,
+    event: Event,
+    message: &str,
+    suppressed: u64,
+) -> anyhow::Result<()> {
+    let mut text = message.to_string();
+    if suppressed > 0 {
+        text +=
+            &format!("\n\n{suppressed} similar notifications were suppressed since the last one.");
+    }
+    let now = ctx.get_time().now();
+
+    let url = ctx.get_ini().get_notify_webhook_url();
+    if !url.is_empty() {
+        let json = serde_json::json!({
+            "kind": event.get_name(),
+            "message": text,
+            "time": now.format(&time::format_description::well_known::Rfc3339)?,
+        });
+        let mut headers = HashMap::new();
+        headers.insert("Content-Type".to_string(), "application/json".to_string());
+        ctx.get_network()
+            .urlopen(&url, &serde_json::to_string(&json)?, &headers)?;
+    }
+
+    let to = ctx.get_ini().get_notify_smtp_to();
+    if !to.is_empty() {
+        let from = ctx.get_ini().get_notify_smtp_from();
+        let date = now.format(&time::format_description::well_known::Rfc2822)?;
+        let name = event.get_name();
+        let body = text.lines().collect::<Vec<_>>().join("\r\n");
+        let mail = format!(
+            "From: {from}\r\nTo: {}\r\nSubject: [osm-gimmisn] {name} failure\r\nDate: {date}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{body}\r\n",
+            to.join(", ")
+        );
+        let server = ctx.get_ini().get_notify_smtp_server();
+        ctx.get_network().send_mail(&server, &from, &to, &mail)?;
+    }
+
+    Ok(())
+}
+
+/// Decides if any notification targets are configured.
+fn is_enabled(ctx: &context::Context) -> bool {
+    let ini = ctx.get_ini();
+    !ini.get_notify_webhook_url().is_empty() || !ini.get_notify_smtp_to().is_empty()
+}
+
+/// Sends a notification, unless no targets are configured or it's rate limited. Returns if the
+/// notification was sent.
+fn try_notify(ctx: &context::Context, event: Event, message: &str) -> anyhow::Result<bool> {
+    if !is_enabled(ctx) {
+        return Ok(false);
+    }
+
+    let suppressed = match check_rate_limit(ctx, event)? {
+        Some(value) => value,
+        None => return Ok(false),
+    };
+    send(ctx, event, message, suppressed)?;
+    Ok(true)
+}
+
+/// Notifies about a failure. This is called on error paths already, so a failure to notify is
+/// only logged.
+pub fn notify(ctx: &context::Context, event: Event, message: &str) {
+    if let Err(err) = try_notify(ctx, event, message) {
+        warn!(
+            "notify: failed to send a {} notification: {err:?}",
+            event.get_name()
+        );
+    }
+}
+
+/// Queues a notification, unless no targets are configured. There is at most one queued
+/// notification per kind, the rest is counted as suppressed. Returns if the notification was
+/// queued.
+fn try_queue(ctx: &context::Context, event: Event, message: &str) -> anyhow::Result<bool> {
+    if !is_enabled(ctx) {
+        return Ok(false);
+    }
+
+    let conn = ctx.get_database_connection()?;
+    let mut stmt = conn.prepare("select suppressed, pending from notifications where kind = ?1")?;
+    let mut rows = stmt.query([event.get_name()])?;
+    let mut previous: Option<(String, String)> = None;
+    if let Some(row) = rows.next()? {
+        previous = Some((row.get(0)?, row.get(1)?));
+    }
+    drop(rows);
+    match previous {
+        Some((suppressed, pending)) if !pending.is_empty() => {
+            let suppressed: u64 = suppressed.parse()?;
+            let sql = "update notifications set suppressed = ?1 where kind = ?2";
+            conn.execute(sql, [&(suppressed + 1).to_string(), event.get_name()])?;
+            return Ok(false);
+        }
+        Some(_) => {
+            let sql = "update notifications set pending = ?1 where kind = ?2";
+            conn.execute(sql, [message, event.get_name()])?;
+        }
+        None => {
+            let sql = "insert into notifications (kind, last_sent, suppressed, pending) values (?1, '0', '0', ?2)";
+            conn.execute(sql, [event.get_name(), message])?;
+        }
+    }
+    Ok(true)
+}
+
+/// Queues a notification about a failure, to be sent later by send_queued(). Unlike notify(), this
+/// doesn't wait for the network, so it can be used while serving a web request.
+pub fn queue(ctx: &context::Context, event: Event, message: &str) {
+    if let Err(err) = try_queue(ctx, event, message) {
+        warn!(
+            "queue: failed to queue a {} notification: {err:?}",
+            event.get_name()
+        );
+    }
+}
+
+/// Takes the queued notification of a kind, if there is one.
+fn take_queued(ctx: &context::Context, event: Event) -> anyhow::Result<Option<String>> {
+    let conn = ctx.get_database_connection()?;
+    let mut stmt = conn.prepare("select pending from notifications where kind = ?1")?;
+    let mut rows = stmt.query([event.get_name()])?;
+    let mut pending = String::new();
+    if let Some(row) = rows.next()? {
+        pending = row.get(0)?;
+    }
+    drop(rows);
+    if pending.is_empty() {
+        return Ok(None);
+    }
+
+    let sql = "update notifications set pending = '' where kind = ?1";
+    conn.execute(sql, [event.get_name()])?;
+    Ok(Some(pending))
+}
+
+/// Sends the queued notifications of all kinds.
+fn try_send_queued(ctx: &context::Context) -> anyhow::Result<()> {
+    for event in [Event::Cron, Event::Overpass, Event::Web] {
+        if let Some(message) = take_queued(ctx, event)? {
+            notify(ctx, event, &message);
+        }
+    }
+    Ok(())
+}
+
+/// Sends the notifications queued by queue(), respecting the rate limit.
+pub fn send_queued(ctx: &context::Context) {
+    if let Err(err) = try_send_queued(ctx) {
+        warn!("send_queued: failed to send the queued notifications: {err:?}");
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,192 @@
+/*
+ * Copyright 2025 Miklos Vajna
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+#![deny(warnings)]
+#![warn(clippy::all)]
+#![warn(missing_docs)]
+
+//! Tests for the notify module.
+
+use super::*;
+
+use std::io::Write as _;
+use std::rc::Rc;
+
+/// Creates a test context with the given notify settings in wsgi.ini.
+fn make_test_context(notify_ini: &str, routes: &[context::tests::URLRoute]) -> context::Context {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let wsgi_ini = context::tests::TestFileSystem::make_file();
+    wsgi_ini
+        .borrow_mut()
+        .write_all(
+            format!("[wsgi]\nreference_housenumbers = ''\nreference_street = ''\nreference_citycounts = ''\nreference_zipcounts = ''\n{notify_ini}").as_bytes(),
+        )
+        .unwrap();
+    let files =
+        context::tests::TestFileSystem::make_files(&ctx, &[("workdir/wsgi.ini", &wsgi_ini)]);
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    ctx.update_ini().unwrap();
+    let network = context::tests::TestNetwork::new(routes);
+    let network_rc: Rc<dyn context::Network> = Rc::new(network);
+    ctx.set_network(network_rc);
+    ctx
+}
+
+/// Tests try_notify(): no targets are configured, so nothing is sent.
+#[test]
+fn test_try_notify_disabled() {
+    let ctx = make_test_context("", &[]);
+
+    let ret = try_notify(&ctx, Event::Cron, "myerror").unwrap();
+
+    assert!(!ret);
+    let conn = ctx.get_database_connection().unwrap();
+    let count: i64 = conn
+        .query_row("select count(*) from notifications", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(count, 0);
+}
+
+/// Tests try_notify(): the webhook is called, the second notification is rate limited.
+#[test]
+fn test_try_notify_webhook() {
+    let routes = vec![context::tests::URLRoute::new(
+        /*url=*/ "https://example.com/hook",
+        /*data_path=*/ "src/fixtures/network/notify-webhook.json",
+        /*result_path=*/ "src/fixtures/network/notify-ok.txt",
+    )];
+    let ctx = make_test_context("notify_webhook_url = 'https://example.com/hook'\n", &routes);
+
+    assert!(try_notify(&ctx, Event::Cron, "myerror").unwrap());
+    // Not sent: the route is consumed already, so this would fail otherwise.
+    assert!(!try_notify(&ctx, Event::Cron, "myerror").unwrap());
+    assert!(!try_notify(&ctx, Event::Cron, "myerror").unwrap());
+
+    let conn = ctx.get_database_connection().unwrap();
+    let suppressed: String = conn
+        .query_row(
+            "select suppressed from notifications where kind = 'cron'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(suppressed, "2");
+}
+
+/// Tests try_notify(): a mail is sent, mentioning the previously suppressed notifications.
+#[test]
+fn test_try_notify_mail() {
+    let routes = vec![context::tests::URLRoute::new(
+        /*url=*/ "smtp://localhost:25",
+        /*data_path=*/ "src/fixtures/network/notify-mail.txt",
+        /*result_path=*/ "src/fixtures/network/notify-ok.txt",
+    )];
+    let ctx = make_test_context(
+        "notify_smtp_to = 'a@example.com b@example.com'\nnotify_interval = '30'\n",
+        &routes,
+    );
+    {
+        let two_hours_ago = ctx.get_time().now() - time::Duration::hours(2);
+        let conn = ctx.get_database_connection().unwrap();
+        let sql =
+            "insert into notifications (kind, last_sent, suppressed) values ('overpass', ?1, '3')";
+        conn.execute(sql, [two_hours_ago.unix_timestamp_nanos().to_string()])
+            .unwrap();
+    }
+
+    let ret = try_notify(&ctx, Event::Overpass, "myerror").unwrap();
+
+    assert!(ret);
+    let conn = ctx.get_database_connection().unwrap();
+    let suppressed: String = conn
+        .query_row(
+            "select suppressed from notifications where kind = 'overpass'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(suppressed, "0");
+}
+
+/// Tests notify(): failing to send is not fatal.
+#[test]
+fn test_notify_error() {
+    let ctx = make_test_context("notify_webhook_url = 'https://example.com/hook'\n", &[]);
+
+    // The webhook is not reachable, this is only logged.
+    notify(&ctx, Event::Web, "myerror");
+
+    // The rate limit is still recorded, so an unreachable webhook is not retried all the time.
+    assert!(!try_notify(&ctx, Event::Web, "myerror").unwrap());
+    // Other kinds are not limited.
+    assert!(try_notify(&ctx, Event::Cron, "myerror").is_err());
+}
+
+/// Tests try_queue(): no targets are configured, so nothing is queued.
+#[test]
+fn test_try_queue_disabled() {
+    let ctx = make_test_context("", &[]);
+
+    assert!(!try_queue(&ctx, Event::Web, "myerror").unwrap());
+
+    let conn = ctx.get_database_connection().unwrap();
+    let count: i64 = conn
+        .query_row("select count(*) from notifications", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(count, 0);
+}
+
+/// Tests queue() and send_queued(): only one notification is queued, the rest is counted as
+/// suppressed.
+#[test]
+fn test_queue() {
+    let routes = vec![context::tests::URLRoute::new(
+        /*url=*/ "https://example.com/hook",
+        /*data_path=*/ "",
+        /*result_path=*/ "src/fixtures/network/notify-ok.txt",
+    )];
+    let ctx = make_test_context("notify_webhook_url = 'https://example.com/hook'\n", &routes);
+
+    assert!(try_queue(&ctx, Event::Web, "myerror").unwrap());
+    assert!(!try_queue(&ctx, Event::Web, "myerror2").unwrap());
+
+    let get_row = || -> (String, String, String) {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.query_row(
+            "select last_sent, suppressed, pending from notifications where kind = 'web'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .unwrap()
+    };
+    assert_eq!(get_row(), ("0".into(), "1".into(), "myerror".into()));
+
+    send_queued(&ctx);
+
+    let now = ctx.get_time().now().unix_timestamp_nanos().to_string();
+    assert_eq!(get_row(), (now, "0".into(), "".into()));
+    // Nothing is queued anymore, so a new notification can be queued.
+    send_queued(&ctx);
+    queue(&ctx, Event::Web, "myerror3");
+    assert_eq!(get_row().2, "myerror3");
+}
+
+/// Tests queue() and send_queued(): failing to access the database is not fatal.
+#[test]
+fn test_queue_error() {
+    let ctx = make_test_context("notify_webhook_url = 'https://example.com/hook'\n", &[]);
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.execute_batch("drop table notifications").unwrap();
+    }
+
+    // These are only logged.
+    queue(&ctx, Event::Web, "myerror");
+    send_queued(&ctx);
+
+    assert!(try_queue(&ctx, Event::Web, "myerror").is_err());
+}
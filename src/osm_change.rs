@@ -0,0 +1,512 @@
+/*
+ * Copyright 2025 Miklos Vajna
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+#![deny(warnings)]
+#![warn(clippy::all)]
+#![warn(missing_docs)]
+
+//! The osm_change module applies OSM replication diffs (osmChange files) to the OSM tables.
+
+use crate::context;
+use crate::stats;
+use anyhow::Context;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::io::Write;
+
+/// What happened to an object in an osmChange file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OscAction {
+    /// The object is new.
+    Create,
+    /// The object has a new version.
+    Modify,
+    /// The object is deleted.
+    Delete,
+}
+
+/// One changed object in an osmChange file.
+pub struct OscElement {
+    /// What happened to the object.
+    pub action: OscAction,
+    /// Object type: node, way or relation.
+    pub osm_type: String,
+    /// Object ID.
+    pub id: u64,
+    /// Last editor.
+    pub user: String,
+    /// Last modification.
+    pub timestamp: String,
+    /// Latitude of a node.
+    pub lat: Option<f64>,
+    /// Longitude of a node.
+    pub lon: Option<f64>,
+    /// Tags of the new version.
+    pub tags: HashMap<String, String>,
+    /// Node IDs of a way.
+    pub nodes: Vec<u64>,
+}
+
+impl OscElement {
+    /// Gets the value of a tag, or an empty string.
+    fn get_tag(&self, key: &str) -> String {
+        self.tags.get(key).cloned().unwrap_or_default()
+    }
+
+    /// Decides if the object is a street, see data/streets-template.overpassql.
+    fn is_street(&self) -> bool {
+        if self.osm_type == "node" || !self.tags.contains_key("name") {
+            return false;
+        }
+
+        self.tags.contains_key("highway")
+            || self.get_tag("leisure") == "park"
+            || self.get_tag("place") == "square"
+    }
+
+    /// Decides if the object is an address, see data/street-housenumbers-template.overpassql.
+    fn is_housenumber(&self) -> bool {
+        [
+            "addr:street",
+            "addr:housenumber",
+            "addr:postcode",
+            "addr:conscriptionnumber",
+        ]
+        .iter()
+        .any(|key| self.tags.contains_key(*key))
+    }
+}
+
+/// Parses an osmChange XML document.
+pub fn parse_osc(xml: &str) -> anyhow::Result<Vec<OscElement>> {
+    let package = sxd_document::parser::parse(xml).context("failed to parse osmChange")?;
+    let document = package.as_document();
+    let root = document
+        .root()
+        .children()
+        .into_iter()
+        .find_map(|i| i.element());
+    let Some(root) = root.filter(|i| i.name().local_part() == "osmChange") else {
+        return Err(anyhow::anyhow!("expected an osmChange root element"));
+    };
+
+    let mut elements: Vec<OscElement> = Vec::new();
+    for block in root.children().into_iter().filter_map(|i| i.element()) {
+        let action = match block.name().local_part() {
+            "create" => OscAction::Create,
+            "modify" => OscAction::Modify,
+            "delete" => OscAction::Delete,
+            _ => continue,
+        };
+        for object in block.children().into_iter().filter_map(|i| i.element()) {
+            let osm_type = object.name().local_part().to_string();
+            let Some(id) = object.attribute_value("id") else {
+                return Err(anyhow::anyhow!("expected an id for a {osm_type}"));
+            };
+            let mut element = OscElement {
+                action,
+                osm_type,
+                id: id.parse().context("failed to parse the id")?,
+                user: object.attribute_value("user").unwrap_or("").into(),
+                timestamp: object.attribute_value("timestamp").unwrap_or("").into(),
+                lat: object.attribute_value("lat").and_then(|i| i.parse().ok()),
+                lon: object.attribute_value("lon").and_then(|i| i.parse().ok()),
+                tags: HashMap::new(),
+                nodes: Vec::new(),
+            };
+            for child in object.children().into_iter().filter_map(|i| i.element()) {
+                match child.name().local_part() {
+                    "tag" => {
+                        let key = child.attribute_value("k").unwrap_or("");
+                        let value = child.attribute_value("v").unwrap_or("");
+                        element.tags.insert(key.into(), value.into());
+                    }
+                    "nd" => {
+                        if let Some(node) =
+                            child.attribute_value("ref").and_then(|i| i.parse().ok())
+                        {
+                            element.nodes.push(node);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            elements.push(element);
+        }
+    }
+    Ok(elements)
+}
+
+/// Gets the page in the mtimes table which marks the OSM data of a relation as changed by a diff,
+/// for a page like streets/myrelation. This is separate from the page itself, which tracks the last
+/// update from overpass.
+pub fn get_dirty_page(page: &str) -> String {
+    format!("dirty/{page}")
+}
+
+/// Summarizes what applying an osmChange did.
+#[derive(Default)]
+pub struct OscSummary {
+    /// Pages of the changed relations, e.g. streets/myrelation.
+    pub dirty: BTreeSet<String>,
+    /// New objects which are inside the boundary of multiple relations, e.g. node/42.
+    pub ambiguous: BTreeSet<String>,
+    /// Number of changed objects in the whole_country table.
+    pub whole_country: usize,
+}
+
+/// Bounding box of the OSM house numbers of a relation.
+struct Boundary {
+    relation: String,
+    min_lat: f64,
+    min_lon: f64,
+    max_lat: f64,
+    max_lon: f64,
+}
+
+impl Boundary {
+    /// Decides if a location is inside the boundary.
+    fn contains(&self, lat: f64, lon: f64) -> bool {
+        self.min_lat <= lat && lat <= self.max_lat && self.min_lon <= lon && lon <= self.max_lon
+    }
+}
+
+/// Gets the relations which already have an object in a table.
+fn get_previous_relations(
+    tx: &rusqlite::Transaction<'_>,
+    table: &str,
+    element: &OscElement,
+) -> anyhow::Result<Vec<String>> {
+    let sql = format!("select relation from {table} where osm_id = ?1 and osm_type = ?2");
+    let mut stmt = tx.prepare(&sql)?;
+    let mut rows = stmt.query([element.id.to_string(), element.osm_type.to_string()])?;
+    let mut relations: Vec<String> = Vec::new();
+    while let Some(row) = rows.next()? {
+        relations.push(row.get(0)?);
+    }
+    Ok(relations)
+}
+
+/// Gets the location of an object: the location of a node or of the first known node of a way.
+fn get_location(element: &OscElement, locations: &HashMap<u64, (f64, f64)>) -> Option<(f64, f64)> {
+    match (element.lat, element.lon) {
+        (Some(lat), Some(lon)) => Some((lat, lon)),
+        _ => element.nodes.iter().find_map(|i| locations.get(i).cloned()),
+    }
+}
+
+/// Gets the relations where a new object may belong, based on the stored boundaries. Boundaries
+/// are only bounding boxes, so an object matching multiple relations is not assigned to any of them
+/// and is reported instead.
+fn get_boundary_relations(
+    tx: &rusqlite::Transaction<'_>,
+    boundaries: &[Boundary],
+    element: &OscElement,
+    locations: &HashMap<u64, (f64, f64)>,
+    summary: &mut OscSummary,
+) -> anyhow::Result<Vec<String>> {
+    let Some((lat, lon)) = get_location(element, locations) else {
+        return Ok(Vec::new());
+    };
+
+    let street = element.get_tag("addr:street");
+    let mut stmt =
+        tx.prepare("select count(*) from osm_streets where relation = ?1 and name = ?2")?;
+    let mut relations: Vec<String> = Vec::new();
+    for boundary in boundaries {
+        if !boundary.contains(lat, lon) {
+            continue;
+        }
+        // Boundaries may overlap, so an address also has to refer to a known street.
+        if !street.is_empty() {
+            let count: i64 = stmt.query_row([&boundary.relation, &street], |row| row.get(0))?;
+            if count == 0 {
+                continue;
+            }
+        }
+        relations.push(boundary.relation.to_string());
+    }
+    if relations.len() > 1 {
+        summary
+            .ambiguous
+            .insert(format!("{}/{}", element.osm_type, element.id));
+        relations.clear();
+    }
+    Ok(relations)
+}
+
+/// Applies the changes of an osmChange to the osm_streets, osm_housenumbers and whole_country
+/// tables. Changed objects stay in the relations where they were already known, new objects are
+/// placed using the stored boundaries. New objects are only added to whole_country if they are
+/// inside the union of the boundaries, so a diff covering more than the country doesn't pollute it.
+/// The changed relations are marked dirty, see get_dirty_page(). The osm_interpolations table is
+/// not updated: the house numbers of an interpolation way are on its end nodes, which are
+/// typically not part of the diff.
+pub fn apply_osc(ctx: &context::Context, elements: &[OscElement]) -> anyhow::Result<OscSummary> {
+    let mut summary = OscSummary::default();
+    let mut locations: HashMap<u64, (f64, f64)> = HashMap::new();
+    for element in elements {
+        if let (Some(lat), Some(lon)) = (element.lat, element.lon) {
+            locations.insert(element.id, (lat, lon));
+        }
+    }
+
+    {
+        let mut conn = ctx.get_database_connection()?;
+        let tx = conn.transaction()?;
+        let mut boundaries: Vec<Boundary> = Vec::new();
+        {
+            let mut stmt = tx.prepare(
+                "select relation, min_lat, min_lon, max_lat, max_lon from osm_boundaries order by relation",
+            )?;
+            let mut rows = stmt.query([])?;
+            while let Some(row) = rows.next()? {
+                boundaries.push(Boundary {
+                    relation: row.get(0)?,
+                    min_lat: row.get(1)?,
+                    min_lon: row.get(2)?,
+                    max_lat: row.get(3)?,
+                    max_lon: row.get(4)?,
+                });
+            }
+        }
+
+        for element in elements {
+            let exists = element.action != OscAction::Delete;
+            let osm_id = element.id.to_string();
+            let osm_type = element.osm_type.to_string();
+
+            let mut relations = get_previous_relations(&tx, "osm_streets", element)?;
+            if relations.is_empty() && exists && element.is_street() {
+                relations =
+                    get_boundary_relations(&tx, &boundaries, element, &locations, &mut summary)?;
+            }
+            for relation in relations {
+                tx.execute(
+                    "delete from osm_streets where relation = ?1 and osm_id = ?2 and osm_type = ?3",
+                    [&relation, &osm_id, &osm_type],
+                )?;
+                if exists && element.is_street() {
+                    tx.execute(
+                        "insert or ignore into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                        [
+                            relation.to_string(),
+                            osm_id.to_string(),
+                            element.get_tag("name"),
+                            element.get_tag("highway"),
+                            element.get_tag("service"),
+                            element.get_tag("surface"),
+                            element.get_tag("leisure"),
+                            osm_type.to_string(),
+                        ],
+                    )?;
+                }
+                summary.dirty.insert(format!("streets/{relation}"));
+            }
+
+            let mut relations = get_previous_relations(&tx, "osm_housenumbers", element)?;
+            if relations.is_empty() && exists && element.is_housenumber() {
+                relations =
+                    get_boundary_relations(&tx, &boundaries, element, &locations, &mut summary)?;
+            }
+            for relation in relations {
+                tx.execute(
+                    "delete from osm_housenumbers where relation = ?1 and osm_id = ?2 and osm_type = ?3",
+                    [&relation, &osm_id, &osm_type],
+                )?;
+                if exists && element.is_housenumber() {
+                    tx.execute(
+                        "insert or ignore into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+                        [
+                            relation.to_string(),
+                            osm_id.to_string(),
+                            element.get_tag("addr:street"),
+                            element.get_tag("addr:housenumber"),
+                            element.get_tag("addr:postcode"),
+                            element.get_tag("addr:place"),
+                            element.get_tag("addr:housename"),
+                            element.get_tag("addr:conscriptionnumber"),
+                            element.get_tag("addr:flats"),
+                            element.get_tag("addr:floor"),
+                            element.get_tag("addr:door"),
+                            element.get_tag("addr:unit"),
+                            element.get_tag("name"),
+                            osm_type.to_string(),
+                        ],
+                    )?;
+                }
+                summary.dirty.insert(format!("housenumbers/{relation}"));
+            }
+
+            let mut changed = tx.execute(
+                "delete from whole_country where osm_id = ?1 and osm_type = ?2",
+                [&osm_id, &osm_type],
+            )?;
+            // Objects already known are in the country, new ones have to be inside a boundary.
+            let inside = changed > 0
+                || get_location(element, &locations)
+                    .is_some_and(|(lat, lon)| boundaries.iter().any(|i| i.contains(lat, lon)));
+            if exists && inside && element.tags.contains_key("addr:housenumber") {
+                changed += tx.execute(
+                    "insert into whole_country (postcode, city, street, housenumber, user, osm_id, osm_type, timestamp, place, unit, name, fixme) values (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                    [
+                        element.get_tag("addr:postcode"),
+                        element.get_tag("addr:city"),
+                        element.get_tag("addr:street"),
+                        element.get_tag("addr:housenumber"),
+                        element.user.to_string(),
+                        osm_id.to_string(),
+                        osm_type.to_string(),
+                        element.timestamp.to_string(),
+                        element.get_tag("addr:place"),
+                        element.get_tag("addr:unit"),
+                        element.get_tag("name"),
+                        element.get_tag("fixme"),
+                    ],
+                )?;
+            }
+            if changed > 0 {
+                summary.whole_country += 1;
+            }
+        }
+        tx.commit()?;
+    }
+
+    // Only the touched relations have to be analyzed again.
+    for page in &summary.dirty {
+        stats::set_sql_mtime(ctx, &get_dirty_page(page))?;
+    }
+    Ok(summary)
+}
+
+/// Gets the sequence number of an osmChange file from its path or URL, e.g. 6123456 for
+/// .../006/123/456.osc or 1 for .../000001.osc.
+fn get_sequence_number(name: &str) -> anyhow::Result<u64> {
+    let stem = name.strip_suffix(".osc").unwrap_or(name);
+    let digits: Vec<&str> = stem
+        .rsplit('/')
+        .take_while(|i| !i.is_empty() && i.chars().all(|j| j.is_ascii_digit()))
+        .collect();
+    if digits.is_empty() {
+        return Err(anyhow::anyhow!(
+            "failed to get the sequence number of '{name}'"
+        ));
+    }
+
+    let number: String = digits.into_iter().rev().collect();
+    Ok(number.parse()?)
+}
+
+/// Gets the sequence number of the last applied osmChange file, 0 if there is none.
+fn get_last_sequence_number(ctx: &context::Context) -> anyhow::Result<u64> {
+    let conn = ctx.get_database_connection()?;
+    let mut stmt = conn.prepare("select count from counts where category = 'osc'")?;
+    let mut rows = stmt.query([])?;
+    match rows.next()? {
+        Some(row) => {
+            let count: String = row.get(0)?;
+            Ok(count.parse()?)
+        }
+        None => Ok(0),
+    }
+}
+
+/// Sets the sequence number of the last applied osmChange file.
+fn set_last_sequence_number(ctx: &context::Context, sequence_number: u64) -> anyhow::Result<()> {
+    let conn = ctx.get_database_connection()?;
+    conn.execute(
+        r#"insert into counts (category, count) values ('osc', ?1)
+             on conflict(category) do update set count = excluded.count"#,
+        [sequence_number.to_string()],
+    )?;
+    Ok(())
+}
+
+/// Gets the osmChange files to be applied from a directory or an URL: sequence numbers, names and
+/// contents, skipping the ones which were applied already.
+fn get_osc_sources(
+    ctx: &context::Context,
+    source: &str,
+) -> anyhow::Result<Vec<(u64, String, String)>> {
+    let last = get_last_sequence_number(ctx)?;
+    let mut sources: Vec<(u64, String, String)> = Vec::new();
+    if source.starts_with("http://") || source.starts_with("https://") {
+        let sequence_number = get_sequence_number(source)?;
+        if sequence_number > last {
+            let network = ctx.get_network();
+            let xml = network.urlopen(source, "", &HashMap::new())?;
+            sources.push((sequence_number, source.to_string(), xml));
+        }
+        return Ok(sources);
+    }
+
+    let mut paths: Vec<(u64, String)> = Vec::new();
+    for path in ctx.get_file_system().listdir(source)? {
+        if !path.ends_with(".osc") {
+            continue;
+        }
+        paths.push((get_sequence_number(&path)?, path));
+    }
+    // Numeric order, 1000.osc comes after 999.osc.
+    paths.sort();
+    for (sequence_number, path) in paths {
+        if sequence_number <= last {
+            continue;
+        }
+        let xml = ctx.get_file_system().read_to_string(&path)?;
+        sources.push((sequence_number, path, xml));
+    }
+    Ok(sources)
+}
+
+/// Inner main() that is allowed to fail.
+pub fn our_main(
+    argv: &[String],
+    stream: &mut dyn Write,
+    ctx: &context::Context,
+) -> anyhow::Result<()> {
+    let source = clap::Arg::new("source")
+        .required(true)
+        .help("directory of .osc files or URL of an .osc file");
+    let args = clap::Command::new("osm-gimmisn")
+        .arg(source)
+        .try_get_matches_from(argv)?;
+    let source: &String = args.get_one("source").unwrap();
+
+    for (sequence_number, name, xml) in get_osc_sources(ctx, source)? {
+        let elements = parse_osc(&xml).context(format!("failed to parse '{name}'"))?;
+        let summary = apply_osc(ctx, &elements)?;
+        set_last_sequence_number(ctx, sequence_number)?;
+        let count = elements.len();
+        let mut line = format!("{name}: {count} objects");
+        line += &format!(", {} whole country changes", summary.whole_country);
+        if !summary.dirty.is_empty() {
+            let dirty: Vec<String> = summary.dirty.into_iter().collect();
+            line += &format!(", dirty: {}", dirty.join(", "));
+        }
+        if !summary.ambiguous.is_empty() {
+            let ambiguous: Vec<String> = summary.ambiguous.into_iter().collect();
+            line += &format!(", ambiguous: {}", ambiguous.join(", "));
+        }
+        stream.write_all(format!("{line}\n").as_bytes())?;
+    }
+
+    ctx.get_unit().make_error()
+}
+
+/// Similar to plain main(), but with an interface that allows testing.
+pub fn main(argv: &[String], stream: &mut dyn Write, ctx: &context::Context) -> i32 {
+    match our_main(argv, stream, ctx) {
+        Ok(_) => 0,
+        Err(err) => {
+            stream.write_all(format!("{err:?}\n").as_bytes()).unwrap();
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
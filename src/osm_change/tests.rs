@@ -0,0 +1,365 @@
+/*
+ * Copyright 2025 Miklos Vajna
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+#![deny(warnings)]
+#![warn(clippy::all)]
+#![warn(missing_docs)]
+
+//! Tests for the osm_change module.
+
+use super::*;
+use std::io::Write;
+use std::rc::Rc;
+
+/// Tests parse_osc().
+#[test]
+fn test_parse_osc() {
+    let xml = r#"<?xml version='1.0' encoding='UTF-8'?>
+<osmChange version="0.6">
+  <create>
+    <node id="3" timestamp="2025-06-01T10:00:00Z" user="myuser" lat="47.05" lon="19.05">
+      <tag k="addr:housenumber" v="3"/>
+    </node>
+  </create>
+  <modify>
+    <way id="10">
+      <nd ref="1"/>
+      <nd ref="2"/>
+      <tag k="name" v="Foo utca"/>
+      <comment/>
+    </way>
+  </modify>
+  <delete>
+    <node id="2"/>
+  </delete>
+  <unknown/>
+</osmChange>"#;
+
+    let elements = parse_osc(xml).unwrap();
+
+    assert_eq!(elements.len(), 3);
+    assert_eq!(elements[0].action, OscAction::Create);
+    assert_eq!(elements[0].osm_type, "node");
+    assert_eq!(elements[0].id, 3);
+    assert_eq!(elements[0].user, "myuser");
+    assert_eq!(elements[0].timestamp, "2025-06-01T10:00:00Z");
+    assert_eq!(elements[0].lat, Some(47.05));
+    assert_eq!(elements[0].lon, Some(19.05));
+    assert_eq!(elements[0].get_tag("addr:housenumber"), "3");
+    assert_eq!(elements[1].action, OscAction::Modify);
+    assert_eq!(elements[1].osm_type, "way");
+    assert_eq!(elements[1].nodes, vec![1, 2]);
+    assert_eq!(elements[1].get_tag("name"), "Foo utca");
+    assert_eq!(elements[1].lat, None);
+    assert_eq!(elements[2].action, OscAction::Delete);
+    assert!(elements[2].tags.is_empty());
+}
+
+/// Tests parse_osc(): the error cases.
+#[test]
+fn test_parse_osc_error() {
+    assert!(parse_osc("<osmChange>").is_err());
+    assert!(parse_osc("<?xml version='1.0'?>").is_err());
+    assert!(parse_osc("<osm/>").is_err());
+    assert!(parse_osc("<osmChange><create><node/></create></osmChange>").is_err());
+    assert!(parse_osc(r#"<osmChange><create><node id="x"/></create></osmChange>"#).is_err());
+}
+
+/// Tests apply_osc().
+#[test]
+fn test_apply_osc() {
+    let ctx = context::tests::make_test_context().unwrap();
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into osm_boundaries (relation, min_lat, min_lon, max_lat, max_lon) values ('myrelation', '47.0', '19.0', '47.1', '19.1');
+             insert into osm_boundaries (relation, min_lat, min_lon, max_lat, max_lon) values ('otherrelation', '47.0', '19.0', '47.2', '19.2');
+             insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('myrelation', '10', 'Foo utca', 'residential', '', '', '', 'way');
+             insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('otherrelation', '20', 'Bar utca', 'residential', '', '', '', 'way');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '1', 'Foo utca', '1', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '2', 'Foo utca', '2', '', '', '', '', '', '', '', '', '', 'node');
+             insert into whole_country (postcode, city, street, housenumber, user, osm_id, osm_type, timestamp, place, unit, name, fixme) values ('', '', 'Foo utca', '1', '', '1', 'node', '', '', '', '', '');
+             insert into whole_country (postcode, city, street, housenumber, user, osm_id, osm_type, timestamp, place, unit, name, fixme) values ('', '', 'Foo utca', '2', '', '2', 'node', '', '', '', '', '');
+             insert into osm_interpolations (relation, osm_id, street, interpolation, start_number, end_number) values ('myrelation', '60', 'Foo utca', 'odd', '1', '7');",
+        )
+        .unwrap();
+    }
+    let xml = r#"<osmChange version="0.6">
+  <create>
+    <node id="3" lat="47.05" lon="19.05">
+      <tag k="addr:street" v="Foo utca"/>
+      <tag k="addr:housenumber" v="3"/>
+    </node>
+    <node id="4" lat="48.0" lon="20.0">
+      <tag k="addr:street" v="Foo utca"/>
+      <tag k="addr:housenumber" v="4"/>
+    </node>
+    <node id="5" lat="47.05" lon="19.05"/>
+    <node id="6" lat="47.05" lon="19.05">
+      <tag k="addr:place" v="Placeville"/>
+      <tag k="addr:postcode" v="1111"/>
+    </node>
+    <way id="30">
+      <nd ref="5"/>
+      <tag k="name" v="Baz utca"/>
+      <tag k="highway" v="residential"/>
+    </way>
+    <way id="40">
+      <nd ref="99"/>
+      <tag k="name" v="Qux utca"/>
+      <tag k="highway" v="residential"/>
+    </way>
+    <node id="8" lat="47.15" lon="19.15"/>
+    <way id="50">
+      <nd ref="8"/>
+      <tag k="name" v="Quux utca"/>
+      <tag k="highway" v="residential"/>
+    </way>
+  </create>
+  <modify>
+    <node id="1" lat="47.05" lon="19.05">
+      <tag k="addr:street" v="Foo utca"/>
+      <tag k="addr:housenumber" v="1/A"/>
+    </node>
+    <way id="10">
+      <nd ref="5"/>
+      <tag k="name" v="Foo utca"/>
+    </way>
+    <way id="60">
+      <nd ref="1"/>
+      <tag k="addr:interpolation" v="even"/>
+    </way>
+  </modify>
+  <delete>
+    <node id="2"/>
+  </delete>
+</osmChange>"#;
+    let elements = parse_osc(xml).unwrap();
+
+    let summary = apply_osc(&ctx, &elements).unwrap();
+
+    let dirty: Vec<String> = summary.dirty.into_iter().collect();
+    assert_eq!(
+        dirty,
+        vec![
+            "housenumbers/myrelation",
+            "streets/myrelation",
+            "streets/otherrelation"
+        ]
+    );
+    // Both boundaries contain these and they don't refer to a street.
+    let ambiguous: Vec<String> = summary.ambiguous.into_iter().collect();
+    assert_eq!(ambiguous, vec!["node/6", "way/30"]);
+    // 1 is modified, 2 is deleted, 3 is created; 4 is outside the boundaries.
+    assert_eq!(summary.whole_country, 3);
+    // Marked dirty, but not as updated from overpass.
+    assert!(stats::has_sql_mtime(&ctx, "dirty/streets/myrelation").unwrap());
+    assert!(!stats::has_sql_mtime(&ctx, "streets/myrelation").unwrap());
+    let conn = ctx.get_database_connection().unwrap();
+    let mut stmt = conn
+        .prepare("select relation, osm_id, name from osm_streets order by relation, osm_id")
+        .unwrap();
+    let streets: Vec<(String, String, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .unwrap()
+        .map(|i| i.unwrap())
+        .collect();
+    // Way 10 is no longer a street, way 30 is ambiguous using the location of node 5, way 40 has
+    // no location, way 50 is only inside the boundary of otherrelation.
+    assert_eq!(
+        streets,
+        vec![
+            ("otherrelation".into(), "20".into(), "Bar utca".into()),
+            ("otherrelation".into(), "50".into(), "Quux utca".into()),
+        ]
+    );
+    let mut stmt = conn
+        .prepare(
+            "select relation, osm_id, housenumber, postcode from osm_housenumbers order by relation, osm_id",
+        )
+        .unwrap();
+    let housenumbers: Vec<(String, String, String, String)> = stmt
+        .query_map([], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })
+        .unwrap()
+        .map(|i| i.unwrap())
+        .collect();
+    // Node 3 has a street which is only known in myrelation, node 4 is outside the boundaries,
+    // node 6 is ambiguous.
+    assert_eq!(
+        housenumbers,
+        vec![
+            ("myrelation".into(), "1".into(), "1/A".into(), "".into()),
+            ("myrelation".into(), "3".into(), "3".into(), "".into()),
+        ]
+    );
+    let mut stmt = conn
+        .prepare("select osm_id, housenumber from whole_country order by osm_id")
+        .unwrap();
+    let whole_country: Vec<(String, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap()
+        .map(|i| i.unwrap())
+        .collect();
+    assert_eq!(
+        whole_country,
+        vec![("1".into(), "1/A".into()), ("3".into(), "3".into()),]
+    );
+    // Interpolation ways are not updated from diffs.
+    let interpolation: String = conn
+        .query_row(
+            "select interpolation from osm_interpolations where osm_id = '60'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(interpolation, "odd");
+}
+
+/// Tests get_sequence_number().
+#[test]
+fn test_get_sequence_number() {
+    let url = "https://www.example.com/replication/006/123/456.osc";
+    assert_eq!(get_sequence_number(url).unwrap(), 6123456);
+    assert_eq!(get_sequence_number("/diffs/000001.osc").unwrap(), 1);
+    assert!(get_sequence_number("/diffs/foo.osc").is_err());
+}
+
+/// Tests main(): the diffs are read from a directory in numeric order, and each is applied only
+/// once.
+#[test]
+fn test_main_directory() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let osc = context::tests::TestFileSystem::make_file();
+    osc.borrow_mut()
+        .write_all(
+            std::fs::read_to_string("src/fixtures/network/osm-change.osc")
+                .unwrap()
+                .as_bytes(),
+        )
+        .unwrap();
+    let older_osc = context::tests::TestFileSystem::make_file();
+    older_osc
+        .borrow_mut()
+        .write_all(
+            br#"<osmChange version="0.6">
+  <modify>
+    <node id="1" lat="47.05" lon="19.05">
+      <tag k="addr:street" v="Foo utca"/>
+      <tag k="addr:housenumber" v="1/B"/>
+    </node>
+  </modify>
+  <create>
+    <node id="7" lat="47.05" lon="19.05">
+      <tag k="addr:postcode" v="1111"/>
+    </node>
+  </create>
+</osmChange>"#,
+        )
+        .unwrap();
+    let state = context::tests::TestFileSystem::make_file();
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[
+            ("workdir/diffs/1000.osc", &osc),
+            ("workdir/diffs/999.osc", &older_osc),
+            ("workdir/diffs/state.txt", &state),
+        ],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into osm_boundaries (relation, min_lat, min_lon, max_lat, max_lon) values ('myrelation', '47.0', '19.0', '47.1', '19.1');
+             insert into osm_boundaries (relation, min_lat, min_lon, max_lat, max_lon) values ('otherrelation', '47.0', '19.0', '47.1', '19.1');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '1', 'Foo utca', '1', '', '', '', '', '', '', '', '', '', 'node');",
+        )
+        .unwrap();
+    }
+    let dir = ctx.get_abspath("workdir/diffs");
+    let argv = vec!["".to_string(), dir.to_string()];
+    let mut buf: std::io::Cursor<Vec<u8>> = std::io::Cursor::new(Vec::new());
+
+    let ret = main(&argv, &mut buf, &ctx);
+
+    assert_eq!(ret, 0);
+    let output = String::from_utf8(buf.into_inner()).unwrap();
+    assert_eq!(
+        output,
+        format!(
+            "{dir}/999.osc: 2 objects, 1 whole country changes, dirty: housenumbers/myrelation, ambiguous: node/7\n{dir}/1000.osc: 1 objects, 1 whole country changes, dirty: housenumbers/myrelation\n"
+        )
+    );
+    assert_eq!(get_last_sequence_number(&ctx).unwrap(), 1000);
+
+    // Applied already, so nothing to do.
+    let mut buf: std::io::Cursor<Vec<u8>> = std::io::Cursor::new(Vec::new());
+    let ret = main(&argv, &mut buf, &ctx);
+    assert_eq!(ret, 0);
+    assert!(buf.into_inner().is_empty());
+}
+
+/// Tests main(): the diff is downloaded from an URL.
+#[test]
+fn test_main_url() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let url = "https://www.example.com/000001.osc";
+    let routes = vec![context::tests::URLRoute::new(
+        /*url=*/ url,
+        /*data_path=*/ "",
+        /*result_path=*/ "src/fixtures/network/osm-change.osc",
+    )];
+    let network = context::tests::TestNetwork::new(&routes);
+    let network_rc: Rc<dyn context::Network> = Rc::new(network);
+    ctx.set_network(network_rc);
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into osm_boundaries (relation, min_lat, min_lon, max_lat, max_lon) values ('myrelation', '47.0', '19.0', '47.1', '19.1');",
+        )
+        .unwrap();
+    }
+    let argv = vec!["".to_string(), url.to_string()];
+    let mut buf: std::io::Cursor<Vec<u8>> = std::io::Cursor::new(Vec::new());
+
+    let ret = main(&argv, &mut buf, &ctx);
+
+    assert_eq!(ret, 0);
+    let output = String::from_utf8(buf.into_inner()).unwrap();
+    assert_eq!(
+        output,
+        format!("{url}: 1 objects, 1 whole country changes\n")
+    );
+
+    // Applied already, so no network access.
+    let mut buf: std::io::Cursor<Vec<u8>> = std::io::Cursor::new(Vec::new());
+    let ret = main(&argv, &mut buf, &ctx);
+    assert_eq!(ret, 0);
+    assert!(buf.into_inner().is_empty());
+}
+
+/// Tests main(): the diff is not valid XML.
+#[test]
+fn test_main_error() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let osc = context::tests::TestFileSystem::make_file();
+    osc.borrow_mut().write_all(b"<osmChange>").unwrap();
+    let files = context::tests::TestFileSystem::make_files(&ctx, &[("workdir/diffs/1.osc", &osc)]);
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    let argv = vec!["".to_string(), ctx.get_abspath("workdir/diffs")];
+    let mut buf: std::io::Cursor<Vec<u8>> = std::io::Cursor::new(Vec::new());
+
+    let ret = main(&argv, &mut buf, &ctx);
+
+    assert_eq!(ret, 1);
+    let output = String::from_utf8(buf.into_inner()).unwrap();
+    assert!(output.starts_with("failed to parse '"));
+    // Not marked as applied.
+    assert_eq!(get_last_sequence_number(&ctx).unwrap(), 0);
+}
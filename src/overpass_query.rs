@@ -11,9 +11,11 @@
 //! The overpass_query module allows getting data out of the OSM DB without a full download.
 
 use crate::context;
+use crate::notify;
 use crate::util;
 use lazy_static::lazy_static;
 use std::collections::HashMap;
+use std::collections::VecDeque;
 
 lazy_static! {
     static ref USER_AGENT: String = format!(
@@ -37,17 +39,16 @@ pub fn overpass_query(ctx: &context::Context, query: &str) -> anyhow::Result<Str
     ctx.get_network().urlopen(&url, query, &headers)
 }
 
-/// Checks if we need to sleep before executing an overpass query.
-pub fn overpass_query_need_sleep(ctx: &context::Context) -> i32 {
+/// Gets the status of the overpass instance, which includes the rate limit info.
+fn overpass_query_status(ctx: &context::Context) -> anyhow::Result<String> {
     let url = ctx.get_ini().get_overpass_uri() + "/api/status";
     let mut headers = HashMap::new();
     headers.insert("User-Agent".to_string(), USER_AGENT.to_string());
-    let status = match ctx.get_network().urlopen(&url, "", &headers) {
-        Ok(value) => value,
-        _ => {
-            return 0;
-        }
-    };
+    ctx.get_network().urlopen(&url, "", &headers)
+}
+
+/// Parses the number of seconds to wait from an overpass status.
+fn parse_need_sleep(status: &str) -> i32 {
     let mut sleep = 0;
     let mut available = false;
     let re = regex::Regex::new(r".*in (-?\d+) seconds.*").unwrap();
@@ -74,12 +75,31 @@ pub fn overpass_query_need_sleep(ctx: &context::Context) -> i32 {
     sleep
 }
 
-/// Sleeps to respect overpass rate limit.
-fn overpass_sleep(ctx: &context::Context) {
+/// Parses the number of slots which are available now from an overpass status.
+fn parse_available_slots(status: &str) -> usize {
+    let re = regex::Regex::new(r"^(\d+) slots? available now").unwrap();
+    status
+        .lines()
+        .find_map(|line| re.captures(line))
+        .and_then(|cap| cap[1].parse::<usize>().ok())
+        .unwrap_or(0)
+}
+
+/// Checks if we need to sleep before executing an overpass query.
+pub fn overpass_query_need_sleep(ctx: &context::Context) -> i32 {
+    match overpass_query_status(ctx) {
+        Ok(status) => parse_need_sleep(&status),
+        _ => 0,
+    }
+}
+
+/// Sleeps to respect overpass rate limit. Returns the number of available slots, at least 1.
+fn overpass_sleep(ctx: &context::Context) -> usize {
     loop {
-        let sleep = overpass_query_need_sleep(ctx);
+        let status = overpass_query_status(ctx).unwrap_or_default();
+        let sleep = parse_need_sleep(&status);
         if sleep == 0 {
-            break;
+            return parse_available_slots(&status).max(1);
         }
         info!("overpass_sleep: waiting for {sleep} seconds");
         ctx.get_time().sleep(sleep as u64);
@@ -111,8 +131,74 @@ pub fn overpass_query_with_retry(ctx: &context::Context, query: &str) -> anyhow:
 
         return Ok(response);
     }
+    if let Err(ref err) = ret {
+        let message = format!("overpass query failed after {retry} tries: {err:?}");
+        notify::notify(ctx, notify::Event::Overpass, &message);
+    }
     ret
 }
 
+/// Runs multiple queries, several of them concurrently: as many as the overpass instance has
+/// available slots, but not more than the configured limit. Failed queries are retried. The
+/// results are handed over to `on_result` one by one, on the calling thread. If `deadline` is
+/// set, no new batch is started after it, so the rest of the queries are not reported.
+pub fn overpass_query_many(
+    ctx: &context::Context,
+    queries: &[String],
+    deadline: Option<time::OffsetDateTime>,
+    on_result: &mut dyn FnMut(usize, anyhow::Result<String>) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let max_parallel = ctx.get_ini().get_overpass_max_parallel()?.max(1);
+    let url = ctx.get_ini().get_overpass_uri() + "/api/interpreter";
+    let mut headers = HashMap::new();
+    headers.insert("User-Agent".to_string(), USER_AGENT.to_string());
+    let mut retries: Vec<i32> = vec![0; queries.len()];
+    let mut pending: VecDeque<usize> = (0..queries.len()).collect();
+    while !pending.is_empty() {
+        if let Some(deadline) = deadline
+            && ctx.get_time().now() >= deadline
+        {
+            info!(
+                "overpass_query_many: out of time, {} queries are not started",
+                pending.len()
+            );
+            break;
+        }
+        let slots = overpass_sleep(ctx);
+        let count = slots.min(max_parallel).min(pending.len());
+        let batch: Vec<usize> = pending.drain(..count).collect();
+        info!(
+            "overpass_query_many: running {count} queries, {} more pending",
+            pending.len()
+        );
+        let requests: Vec<context::NetworkRequest> = batch
+            .iter()
+            .map(|i| context::NetworkRequest {
+                url: url.clone(),
+                data: queries[*i].clone(),
+                headers: headers.clone(),
+            })
+            .collect();
+        let responses = ctx.get_network().urlopen_many(&requests);
+        for (index, response) in batch.into_iter().zip(responses) {
+            if let Err(ref err) = response {
+                info!("overpass_query_many: http error: {err}");
+                retries[index] += 1;
+                if should_retry(retries[index]) {
+                    pending.push_back(index);
+                    continue;
+                }
+                let message = format!(
+                    "overpass query failed after {} tries: {err:?}",
+                    retries[index]
+                );
+                notify::notify(ctx, notify::Event::Overpass, &message);
+            }
+            on_result(index, response)?;
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests;
@@ -11,6 +11,7 @@
 //! Tests for the overpass_query module.
 
 use super::*;
+use std::io::Write;
 use std::rc::Rc;
 
 /// Tests overpass_query_need_sleep().
@@ -135,3 +136,90 @@ fn test_overpass_sleep_need_sleep() {
         .unwrap();
     assert_eq!(time.get_sleep(), 12);
 }
+
+/// Tests parse_available_slots().
+#[test]
+fn test_parse_available_slots() {
+    let happy = std::fs::read_to_string("src/fixtures/network/overpass-status-happy.txt").unwrap();
+    assert_eq!(parse_available_slots(&happy), 2);
+    let wait = std::fs::read_to_string("src/fixtures/network/overpass-status-wait.txt").unwrap();
+    assert_eq!(parse_available_slots(&wait), 0);
+    assert_eq!(parse_available_slots("1 slot available now.\n"), 1);
+}
+
+/// Tests overpass_query_many(): 2 slots, the failed query is retried in the next batch.
+#[test]
+fn test_overpass_query_many() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let status = "src/fixtures/network/overpass-status-happy.txt";
+    let interpreter = "https://overpass-api.de/api/interpreter";
+    let routes = vec![
+        context::tests::URLRoute::new("https://overpass-api.de/api/status", "", status),
+        context::tests::URLRoute::new(interpreter, "", "src/fixtures/network/overpass-happy.csv"),
+        context::tests::URLRoute::new(interpreter, "", ""),
+        context::tests::URLRoute::new("https://overpass-api.de/api/status", "", status),
+        context::tests::URLRoute::new(interpreter, "", "src/fixtures/network/overpass-stats.csv"),
+        context::tests::URLRoute::new(interpreter, "", "src/fixtures/network/overpass.xml"),
+    ];
+    let network = context::tests::TestNetwork::new(&routes);
+    let network_rc: Rc<dyn context::Network> = Rc::new(network);
+    ctx.set_network(network_rc);
+    let queries: Vec<String> = vec!["q0".into(), "q1".into(), "q2".into()];
+    let mut results: Vec<(usize, String)> = Vec::new();
+
+    overpass_query_many(&ctx, &queries, None, &mut |index, result| {
+        results.push((index, result?));
+        Ok(())
+    })
+    .unwrap();
+
+    let indexes: Vec<usize> = results.iter().map(|(index, _)| *index).collect();
+    assert_eq!(indexes, vec![0, 2, 1]);
+    assert!(results[0].1.starts_with("@id"));
+    assert!(results[2].1.starts_with("<?xml"));
+}
+
+/// Tests overpass_query_many(): the query keeps failing, and the callback fails.
+#[test]
+fn test_overpass_query_many_error() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let wsgi_ini = context::tests::TestFileSystem::make_file();
+    wsgi_ini
+        .borrow_mut()
+        .write_all(b"[wsgi]\nreference_housenumbers = ''\nreference_street = ''\nreference_citycounts = ''\nreference_zipcounts = ''\noverpass_max_parallel = '1'\n")
+        .unwrap();
+    let files =
+        context::tests::TestFileSystem::make_files(&ctx, &[("workdir/wsgi.ini", &wsgi_ini)]);
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    ctx.update_ini().unwrap();
+    assert_eq!(ctx.get_ini().get_overpass_max_parallel().unwrap(), 1);
+    let queries: Vec<String> = vec!["q0".into()];
+    let mut calls = 0;
+
+    let ret = overpass_query_many(&ctx, &queries, None, &mut |_index, result| {
+        calls += 1;
+        result?;
+        Ok(())
+    });
+
+    assert!(ret.is_err());
+    assert_eq!(calls, 1);
+}
+
+/// Tests overpass_query_many(): the deadline is reached, no queries are started.
+#[test]
+fn test_overpass_query_many_deadline() {
+    let ctx = context::tests::make_test_context().unwrap();
+    let queries: Vec<String> = vec!["q0".into(), "q1".into()];
+    let deadline = Some(ctx.get_time().now());
+    let mut calls = 0;
+
+    overpass_query_many(&ctx, &queries, deadline, &mut |_index, _result| {
+        calls += 1;
+        Ok(())
+    })
+    .unwrap();
+
+    assert_eq!(calls, 0);
+}
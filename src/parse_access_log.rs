@@ -10,7 +10,6 @@
 
 //! Parses the Apache access log of osm-gimmisn for 1 month.
 
-use anyhow::Context;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::io::BufRead;
@@ -53,7 +52,7 @@ fn is_search_bot(line: &str) -> bool {
     false
 }
 
-/// Determine the top 20%: set of frequently visited relations.
+/// Counts the visits of relations and determines the top 20%: set of frequently visited relations.
 fn get_frequent_relations(
     ctx: &context::Context,
     log_file: &str,
@@ -88,27 +87,11 @@ fn get_frequent_relations(
         let entry = counts.entry(relation_name).or_insert(0);
         (*entry) += 1;
     }
-    let mut count_list: Vec<_> = counts.iter().collect();
-    // Reverse, by value.
-    count_list.sort_by(|a, b| b.1.cmp(a.1));
-
-    // Dump relations and their visit count to workdir for further inspection.
-    let csv_stream = ctx.get_file_system().open_write(&format!(
-        "{}/frequent-relations.csv",
-        ctx.get_ini().get_workdir()
-    ))?;
-    let mut guard = csv_stream.borrow_mut();
-    for item in count_list.iter() {
-        guard
-            .write_all(format!("{}\t{}\n", item.0, item.1).as_bytes())
-            .context("write_all() failed")?;
-    }
 
-    let relation_count = count_list.len() as f64;
-    let frequent_count = (relation_count * 0.2).round() as usize;
-    let count_list = &count_list[..frequent_count];
-    let frequent_relations: HashSet<String> = count_list.iter().map(|i| i.0.to_string()).collect();
-    Ok(frequent_relations)
+    // Store relations and their visit count, so cron can prioritize them.
+    stats::add_relation_visits(ctx, &counts)?;
+
+    Ok(stats::get_frequent_relations(&counts))
 }
 
 /// Builds a name -> create_date dictionary for relations.
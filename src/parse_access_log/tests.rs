@@ -120,13 +120,9 @@ author-time 1588975200\n\
         },
     });
     let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
-    let frequent_relations = context::tests::TestFileSystem::make_file();
     let files = context::tests::TestFileSystem::make_files(
         &ctx,
-        &[
-            ("data/yamls.cache", &yamls_cache_value),
-            ("workdir/frequent-relations.csv", &frequent_relations),
-        ],
+        &[("data/yamls.cache", &yamls_cache_value)],
     );
     let file_system = context::tests::TestFileSystem::from_files(&files);
     ctx.set_file_system(&file_system);
@@ -164,6 +160,12 @@ author-time 1588975200\n\
     // Also, if this would be not ignored, it would push 'inactiverelation' out of the active
     // relation list.
     assert_eq!(actual.contains("gyomaendrod"), false);
+
+    // Visit counts are stored in the database.
+    let visits = stats::get_relation_visits(&ctx).unwrap();
+    assert_eq!(visits["inactiverelation"], 3);
+    assert_eq!(visits["budafok"], 2);
+    assert!(!visits.contains_key("gyomaendrod"));
 }
 
 /// Tests main(), the failing case: missing required parameter.
@@ -0,0 +1,45 @@
+/*
+ * Copyright 2025 Miklos Vajna
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+#![deny(warnings)]
+#![warn(clippy::all)]
+#![warn(missing_docs)]
+
+//! Proposes filters for a relation, inferring street ranges from the OSM house numbers.
+
+use crate::areas;
+use crate::context;
+use std::io::Write;
+
+/// Inner main() that is allowed to fail.
+pub fn our_main(
+    argv: &[String],
+    stream: &mut dyn Write,
+    ctx: &context::Context,
+) -> anyhow::Result<()> {
+    let relation_name = argv[1].clone();
+
+    let mut relations = areas::Relations::new(ctx)?;
+    let mut relation = relations.get_relation(&relation_name)?;
+    let filters = relation.get_proposed_filters()?;
+    stream.write_all(areas::format_proposed_filters_yaml(&filters).as_bytes())?;
+
+    ctx.get_unit().make_error()
+}
+
+/// Similar to plain main(), but with an interface that allows testing.
+pub fn main(argv: &[String], stream: &mut dyn Write, ctx: &context::Context) -> i32 {
+    match our_main(argv, stream, ctx) {
+        Ok(_) => 0,
+        Err(err) => {
+            stream.write_all(format!("{err:?}\n").as_bytes()).unwrap();
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -10,12 +10,39 @@
 
 //! The ranges module contains functionality related to the Ranges class.
 
+use anyhow::Context as _;
+use lazy_static::lazy_static;
+
+lazy_static! {
+    static ref BOUND: regex::Regex = regex::Regex::new(r"^([0-9]+)/?([A-Za-z])?$").unwrap();
+}
+
+/// Parses a range bound or an except item like '20', '20/B' or '20b' into a number and an
+/// optional letter. Leading and trailing whitespace is ignored.
+pub fn parse_bound(value: &str) -> anyhow::Result<(i64, Option<char>)> {
+    let cap = BOUND
+        .captures(value.trim())
+        .context(format!("failed to parse '{value}' as '42' or '42/B'"))?;
+    let number: i64 = cap[1].parse()?;
+    let letter = cap
+        .get(2)
+        .and_then(|i| i.as_str().chars().next())
+        .map(|i| i.to_ascii_uppercase());
+    Ok((number, letter))
+}
+
 /// A range object represents an odd or even range of integer numbers.
 #[derive(Clone, Debug)]
 pub struct Range {
     start: i64,
     end: i64,
     is_odd: Option<bool>,
+    /// Letter of the start, e.g. 'B' for '2/B': lower letters of the start number are excluded.
+    start_letter: Option<char>,
+    /// Letter of the end, e.g. 'B' for '20/B': higher letters of the end number are excluded.
+    end_letter: Option<char>,
+    /// Numbers (with an optional letter) which are excluded, despite being in the range.
+    except: Vec<(i64, Option<char>)>,
 }
 
 impl Range {
@@ -24,21 +51,79 @@ impl Range {
         if interpolation == "all" {
             is_odd = None
         }
-        Range { start, end, is_odd }
+        Range {
+            start,
+            end,
+            is_odd,
+            start_letter: None,
+            end_letter: None,
+            except: Vec::new(),
+        }
     }
 
-    fn contains(&self, item: i64) -> bool {
+    /// Sets the letters of the start and the end.
+    pub fn with_letters(mut self, start_letter: Option<char>, end_letter: Option<char>) -> Self {
+        self.start_letter = start_letter;
+        self.end_letter = end_letter;
+        self
+    }
+
+    /// Sets the excluded numbers.
+    pub fn with_except(mut self, except: Vec<(i64, Option<char>)>) -> Self {
+        self.except = except;
+        self
+    }
+
+    /// Checks a number, ignoring the letters of the bounds and the except items with a letter.
+    fn contains_number(&self, item: i64) -> bool {
         if let Some(is_odd) = self.is_odd
             && is_odd != (item % 2 == 1)
         {
             return false;
         }
 
-        if self.start <= item && item <= self.end {
-            return true;
+        self.start <= item && item <= self.end
+    }
+
+    /// Checks a number without a letter: a letter of the start excludes the bare start number,
+    /// e.g. '2' is before '2/B'. The letter of the end is ignored, as '20' is before '20/B'.
+    fn contains(&self, item: i64) -> bool {
+        if !self.contains_number(item) {
+            return false;
         }
 
-        false
+        if self.except.contains(&(item, None)) {
+            return false;
+        }
+
+        !(self.start_letter.is_some() && item == self.start)
+    }
+
+    /// Checks a number with a letter, e.g. 20/C.
+    fn contains_letter(&self, item: i64, letter: char) -> bool {
+        if !self.contains_number(item) {
+            return false;
+        }
+
+        if self.except.contains(&(item, None)) || self.except.contains(&(item, Some(letter))) {
+            return false;
+        }
+
+        if let Some(start_letter) = self.start_letter
+            && item == self.start
+            && letter < start_letter
+        {
+            return false;
+        }
+
+        if let Some(end_letter) = self.end_letter
+            && item == self.end
+            && letter > end_letter
+        {
+            return false;
+        }
+
+        true
     }
 }
 
@@ -56,7 +141,11 @@ impl PartialEq for Range {
             return false;
         }
 
-        true
+        if self.start_letter != other.start_letter || self.end_letter != other.end_letter {
+            return false;
+        }
+
+        self.except == other.except
     }
 }
 
@@ -80,6 +169,11 @@ impl Ranges {
 
         false
     }
+
+    /// Same as contains(), but for a number with a letter, e.g. 20/C.
+    pub fn contains_letter(&self, item: i64, letter: char) -> bool {
+        self.items.iter().any(|i| i.contains_letter(item, letter))
+    }
 }
 
 impl PartialEq for Ranges {
@@ -66,7 +66,7 @@ fn test_range_traits() {
     let range = make_range(1, 3);
     assert_eq!(
         format!("{range:?}"),
-        "Range { start: 1, end: 3, is_odd: Some(true) }"
+        "Range { start: 1, end: 3, is_odd: Some(true), start_letter: None, end_letter: None, except: [] }"
     );
     let range2 = range.clone();
     assert_eq!(range2, range);
@@ -104,6 +104,59 @@ fn test_ranges_none() {
 #[test]
 fn test_ranges_traits() {
     let ranges = Ranges::new(vec![make_range(0, 0), make_range(1, 1)]);
-    let expected = "Ranges { items: [Range { start: 0, end: 0, is_odd: Some(false) }, Range { start: 1, end: 1, is_odd: Some(true) }] }";
+    let expected = "Ranges { items: [Range { start: 0, end: 0, is_odd: Some(false), start_letter: None, end_letter: None, except: [] }, Range { start: 1, end: 1, is_odd: Some(true), start_letter: None, end_letter: None, except: [] }] }";
     assert_eq!(format!("{ranges:?}"), expected);
 }
+
+/// Tests parse_bound().
+#[test]
+fn test_parse_bound() {
+    assert_eq!(parse_bound("20").unwrap(), (20, None));
+    assert_eq!(parse_bound("20/B").unwrap(), (20, Some('B')));
+    assert_eq!(parse_bound("20b").unwrap(), (20, Some('B')));
+    assert_eq!(parse_bound("20/b").unwrap(), (20, Some('B')));
+    assert_eq!(parse_bound(" 20 ").unwrap(), (20, None));
+    assert!(parse_bound("20 / B").is_err());
+    assert_eq!(parse_bound("x").is_err(), true);
+}
+
+/// Range: Tests except items.
+#[test]
+fn test_range_except() {
+    let test = make_range(1, 51).with_except(vec![(13, None), (15, Some('A'))]);
+    assert_eq!(test.contains(13), false);
+    assert_eq!(test.contains(15), true);
+    assert_eq!(test.contains_letter(13, 'A'), false);
+    assert_eq!(test.contains_letter(15, 'A'), false);
+    assert_eq!(test.contains_letter(15, 'B'), true);
+    assert_eq!(test != make_range(1, 51), true);
+}
+
+/// Range: Tests lettered bounds.
+#[test]
+fn test_range_letters() {
+    let test = make_range(2, 20).with_letters(Some('B'), Some('B'));
+    // 2 is before 2/B, but 20 is before 20/B.
+    assert_eq!(test.contains(2), false);
+    assert!(test.contains(4));
+    assert_eq!(test.contains(20), true);
+    assert_eq!(test.contains_letter(2, 'A'), false);
+    assert_eq!(test.contains_letter(2, 'B'), true);
+    assert_eq!(test.contains_letter(10, 'Z'), true);
+    assert_eq!(test.contains_letter(20, 'B'), true);
+    assert_eq!(test.contains_letter(20, 'C'), false);
+    assert_eq!(test.contains_letter(22, 'A'), false);
+    assert_eq!(test != make_range(2, 20), true);
+}
+
+/// Ranges: Tests contains_letter().
+#[test]
+fn test_ranges_contains_letter() {
+    let test = Ranges::new(vec![
+        make_range(1, 1).with_letters(None, Some('A')),
+        make_range(2, 2),
+    ]);
+    assert_eq!(test.contains_letter(1, 'A'), true);
+    assert_eq!(test.contains_letter(1, 'B'), false);
+    assert_eq!(test.contains_letter(2, 'B'), true);
+}
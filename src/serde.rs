@@ -42,6 +42,8 @@ pub struct OverpassTags {
     pub unit: Option<String>,
     #[serde(rename(deserialize = "addr:city"))]
     pub city: Option<String>,
+    #[serde(rename(deserialize = "addr:interpolation"))]
+    pub interpolation: Option<String>,
     // endregion housenumbers
     pub fixme: Option<String>,
 }
@@ -55,6 +57,12 @@ pub struct OverpassElement {
     pub user: Option<String>,
     pub timestamp: Option<String>,
     pub tags: OverpassTags,
+    /// Node IDs of a way.
+    pub nodes: Option<Vec<u64>>,
+    /// Latitude of a node.
+    pub lat: Option<f64>,
+    /// Longitude of a node.
+    pub lon: Option<f64>,
 }
 
 #[derive(serde::Deserialize)]
@@ -362,7 +362,114 @@ pub fn init(conn: &mut rusqlite::Connection) -> anyhow::Result<()> {
         )?;
     }
 
-    tx.execute("pragma user_version = 21", [])?;
+    if user_version < 22 {
+        // Tracks addr:interpolation ways from OSM for a relation.
+        tx.execute_batch(
+            "create table osm_interpolations (
+                    relation text not null,
+                    osm_id text not null,
+                    street text not null,
+                    interpolation text not null,
+                    start_number text not null,
+                    end_number text not null,
+                    unique(relation, osm_id)
+                );
+            create index idx_osm_interpolations
+                on osm_interpolations(relation);",
+        )?;
+    }
+
+    if user_version < 23 {
+        // Postcode of a reference house number, if the reference has it.
+        tx.execute(
+            "alter table ref_housenumbers add column postcode text not null default ''",
+            [],
+        )?;
+    }
+
+    if user_version < 24 {
+        // Bounding box of the OSM house numbers of a relation, and lookup by OSM ID, for applying
+        // replication diffs.
+        tx.execute_batch(
+            "create table osm_boundaries (
+                    relation text not null,
+                    min_lat real not null,
+                    min_lon real not null,
+                    max_lat real not null,
+                    max_lon real not null,
+                    unique(relation)
+                );
+            create index idx_osm_streets_osm_id
+                on osm_streets(osm_id);
+            create index idx_osm_housenumbers_osm_id
+                on osm_housenumbers(osm_id);
+            create index idx_whole_country_osm_id
+                on whole_country(osm_id);",
+        )?;
+    }
+
+    if user_version < 25 {
+        // Tracks the runs of the cron job.
+        tx.execute(
+            "create table cron_runs (
+                    id integer primary key autoincrement,
+                    start text not null,
+                    end text not null,
+                    mode text not null,
+                    filters text not null,
+                    stages text not null,
+                    failed_relations text not null,
+                    vm_peak text not null,
+                    error text not null
+                )",
+            [],
+        )?;
+    }
+
+    if user_version < 26 {
+        // Visit counts of relations, from the access log and from on-demand updates.
+        tx.execute(
+            "create table relation_visits (
+                    relation text not null,
+                    count integer not null,
+                    unique(relation)
+                )",
+            [],
+        )?;
+    }
+
+    if user_version < 27 {
+        // Rate limiting of failure notifications: the last sent one and the suppressed ones since.
+        tx.execute(
+            "create table notifications (
+                    kind text not null,
+                    last_sent text not null,
+                    suppressed text not null,
+                    unique(kind)
+                )",
+            [],
+        )?;
+    }
+
+    if user_version < 28 {
+        // The other street of misattributed street lints.
+        tx.execute(
+            "alter table relation_lints add column
+            other_street text not null default ''",
+            [],
+        )?;
+    }
+
+    if user_version < 29 {
+        // A queued notification, to be sent later by cron.
+        tx.execute(
+            "alter table notifications add column
+            pending text not null default ''",
+            [],
+        )?;
+    }
+
+    tx.execute("pragma user_version = 29", [])?;
     tx.commit().context("commit() failed")?;
     Ok(())
 }
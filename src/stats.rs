@@ -15,6 +15,7 @@ use crate::sql;
 use crate::util;
 use anyhow::Context;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::ops::DerefMut;
 
 #[cfg(not(test))]
@@ -428,6 +429,51 @@ fn handle_monthly_total(
     Ok(())
 }
 
+/// Downsamples the rows of the stats tables which are older than the retention window to monthly
+/// snapshots: only the rows of the first day of each month are kept, which is what the monthly
+/// handlers read. Returns the number of removed rows.
+pub fn downsample_stats(ctx: &context::Context) -> anyhow::Result<usize> {
+    // get_topcities() compares today with 30 days ago, so keep at least that much.
+    let days = ctx.get_ini().get_stats_retention_days()?.max(31);
+    let ymd = time::format_description::parse_borrowed::<1>("[year]-[month]-[day]")?;
+    let cutoff = (ctx.get_time().now() - time::Duration::days(days)).format(&ymd)?;
+    let tables = [
+        ("stats_counts", None),
+        ("stats_usercounts", None),
+        ("stats_citycounts", Some("city")),
+        ("stats_zipcounts", Some("zip")),
+        ("stats_topusers", Some("user")),
+    ];
+    let mut removed = 0;
+    let mut conn = ctx.get_database_connection()?;
+    let tx = conn.transaction()?;
+    for (table, key) in tables {
+        let same_key = match key {
+            Some(key) => format!(" and old.{key} = {table}.{key}"),
+            None => "".to_string(),
+        };
+        // If there is no row for the first day of a month, then the earliest row of the month
+        // becomes the snapshot.
+        let sql = format!(
+            r#"update {table} set date = substr(date, 1, 8) || '01'
+            where date < ?1
+            and date = (select min(old.date) from {table} old
+                where substr(old.date, 1, 7) = substr({table}.date, 1, 7){same_key})
+            and not exists (select 1 from {table} old
+                where old.date = substr({table}.date, 1, 8) || '01'{same_key})"#
+        );
+        tx.execute(&sql, [&cutoff])?;
+        let sql = format!("delete from {table} where date < ?1 and substr(date, 9, 2) != '01'");
+        removed += tx.execute(&sql, [&cutoff])?;
+    }
+    tx.commit()?;
+    // Vacuum rewrites the whole database, so only do it when there is space to reclaim.
+    if removed > 0 {
+        conn.execute_batch("vacuum")?;
+    }
+    Ok(removed)
+}
+
 /// Generates the stats json and writes it to `json_path`.
 pub fn generate_json(ctx: &context::Context, json_path: &str) -> anyhow::Result<()> {
     let mut j = serde_json::json!({});
@@ -524,6 +570,65 @@ pub fn has_sql_count(ctx: &context::Context, table: &str, relation: &str) -> any
     Ok(rows.next()?.is_some())
 }
 
+/// Gets the visit count of each relation.
+pub fn get_relation_visits(ctx: &context::Context) -> anyhow::Result<HashMap<String, u64>> {
+    let conn = ctx.get_database_connection()?;
+    let mut stmt = conn.prepare("select relation, count from relation_visits")?;
+    let mut rows = stmt.query([])?;
+    let mut ret = HashMap::new();
+    while let Some(row) = rows.next()? {
+        let relation: String = row.get(0)?;
+        let count: i64 = row.get(1)?;
+        ret.insert(relation, u64::try_from(count)?);
+    }
+    Ok(ret)
+}
+
+/// Adds new visit counts of relations. The existing counts are halved first, so old visits age out
+/// and relations without visits are eventually removed.
+pub fn add_relation_visits(
+    ctx: &context::Context,
+    visits: &HashMap<String, u64>,
+) -> anyhow::Result<()> {
+    let mut conn = ctx.get_database_connection()?;
+    let tx = conn.transaction()?;
+    tx.execute_batch(
+        "update relation_visits set count = count / 2;
+         delete from relation_visits where count = 0;",
+    )?;
+    for (relation, count) in visits {
+        let sql = r#"insert into relation_visits (relation, count) values (?1, ?2)
+             on conflict(relation) do update set count = count + excluded.count"#;
+        tx.execute(sql, (relation, i64::try_from(*count)?))?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Increments the visit count of a relation.
+pub fn add_relation_visit(ctx: &context::Context, relation: &str) -> anyhow::Result<()> {
+    let conn = ctx.get_database_connection()?;
+    conn.execute(
+        r#"insert into relation_visits (relation, count) values (?1, 1)
+             on conflict(relation) do update set count = count + 1"#,
+        [relation],
+    )?;
+    Ok(())
+}
+
+/// Determines the top 20%: set of frequently visited relations.
+pub fn get_frequent_relations(visits: &HashMap<String, u64>) -> HashSet<String> {
+    let mut count_list: Vec<_> = visits.iter().collect();
+    // Reverse, by value, then by name to be deterministic.
+    count_list.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    let relation_count = count_list.len() as f64;
+    let frequent_count = (relation_count * 0.2).round() as usize;
+    count_list[..frequent_count]
+        .iter()
+        .map(|i| i.0.to_string())
+        .collect()
+}
+
 pub fn set_sql_json(
     ctx: &context::Context,
     table: &str,
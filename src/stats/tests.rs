@@ -422,3 +422,139 @@ fn test_update_invalid_addr_cities() {
         assert_eq!(count, 1);
     }
 }
+
+/// Tests add_relation_visit() and get_relation_visits().
+#[test]
+fn test_add_relation_visit() {
+    let ctx = context::tests::make_test_context().unwrap();
+
+    add_relation_visit(&ctx, "gazdagret").unwrap();
+    add_relation_visit(&ctx, "gazdagret").unwrap();
+    add_relation_visit(&ctx, "ujbuda").unwrap();
+
+    let visits = get_relation_visits(&ctx).unwrap();
+    assert_eq!(visits.len(), 2);
+    assert_eq!(visits["gazdagret"], 2);
+    assert_eq!(visits["ujbuda"], 1);
+}
+
+/// Tests add_relation_visits(): old counts are halved, then the new counts are added.
+#[test]
+fn test_add_relation_visits() {
+    let ctx = context::tests::make_test_context().unwrap();
+    for _ in 0..3 {
+        add_relation_visit(&ctx, "gazdagret").unwrap();
+    }
+    add_relation_visit(&ctx, "ujbuda").unwrap();
+    let visits: HashMap<String, u64> = [("gazdagret".to_string(), 1), ("budafok".to_string(), 2)]
+        .into_iter()
+        .collect();
+
+    add_relation_visits(&ctx, &visits).unwrap();
+
+    let visits = get_relation_visits(&ctx).unwrap();
+    assert_eq!(visits.len(), 2);
+    // 3 / 2 + 1.
+    assert_eq!(visits["gazdagret"], 2);
+    assert_eq!(visits["budafok"], 2);
+    // 1 / 2 is 0, so removed.
+    assert!(!visits.contains_key("ujbuda"));
+}
+
+/// Tests get_frequent_relations().
+#[test]
+fn test_get_frequent_relations() {
+    let visits: HashMap<String, u64> = [
+        ("a".to_string(), 5),
+        ("b".to_string(), 1),
+        ("c".to_string(), 3),
+        ("d".to_string(), 2),
+        ("e".to_string(), 3),
+        ("f".to_string(), 1),
+        ("g".to_string(), 1),
+        ("h".to_string(), 1),
+        ("i".to_string(), 1),
+        ("j".to_string(), 1),
+    ]
+    .into_iter()
+    .collect();
+
+    let actual = get_frequent_relations(&visits);
+
+    // Top 20% of 10 is 2, ties are broken by name.
+    let expected: HashSet<String> = ["a".to_string(), "c".to_string()].into_iter().collect();
+    assert_eq!(actual, expected);
+}
+
+/// Tests downsample_stats(): old daily rows are removed, the monthly handlers still work.
+#[test]
+fn test_downsample_stats() {
+    let ctx = context::tests::make_test_context().unwrap();
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        let mut day = time::macros::date!(2019 - 04 - 20);
+        let mut count = 200000;
+        while day <= time::macros::date!(2020 - 05 - 10) {
+            let sql = "insert into stats_counts (date, count) values (?1, ?2)";
+            conn.execute(sql, [day.to_string(), count.to_string()])
+                .unwrap();
+            day = day.next_day().unwrap();
+            count += 10;
+        }
+        conn.execute_batch(
+            "insert into stats_citycounts (date, city, count) values ('2019-06-01', 'budapest', '10');
+             insert into stats_citycounts (date, city, count) values ('2019-06-02', 'budapest', '11');
+             insert into stats_citycounts (date, city, count) values ('2019-06-03', 'szeged', '5');
+             insert into stats_citycounts (date, city, count) values ('2019-06-04', 'szeged', '6');
+             insert into stats_citycounts (date, city, count) values ('2020-05-09', 'szeged', '7');",
+        )
+        .unwrap();
+    }
+    let mut expected = serde_json::json!({});
+    handle_monthly_new(&ctx, &mut expected, /*month_range=*/ 12).unwrap();
+    handle_monthly_total(&ctx, &mut expected, /*month_range=*/ 11).unwrap();
+
+    let removed = downsample_stats(&ctx).unwrap();
+
+    let mut actual = serde_json::json!({});
+    handle_monthly_new(&ctx, &mut actual, /*month_range=*/ 12).unwrap();
+    handle_monthly_total(&ctx, &mut actual, /*month_range=*/ 11).unwrap();
+    assert_eq!(actual, expected);
+    assert_eq!(expected["monthly"].as_array().unwrap().len(), 13);
+    // Already downsampled, nothing to remove.
+    assert_eq!(downsample_stats(&ctx).unwrap(), 0);
+    let conn = ctx.get_database_connection().unwrap();
+    let mut stmt = conn
+        .prepare("select date from stats_counts where date < '2020-04-10' order by date")
+        .unwrap();
+    let dates: Vec<String> = stmt
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .map(|i| i.unwrap())
+        .collect();
+    // 2019-04-20 is the earliest row of its month, so it becomes a snapshot, then 2019-05-01 ..
+    // 2020-04-01, then the daily rows of the 31 days window (the minimum window).
+    assert_eq!(dates.len(), 14);
+    assert_eq!(dates[0], "2019-04-01");
+    assert_eq!(dates[1], "2019-05-01");
+    assert_eq!(dates[12], "2020-04-01");
+    assert_eq!(dates[13], "2020-04-09");
+    let mut stmt = conn
+        .prepare("select date, city, count from stats_citycounts order by date, city")
+        .unwrap();
+    let citycounts: Vec<(String, String, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .unwrap()
+        .map(|i| i.unwrap())
+        .collect();
+    assert_eq!(
+        citycounts,
+        vec![
+            ("2019-06-01".into(), "budapest".into(), "10".into()),
+            ("2019-06-01".into(), "szeged".into(), "5".into()),
+            ("2020-05-09".into(), "szeged".into(), "7".into()),
+        ]
+    );
+    // 2019-04-20 .. 2020-04-08 is 355 days, 13 snapshots are kept, plus 2 city counts.
+    assert_eq!(removed, 355 - 13 + 2);
+}
@@ -0,0 +1,46 @@
+/*
+ * Copyright 2025 Miklos Vajna
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+#![deny(warnings)]
+#![warn(clippy::all)]
+#![warn(missing_docs)]
+
+//! Suggests refstreets entries for a relation, pairing missing and additional streets.
+
+use crate::areas;
+use crate::context;
+use crate::util;
+use std::io::Write;
+
+/// Inner main() that is allowed to fail.
+pub fn our_main(
+    argv: &[String],
+    stream: &mut dyn Write,
+    ctx: &context::Context,
+) -> anyhow::Result<()> {
+    let relation_name = argv[1].clone();
+
+    let mut relations = areas::Relations::new(ctx)?;
+    let relation = relations.get_relation(&relation_name)?;
+    let suggestions = relation.get_refstreet_suggestions()?;
+    stream.write_all(util::format_refstreets_yaml(&suggestions).as_bytes())?;
+
+    ctx.get_unit().make_error()
+}
+
+/// Similar to plain main(), but with an interface that allows testing.
+pub fn main(argv: &[String], stream: &mut dyn Write, ctx: &context::Context) -> i32 {
+    match our_main(argv, stream, ctx) {
+        Ok(_) => 0,
+        Err(err) => {
+            stream.write_all(format!("{err:?}\n").as_bytes()).unwrap();
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
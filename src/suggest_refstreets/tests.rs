@@ -0,0 +1,90 @@
+/*
+ * Copyright 2025 Miklos Vajna
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+#![deny(warnings)]
+#![warn(clippy::all)]
+#![warn(missing_docs)]
+
+//! Tests for the suggest_refstreets module.
+
+use super::*;
+use std::io::Read;
+use std::io::Seek;
+use std::rc::Rc;
+
+/// Tests main().
+#[test]
+fn test_main() {
+    let argv = vec!["".to_string(), "myrelation".to_string()];
+    let mut buf: std::io::Cursor<Vec<u8>> = std::io::Cursor::new(Vec::new());
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "01",
+                "refsettlement": "011",
+                "osmrelation": 42,
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into ref_streets (county_code, settlement_code, street) values ('01', '011', 'Vizöntő utca');
+             insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('myrelation', '1', 'Vízöntő u.', '', '', '', '', 'way');",
+        )
+        .unwrap();
+    }
+
+    let ret = main(&argv, &mut buf, &ctx);
+
+    assert_eq!(ret, 0);
+    buf.rewind().unwrap();
+    let mut actual: Vec<u8> = Vec::new();
+    buf.read_to_end(&mut actual).unwrap();
+    assert_eq!(
+        String::from_utf8(actual).unwrap(),
+        "refstreets:\n  'Vízöntő u.': 'Vizöntő utca'\n"
+    );
+}
+
+/// Tests main(), the failing case.
+#[test]
+fn test_main_error() {
+    let argv = vec!["".to_string(), "myrelation".to_string()];
+    let mut buf: std::io::Cursor<Vec<u8>> = std::io::Cursor::new(Vec::new());
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "01",
+                "refsettlement": "011",
+                "osmrelation": 42,
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    let unit = context::tests::TestUnit::new();
+    let unit_rc: Rc<dyn context::Unit> = Rc::new(unit);
+    ctx.set_unit(&unit_rc);
+
+    let ret = main(&argv, &mut buf, &ctx);
+
+    assert_eq!(ret, 1);
+}
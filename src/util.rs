@@ -30,18 +30,23 @@ lazy_static! {
         regex::Regex::new(r"^([0-9]+)( |/)?([A-Za-z])$").unwrap();
     static ref NUMBER_PER_NUMBER: regex::Regex =
         regex::Regex::new(r"^([0-9]+)(/)([0-9])$").unwrap();
+    static ref LETTER_RANGE: regex::Regex =
+        regex::Regex::new(r"^([0-9]+) ?/? ?([A-Za-z]) ?- ?(?:([0-9]+) ?/? ?)?([A-Za-z])$").unwrap();
     static ref NUMBER_WITH_JUNK: regex::Regex = regex::Regex::new(r"([0-9]+).*").unwrap();
     static ref NUMBER_WITH_REMAINDER: regex::Regex =
         regex::Regex::new(r"^([0-9]*)([^0-9].*|)$").unwrap();
     static ref LETTER_SUFFIX: regex::Regex = regex::Regex::new(r".*([A-Za-z]+)\*?").unwrap();
     static ref NUMBER_SUFFIX: regex::Regex = regex::Regex::new(r"^.*/([0-9])\*?$").unwrap();
-    static ref NULL_END: regex::Regex = regex::Regex::new(r" null$").unwrap();
     static ref GIT_HASH: regex::Regex = regex::Regex::new(r".*-g([0-9a-f]+)(-modified)?").unwrap();
 }
 
 /// The version of the application, from git.
 pub const VERSION: &str = git_version::git_version!(args = ["--always", "--long"]);
 
+/// The largest difference between the endpoints of an addr:interpolation way which is still
+/// expanded to house numbers.
+const MAX_INTERPOLATION_SPAN: i64 = 1000;
+
 /// A house number range is a string that may expand to one or more HouseNumber instances in the
 /// future. It can also have a comment.
 #[derive(Clone, Debug)]
@@ -363,6 +368,39 @@ impl HouseNumber {
         Ok(ret)
     }
 
+    /// Parses a letter range like '12/A-12/D' or '12a-d' into a number and the first and last
+    /// letter. Returns Ok(None) if the input is not a letter range and an error if it looks like
+    /// one, but it's malformed, e.g. '12/D-12/A' or '12/A-14/B'.
+    pub fn parse_letter_range(
+        house_number: &str,
+        source_suffix: &str,
+    ) -> anyhow::Result<Option<(i64, char, char)>> {
+        let house_number = &house_number[..house_number.len() - source_suffix.len()];
+        let cap = match LETTER_RANGE.captures(house_number) {
+            Some(value) => value,
+            None => {
+                return Ok(None);
+            }
+        };
+
+        // If parse() fails, then LETTER_RANGE is broken.
+        let number: i64 = cap[1].parse().unwrap();
+        if let Some(end_number) = cap.get(3)
+            && end_number.as_str().parse::<i64>().unwrap() != number
+        {
+            return Err(anyhow!(
+                "letter range with different numbers: '{house_number}'"
+            ));
+        }
+        let first = cap[2].to_uppercase().chars().next().unwrap();
+        let last = cap[4].to_uppercase().chars().next().unwrap();
+        if first >= last {
+            return Err(anyhow!("letter range is not increasing: '{house_number}'"));
+        }
+
+        Ok(Some((number, first, last)))
+    }
+
     /// Sets the housenumber's OSM object ID, if this comes from OSM.
     pub fn set_id(&mut self, id: u64) {
         self.id = Some(id);
@@ -569,38 +607,12 @@ pub fn color_house_number(house_number: &HouseNumberRange) -> yattag::Doc {
     doc
 }
 
-/// One row in workdir/refs/streets_<DATE>.tsv.
-#[derive(serde::Deserialize)]
-pub struct RefStreet {
-    /// County code.
-    #[serde(rename = "COUNTY_CODE")]
-    pub county: String,
-    /// Settlement code.
-    #[serde(rename = "SETTLEMENT_CODE")]
-    pub settlement: String,
-    /// Street name.
-    #[serde(rename = "STREET")]
-    pub street: String,
-}
-
-/// One row in workdir/refs/housenumbers_<DATE>.tsv.
-#[derive(serde::Deserialize)]
-pub struct RefHouseNumber {
-    /// County code.
-    #[serde(rename = "COUNTY_CODE")]
-    pub county: String,
-    /// Settlement code.
-    #[serde(rename = "SETTLEMENT_CODE")]
-    pub settlement: String,
-    /// Street name.
-    #[serde(rename = "STREET")]
-    pub street: String,
-    /// House number.
-    #[serde(rename = "HOUSENUMBER")]
-    pub housenumber: String,
-    /// Comment.
-    #[serde(rename = "COMMENT")]
-    pub comment: Option<String>,
+/// Finds the index of a column in the header of a reference TSV.
+fn get_column_index(headers: &csv::StringRecord, name: &str) -> anyhow::Result<usize> {
+    headers
+        .iter()
+        .position(|i| i == name)
+        .context(format!("missing column: '{name}'"))
 }
 
 /// Builds an in-database index from the reference TSV (house number version).
@@ -619,45 +631,43 @@ pub fn build_reference_index(ctx: &context::Context, paths: &[String]) -> anyhow
         }
     }
 
+    let profile = ctx.get_profile();
+    let columns = profile.get_columns();
     for abspath in paths {
         let mut tokens = abspath.split('/');
         let path = tokens.next_back().unwrap();
-        if path.starts_with("hazszamok_kieg") {
-            let stream = ctx.get_file_system().open_read(abspath)?;
-            let mut guard = stream.borrow_mut();
-            let read = std::io::BufReader::new(guard.deref_mut());
-            let mut reader = csv::ReaderBuilder::new()
-                .delimiter(b'\t')
-                .double_quote(true)
-                .from_reader(read);
-            let tx = conn.transaction()?;
-            for result in reader.deserialize() {
-                let row: RefHouseNumber = result?;
-                tx.execute(
-                        "insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values (?1, ?2, ?3, ?4, ?5)",
-                        [row.county, row.settlement, row.street, row.housenumber, row.comment.unwrap_or(" ".into())],
-                    )?;
-            }
-            tx.commit()?;
-        } else {
-            // Assume "hazszamok_".
-            let stream = ctx.get_file_system().open_read(abspath)?;
-            let mut guard = stream.borrow_mut();
-            let read = std::io::BufReader::new(guard.deref_mut());
-            let mut reader = csv::ReaderBuilder::new()
-                .delimiter(b'\t')
-                .double_quote(true)
-                .from_reader(read);
-            let tx = conn.transaction()?;
-            for result in reader.deserialize() {
-                let row: RefHouseNumber = result?;
-                tx.execute(
-                          "insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values (?1, ?2, ?3, ?4, '')",
-                          [row.county, row.settlement, row.street, row.housenumber],
-                      )?;
-            }
-            tx.commit()?;
+        let stream = ctx.get_file_system().open_read(abspath)?;
+        let mut guard = stream.borrow_mut();
+        let read = std::io::BufReader::new(guard.deref_mut());
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(b'\t')
+            .double_quote(true)
+            .from_reader(read);
+        let headers = reader.headers()?.clone();
+        let county = get_column_index(&headers, &columns.county)?;
+        let settlement = get_column_index(&headers, &columns.settlement)?;
+        let street = get_column_index(&headers, &columns.street)?;
+        let housenumber = get_column_index(&headers, &columns.housenumber)?;
+        let comment = headers.iter().position(|i| i == columns.comment);
+        let postcode = headers.iter().position(|i| i == columns.postcode);
+        let tx = conn.transaction()?;
+        for result in reader.records() {
+            let row = result?;
+            let row_comment = comment.and_then(|i| row.get(i)).filter(|i| !i.is_empty());
+            let row_postcode = postcode.and_then(|i| row.get(i)).unwrap_or("");
+            tx.execute(
+                "insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment, postcode) values (?1, ?2, ?3, ?4, ?5, ?6)",
+                [
+                    row[county].to_string(),
+                    row[settlement].to_string(),
+                    row[street].to_string(),
+                    row[housenumber].to_string(),
+                    profile.get_comment(path, row_comment),
+                    row_postcode.to_string(),
+                ],
+            )?;
         }
+        tx.commit()?;
     }
 
     Ok(())
@@ -678,6 +688,8 @@ pub fn build_street_reference_index(ctx: &context::Context, path: &str) -> anyho
         }
     }
 
+    let profile = ctx.get_profile();
+    let columns = profile.get_columns();
     let stream = ctx.get_file_system().open_read(path)?;
     let mut guard = stream.borrow_mut();
     let read = std::io::BufReader::new(guard.deref_mut());
@@ -685,13 +697,20 @@ pub fn build_street_reference_index(ctx: &context::Context, path: &str) -> anyho
         .delimiter(b'\t')
         .double_quote(true)
         .from_reader(read);
+    let headers = reader.headers()?.clone();
+    let county = get_column_index(&headers, &columns.county)?;
+    let settlement = get_column_index(&headers, &columns.settlement)?;
+    let street = get_column_index(&headers, &columns.street)?;
     let tx = conn.transaction()?;
-    for result in reader.deserialize() {
-        let row: RefStreet = result?;
-        let street = NULL_END.replace(&row.street, "").to_string();
+    for result in reader.records() {
+        let row = result?;
         tx.execute(
             "insert into ref_streets (county_code, settlement_code, street) values (?1, ?2, ?3)",
-            [row.county, row.settlement, street],
+            [
+                row[county].to_string(),
+                row[settlement].to_string(),
+                profile.normalize_street(&row[street]),
+            ],
         )?;
     }
     tx.commit()?;
@@ -1012,6 +1031,75 @@ impl OsmHouseNumber {
     }
 }
 
+/// One row in the `osm_interpolations` SQL table for a relation: an addr:interpolation way with
+/// the house numbers of its endpoints.
+pub struct OsmInterpolation {
+    /// Object ID of the way.
+    pub id: u64,
+    /// Street name.
+    pub street: String,
+    /// Interpolation type, e.g. odd.
+    pub interpolation: String,
+    /// House number of the first node.
+    pub start: String,
+    /// House number of the last node.
+    pub end: String,
+}
+
+impl OsmInterpolation {
+    /// Creates a new OsmInterpolation.
+    pub fn new(id: u64, street: &str, interpolation: &str, start: &str, end: &str) -> Self {
+        OsmInterpolation {
+            id,
+            street: street.into(),
+            interpolation: interpolation.into(),
+            start: start.into(),
+            end: end.into(),
+        }
+    }
+
+    /// Expands the interpolation to the implied house numbers, including the endpoints. Only the
+    /// odd, even and all types are handled, anything else implies no house numbers. Invalid or too
+    /// long interpolations imply no house numbers, either.
+    pub fn get_numbers(&self) -> Vec<i64> {
+        let (step, parity) = match self.interpolation.as_str() {
+            "odd" => (2, Some(1)),
+            "even" => (2, Some(0)),
+            "all" => (1, None),
+            _ => {
+                return Vec::new();
+            }
+        };
+        let mut numbers: Vec<i64> = Vec::new();
+        for house_number in [&self.start, &self.end] {
+            if let Some(cap) = NUMBER_WITH_JUNK.captures_iter(house_number).next() {
+                // parse() fails if the number doesn't fit into i64.
+                match cap[1].parse::<i64>() {
+                    Ok(value) => numbers.push(value),
+                    Err(_) => return Vec::new(),
+                }
+            }
+        }
+        if numbers.len() != 2 {
+            return Vec::new();
+        }
+
+        // The way may go in either direction.
+        let start = numbers[0].min(numbers[1]);
+        let end = numbers[0].max(numbers[1]);
+        if end - start > MAX_INTERPOLATION_SPAN {
+            return Vec::new();
+        }
+        if let Some(parity) = parity
+            && (start % 2 != parity || end % 2 != parity)
+        {
+            // E.g. an odd interpolation between 2 and 8 is a tagging mistake.
+            return Vec::new();
+        }
+        (start..end + 1).step_by(step).collect()
+    }
+}
+
 /// Reads a house number CSV and extracts streets from rows.
 /// Returns a list of street objects, with their name, ID and type set.
 pub fn get_street_from_housenumber(housenumbers: &[OsmHouseNumber]) -> anyhow::Result<Vec<Street>> {
@@ -1158,6 +1246,7 @@ pub fn split_house_number_by_separator(
 
         if !areas::normalizer_contains(
             number,
+            /*letter=*/ None,
             normalizer,
             relation_name,
             street_name,
@@ -1222,6 +1311,40 @@ pub fn get_sort_key(bytes: &str) -> Vec<u8> {
     bytes.as_bytes().to_vec()
 }
 
+/// Calculates the Levenshtein distance of two strings, counting characters, not bytes.
+pub fn get_edit_distance(first: &str, second: &str) -> usize {
+    let second: Vec<char> = second.chars().collect();
+    let mut previous: Vec<usize> = (0..=second.len()).collect();
+    for (i, first_char) in first.chars().enumerate() {
+        let mut current: Vec<usize> = vec![i + 1];
+        for (j, second_char) in second.iter().enumerate() {
+            let substitution = previous[j] + usize::from(first_char != *second_char);
+            let insertion = current[j] + 1;
+            let deletion = previous[j + 1] + 1;
+            current.push(substitution.min(insertion).min(deletion));
+        }
+        previous = current;
+    }
+    previous[second.len()]
+}
+
+/// Produces a refstreets yaml snippet from OSM name -> reference name pairs.
+pub fn format_refstreets_yaml(pairs: &[(String, String)]) -> String {
+    if pairs.is_empty() {
+        return "".into();
+    }
+
+    let mut ret = String::from("refstreets:\n");
+    for (osm_name, ref_name) in pairs {
+        ret.push_str(&format!(
+            "  '{}': '{}'\n",
+            osm_name.replace('\'', "''"),
+            ref_name.replace('\'', "''")
+        ));
+    }
+    ret
+}
+
 /// Builds a set of valid settlement names.
 pub fn get_valid_settlements(ctx: &context::Context) -> anyhow::Result<HashSet<String>> {
     let mut settlements: HashSet<String> = HashSet::new();
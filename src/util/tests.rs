@@ -132,6 +132,39 @@ fn test_build_reference_index() {
     }
 }
 
+/// Tests build_reference_index(), when the reference has a postcode column.
+#[test]
+fn test_build_reference_index_postcode() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let ref_file = context::tests::TestFileSystem::make_file();
+    ref_file
+        .borrow_mut()
+        .write_all(b"COUNTY_CODE\tSETTLEMENT_CODE\tSTREET\tHOUSENUMBER\tPOSTCODE\n01\t011\tFo utca\t1\t1111\n")
+        .unwrap();
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[("workdir/refs/hazszamok_20250101.tsv", &ref_file)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.execute_batch("delete from ref_housenumbers").unwrap();
+    }
+    let refpath = ctx.get_abspath("workdir/refs/hazszamok_20250101.tsv");
+
+    build_reference_index(&ctx, &[refpath]).unwrap();
+
+    let conn = ctx.get_database_connection().unwrap();
+    let mut stmt = conn
+        .prepare("select postcode from ref_housenumbers")
+        .unwrap();
+    let mut rows = stmt.query([]).unwrap();
+    let row = rows.next().unwrap().unwrap();
+    let postcode: String = row.get(0).unwrap();
+    assert_eq!(postcode, "1111");
+}
+
 /// Tests build_street_reference_index().
 #[test]
 fn test_build_street_reference_index() {
@@ -469,6 +502,86 @@ fn test_house_number_normalize_letter_suffix() {
     assert_eq!(HouseNumber::normalize_letter_suffix("x", "").is_err(), true);
 }
 
+/// Tests OsmInterpolation::get_numbers().
+#[test]
+fn test_osm_interpolation_get_numbers() {
+    let interpolation = OsmInterpolation::new(1, "mystreet", "odd", "9", "1");
+    assert_eq!(interpolation.get_numbers(), vec![1, 3, 5, 7, 9]);
+
+    let interpolation = OsmInterpolation::new(1, "mystreet", "all", "2", "4/A");
+    assert_eq!(interpolation.get_numbers(), vec![2, 3, 4]);
+
+    let interpolation = OsmInterpolation::new(1, "mystreet", "alphabetic", "2", "2/C");
+    assert_eq!(interpolation.get_numbers().is_empty(), true);
+
+    let interpolation = OsmInterpolation::new(1, "mystreet", "even", "", "4");
+    assert_eq!(interpolation.get_numbers().is_empty(), true);
+}
+
+/// Tests OsmInterpolation::get_numbers(): an endpoint which doesn't fit into i64.
+#[test]
+fn test_osm_interpolation_get_numbers_overflow() {
+    let interpolation =
+        OsmInterpolation::new(1, "mystreet", "all", "1", "123456789012345678901234567890");
+
+    assert!(interpolation.get_numbers().is_empty());
+}
+
+/// Tests OsmInterpolation::get_numbers(): a too large span implies no house numbers.
+#[test]
+fn test_osm_interpolation_get_numbers_span() {
+    let interpolation = OsmInterpolation::new(1, "mystreet", "all", "1", "1001");
+    assert_eq!(interpolation.get_numbers().len(), 1001);
+
+    let interpolation = OsmInterpolation::new(1, "mystreet", "all", "1", "1002");
+    assert!(interpolation.get_numbers().is_empty());
+}
+
+/// Tests OsmInterpolation::get_numbers(): the parity of the endpoints has to match the type.
+#[test]
+fn test_osm_interpolation_get_numbers_parity() {
+    let interpolation = OsmInterpolation::new(1, "mystreet", "odd", "2", "8");
+    assert!(interpolation.get_numbers().is_empty());
+
+    let interpolation = OsmInterpolation::new(1, "mystreet", "odd", "1", "8");
+    assert!(interpolation.get_numbers().is_empty());
+
+    let interpolation = OsmInterpolation::new(1, "mystreet", "even", "8", "2");
+    assert_eq!(interpolation.get_numbers(), vec![2, 4, 6, 8]);
+
+    let interpolation = OsmInterpolation::new(1, "mystreet", "even", "3", "9");
+    assert!(interpolation.get_numbers().is_empty());
+}
+
+/// Tests HouseNumber::parse_letter_range().
+#[test]
+fn test_house_number_parse_letter_range() {
+    assert_eq!(
+        HouseNumber::parse_letter_range("12/A-12/D", "").unwrap(),
+        Some((12, 'A', 'D'))
+    );
+    assert_eq!(
+        HouseNumber::parse_letter_range("12a-d*", "*").unwrap(),
+        Some((12, 'A', 'D'))
+    );
+    assert_eq!(
+        HouseNumber::parse_letter_range("12 a - 12 c", "").unwrap(),
+        Some((12, 'A', 'C'))
+    );
+    assert_eq!(HouseNumber::parse_letter_range("12-14", "").unwrap(), None);
+    assert_eq!(HouseNumber::parse_letter_range("12/A", "").unwrap(), None);
+    // Different numbers.
+    assert_eq!(
+        HouseNumber::parse_letter_range("12/A-14/B", "").is_err(),
+        true
+    );
+    // Not increasing.
+    assert_eq!(
+        HouseNumber::parse_letter_range("12/D-12/A", "").is_err(),
+        true
+    );
+}
+
 /// Tests HouseNumberRange::get_lowercase_number().
 #[test]
 fn test_house_number_range_get_lowercase_number() {
@@ -779,3 +892,23 @@ fn test_numbered_street_debug() {
 
     assert_eq!(ret.starts_with("NumberedStreet"), true);
 }
+
+/// Tests get_edit_distance().
+#[test]
+fn test_get_edit_distance() {
+    assert_eq!(get_edit_distance("", ""), 0);
+    assert_eq!(get_edit_distance("kitten", "sitting"), 3);
+    assert_eq!(get_edit_distance("rézsű", "rézsü"), 1);
+    assert_eq!(get_edit_distance("", "utca"), 4);
+}
+
+/// Tests format_refstreets_yaml().
+#[test]
+fn test_format_refstreets_yaml() {
+    assert_eq!(format_refstreets_yaml(&[]), "");
+    let pairs = vec![("O'Brien utca".to_string(), "Obrien utca".to_string())];
+    assert_eq!(
+        format_refstreets_yaml(&pairs),
+        "refstreets:\n  'O''Brien utca': 'Obrien utca'\n"
+    );
+}
@@ -12,41 +12,158 @@
 
 use crate::areas;
 use crate::context;
+use crate::ranges;
+use crate::stats;
+use crate::util;
 use anyhow::Context;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::io::Write;
 
+/// The severity of a validation problem.
+#[derive(Clone, Copy, PartialEq)]
+enum Severity {
+    /// Validation fails.
+    Error,
+    /// Reported, but validation doesn't fail.
+    Warning,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A validation problem.
+struct Diagnostic {
+    /// The yaml path of the problem, e.g. 'filters.Foo utca.ranges[0]'.
+    path: String,
+    severity: Severity,
+    message: String,
+}
+
+impl Diagnostic {
+    fn new(path: &str, message: &str) -> Self {
+        Diagnostic {
+            path: path.into(),
+            severity: Severity::Error,
+            message: message.into(),
+        }
+    }
+
+    fn warning(path: &str, message: &str) -> Self {
+        Diagnostic {
+            path: path.into(),
+            severity: Severity::Warning,
+            message: message.into(),
+        }
+    }
+}
+
+/// A validation problem with its location, in JSON format.
+#[derive(serde::Serialize)]
+struct JsonDiagnostic {
+    file: String,
+    /// 1-based line number, if known.
+    line: Option<usize>,
+    /// 1-based column number, if known.
+    column: Option<usize>,
+    path: String,
+    severity: String,
+    message: String,
+}
+
+/// Error for the case when the validation problems are already written to the output.
+#[derive(Debug)]
+struct ReportedError;
+
+impl std::fmt::Display for ReportedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "validation failed")
+    }
+}
+
+impl std::error::Error for ReportedError {}
+
+/// Parses a range bound or an except item, using the syntax of ranges::parse_bound(), but without
+/// tolerating whitespace.
+fn parse_range_bound(value: &str) -> Option<(i64, Option<char>)> {
+    if value.trim() != value {
+        return None;
+    }
+
+    ranges::parse_bound(value).ok()
+}
+
 /// Validates a range description: check for missing keys."""
 fn validate_range_missing_keys(
-    errors: &mut Vec<String>,
+    errors: &mut Vec<Diagnostic>,
     parent: &str,
     range_data: &areas::RelationRangesDict,
     filter_data: &areas::RelationFiltersDict,
 ) -> anyhow::Result<()> {
-    let start: i64 = match range_data.start.parse() {
-        Ok(value) => value,
-        Err(_) => {
-            errors.push(format!(
-                "expected value type for '{parent}.start' is a digit str"
+    let start = match parse_range_bound(&range_data.start) {
+        Some(value) => value,
+        None => {
+            errors.push(Diagnostic::new(
+                &format!("{parent}.start"),
+                &format!("expected value type for '{parent}.start' is a digit str"),
             ));
             return Ok(());
         }
     };
-    let end: i64 = match range_data.end.parse() {
-        Ok(value) => value,
-        Err(_) => {
-            errors.push(format!(
-                "expected value type for '{parent}.end' is a digit str"
+    let end = match parse_range_bound(&range_data.end) {
+        Some(value) => value,
+        None => {
+            errors.push(Diagnostic::new(
+                &format!("{parent}.end"),
+                &format!("expected value type for '{parent}.end' is a digit str"),
             ));
             return Ok(());
         }
     };
     if start > end {
-        errors.push(format!("expected end >= start for '{parent}'"));
+        errors.push(Diagnostic::new(
+            parent,
+            &format!("expected end >= start for '{parent}'"),
+        ));
     }
 
-    if filter_data.interpolation.is_none() && start % 2 != end % 2 {
-        errors.push(format!("expected start % 2 == end % 2 for '{parent}'"))
+    if filter_data.interpolation.is_none() && start.0 % 2 != end.0 % 2 {
+        errors.push(Diagnostic::new(
+            parent,
+            &format!("expected start % 2 == end % 2 for '{parent}'"),
+        ))
+    }
+
+    if let Some(ref except) = range_data.except {
+        for (index, item) in except.iter().enumerate() {
+            match parse_range_bound(item) {
+                Some((number, _letter)) => {
+                    if number < start.0 || number > end.0 {
+                        errors.push(Diagnostic::new(
+                            &format!("{parent}.except[{index}]"),
+                            &format!(
+                                "expected '{parent}.except[{index}]' to be between start and end"
+                            ),
+                        ));
+                    }
+                }
+                None => {
+                    errors.push(Diagnostic::new(
+                        &format!("{parent}.except[{index}]"),
+                        &format!(
+                            "expected format for '{parent}.except[{index}]' is '42' or '42/B'"
+                        ),
+                    ));
+                }
+            }
+        }
     }
 
     Ok(())
@@ -54,7 +171,7 @@ fn validate_range_missing_keys(
 
 /// Validates a range description.
 fn validate_range(
-    errors: &mut Vec<String>,
+    errors: &mut Vec<Diagnostic>,
     parent: &str,
     range_data: &areas::RelationRangesDict,
     filter_data: &areas::RelationFiltersDict,
@@ -65,7 +182,7 @@ fn validate_range(
 
 /// Validates a range list.
 fn validate_ranges(
-    errors: &mut Vec<String>,
+    errors: &mut Vec<Diagnostic>,
     parent: &str,
     ranges: &[areas::RelationRangesDict],
     filter_data: &areas::RelationFiltersDict,
@@ -80,7 +197,7 @@ fn validate_ranges(
 
 /// Validates an 'invalid' or 'valid' list.
 fn validate_filter_invalid_valid(
-    errors: &mut Vec<String>,
+    errors: &mut Vec<Diagnostic>,
     parent: &str,
     invalid: &[String],
 ) -> anyhow::Result<()> {
@@ -103,8 +220,9 @@ fn validate_filter_invalid_valid(
         if is_range.is_match(invalid_data) {
             continue;
         }
-        errors.push(format!(
-            "expected format for '{parent}[{index}]' is '42', '42a' or '42/1'"
+        errors.push(Diagnostic::new(
+            &format!("{parent}[{index}]"),
+            &format!("expected format for '{parent}[{index}]' is '42', '42a' or '42/1'"),
         ));
     }
 
@@ -113,7 +231,7 @@ fn validate_filter_invalid_valid(
 
 /// Validates a filter dictionary.
 fn validate_filter(
-    errors: &mut Vec<String>,
+    errors: &mut Vec<Diagnostic>,
     parent: &str,
     filter_data: &areas::RelationFiltersDict,
 ) -> anyhow::Result<()> {
@@ -134,7 +252,7 @@ fn validate_filter(
 
 /// Validates a filter list.
 fn validate_filters(
-    errors: &mut Vec<String>,
+    errors: &mut Vec<Diagnostic>,
     parent: &str,
     filters: &HashMap<String, areas::RelationFiltersDict>,
 ) -> anyhow::Result<()> {
@@ -149,28 +267,38 @@ fn validate_filters(
 
 /// Validates a reference streets list.
 fn validate_refstreets(
-    errors: &mut Vec<String>,
+    errors: &mut Vec<Diagnostic>,
     parent: &str,
     refstreets: &HashMap<String, String>,
 ) -> anyhow::Result<()> {
     let context = format!("{parent}.");
     for (key, value) in refstreets {
         if key.contains('\'') || key.contains('"') {
-            errors.push(format!("expected no quotes in '{context}{key}'"));
+            errors.push(Diagnostic::new(
+                &format!("{context}{key}"),
+                &format!("expected no quotes in '{context}{key}'"),
+            ));
         }
         if value.contains('\'') || value.contains('"') {
-            errors.push(format!("expected no quotes in value of '{context}{key}'"));
+            errors.push(Diagnostic::new(
+                &format!("{context}{key}"),
+                &format!("expected no quotes in value of '{context}{key}'"),
+            ));
         }
         if key == value {
-            errors.push(format!("expected value != key for '{context}{key}'"));
+            errors.push(Diagnostic::new(
+                &format!("{context}{key}"),
+                &format!("expected value != key for '{context}{key}'"),
+            ));
         }
     }
     let mut reverse: Vec<_> = refstreets.values().map(|value| value.as_str()).collect();
     reverse.sort_unstable();
     reverse.dedup();
     if refstreets.keys().len() != reverse.len() {
-        errors.push(format!(
-            "osm and ref streets are not a 1:1 mapping in '{parent}'"
+        errors.push(Diagnostic::new(
+            parent,
+            &format!("osm and ref streets are not a 1:1 mapping in '{parent}'"),
         ));
     }
 
@@ -179,7 +307,7 @@ fn validate_refstreets(
 
 /// Validates a toplevel or a nested relation.
 fn validate_relation(
-    errors: &mut Vec<String>,
+    errors: &mut Vec<Diagnostic>,
     parent: &str,
     relation: &areas::RelationDict,
 ) -> anyhow::Result<()> {
@@ -190,13 +318,22 @@ fn validate_relation(
         // Just to be consistent, we require these keys in relations.yaml for now, even if code would
         // handle having them there or in relation-foo.yaml as well.
         if relation.osmrelation.is_none() {
-            errors.push(format!("missing key '{context}osmrelation'"));
+            errors.push(Diagnostic::new(
+                parent,
+                &format!("missing key '{context}osmrelation'"),
+            ));
         }
         if relation.refcounty.is_none() {
-            errors.push(format!("missing key '{context}refcounty'"));
+            errors.push(Diagnostic::new(
+                parent,
+                &format!("missing key '{context}refcounty'"),
+            ));
         }
         if relation.refsettlement.is_none() {
-            errors.push(format!("missing key '{context}refsettlement'"));
+            errors.push(Diagnostic::new(
+                parent,
+                &format!("missing key '{context}refsettlement'"),
+            ));
         }
     }
 
@@ -212,7 +349,7 @@ fn validate_relation(
 
 /// Validates a relation list.
 fn validate_relations(
-    errors: &mut Vec<String>,
+    errors: &mut Vec<Diagnostic>,
     relations: &areas::RelationsDict,
 ) -> anyhow::Result<()> {
     for (key, value) in relations {
@@ -222,38 +359,1070 @@ fn validate_relations(
     Ok(())
 }
 
+/// Validates a relation against the database state: OSM streets, reference streets and
+/// reference house numbers.
+fn validate_relation_db(
+    errors: &mut Vec<Diagnostic>,
+    parent: &str,
+    relation: &mut areas::Relation<'_>,
+) -> anyhow::Result<()> {
+    let streets_page = format!("streets/{}", relation.get_name());
+    if !stats::has_sql_mtime(relation.get_ctx(), &streets_page)? {
+        // No OSM data yet, nothing to compare with.
+        return Ok(());
+    }
+
+    let mut context: String = "".into();
+    if !parent.is_empty() {
+        context = format!("{parent}.");
+    }
+
+    let (osm_invalids, ref_invalids) = relation.get_invalid_refstreets()?;
+    for osm_invalid in osm_invalids {
+        errors.push(Diagnostic::new(
+            &format!("{context}refstreets.{osm_invalid}"),
+            &format!("expected OSM street name for '{context}refstreets.{osm_invalid}'"),
+        ));
+    }
+    for ref_invalid in ref_invalids {
+        errors.push(Diagnostic::new(
+            &format!("{context}refstreets"),
+            &format!(
+                "expected reference street name, which is not an OSM street name, for value '{ref_invalid}' in '{context}refstreets'"
+            ),
+        ));
+    }
+    for key_invalid in relation.get_invalid_filter_keys()? {
+        errors.push(Diagnostic::new(
+            &format!("{context}filters.{key_invalid}"),
+            &format!("expected OSM street name for '{context}filters.{key_invalid}'"),
+        ));
+    }
+
+    relation.get_missing_housenumbers()?;
+    for lint in relation.get_lints() {
+        if lint.source != areas::RelationLintSource::Invalid
+            || lint.reason != areas::RelationLintReason::DeletedFromRef
+        {
+            continue;
+        }
+        errors.push(Diagnostic::new(
+            &format!("{context}filters.{}.invalid", lint.street_name),
+            &format!(
+                "expected '{}' in '{context}filters.{}.invalid' to match a reference house number",
+                lint.housenumber, lint.street_name
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Reads a yaml file next to the validated one, if it exists.
+fn read_sibling_yaml<T: serde::de::DeserializeOwned>(
+    ctx: &context::Context,
+    yaml_path: &str,
+    file_name: &str,
+) -> anyhow::Result<Option<T>> {
+    let path = std::path::Path::new(yaml_path)
+        .with_file_name(file_name)
+        .to_string_lossy()
+        .to_string();
+    if !ctx.get_file_system().path_exists(&path) {
+        return Ok(None);
+    }
+
+    let data = ctx.get_file_system().read_to_string(&path)?;
+    let ret = serde_yaml::from_str(&data).context(format!("failed to parse {path}"))?;
+    Ok(Some(ret))
+}
+
+/// Gets the house numbers matched by the ranges of a street filter, ignoring letters. Invalid
+/// bounds are ignored here, validate_relation() already reports them.
+fn get_filter_numbers(filter: &areas::RelationFiltersDict) -> HashSet<i64> {
+    let all = filter.interpolation.as_deref() == Some("all");
+    let mut ret: HashSet<i64> = HashSet::new();
+    for range in filter.ranges.iter().flatten() {
+        let (Ok((start, _)), Ok((end, _))) = (
+            ranges::parse_bound(&range.start),
+            ranges::parse_bound(&range.end),
+        ) else {
+            continue;
+        };
+        let except: Vec<i64> = range
+            .except
+            .iter()
+            .flatten()
+            .filter_map(|i| ranges::parse_bound(i).ok())
+            .filter(|(_, letter)| letter.is_none())
+            .map(|(number, _)| number)
+            .collect();
+        ret.extend((start..=end).filter(|i| (all || i % 2 == start % 2) && !except.contains(i)));
+    }
+    ret
+}
+
+/// Validates relations against each other: unique IDs and aliases, known refcounty and
+/// refsettlement codes, consistent filters in relations of the same refsettlement.
+fn validate_relations_across(
+    errors: &mut Vec<Diagnostic>,
+    ctx: &context::Context,
+    yaml_path: &str,
+    relations: &areas::RelationsDict,
+) -> anyhow::Result<()> {
+    let mut names: Vec<&String> = relations.keys().collect();
+    names.sort();
+
+    let mut osmrelations: HashMap<u64, &String> = HashMap::new();
+    // Aliases must not clash with relation names, either.
+    let mut aliases: HashMap<&String, &String> = names.iter().map(|i| (*i, *i)).collect();
+    for name in &names {
+        let relation = &relations[*name];
+        if let Some(osmrelation) = relation.osmrelation {
+            match osmrelations.get(&osmrelation) {
+                Some(other) => errors.push(Diagnostic::new(
+                    &format!("{name}.osmrelation"),
+                    &format!(
+                        "expected unique value for '{name}.osmrelation', already used by '{other}'"
+                    ),
+                )),
+                None => {
+                    osmrelations.insert(osmrelation, name);
+                }
+            }
+        }
+        for (index, alias) in relation.alias.iter().flatten().enumerate() {
+            let path = format!("{name}.alias[{index}]");
+            match aliases.get(alias) {
+                Some(other) => errors.push(Diagnostic::new(
+                    &path,
+                    &format!("expected unique value for '{path}', already used by '{other}'"),
+                )),
+                None => {
+                    aliases.insert(alias, name);
+                }
+            }
+        }
+    }
+
+    let levels = ctx.get_profile().get_hierarchy_levels();
+    let county_names: Option<HashMap<String, String>> =
+        read_sibling_yaml(ctx, yaml_path, &levels.county_names)?;
+    let settlement_names: Option<HashMap<String, HashMap<String, String>>> =
+        read_sibling_yaml(ctx, yaml_path, &levels.settlement_names)?;
+    for name in &names {
+        let relation = &relations[*name];
+        if let (Some(county_names), Some(refcounty)) = (&county_names, &relation.refcounty)
+            && !county_names.contains_key(refcounty)
+        {
+            errors.push(Diagnostic::new(
+                &format!("{name}.refcounty"),
+                &format!(
+                    "expected '{name}.refcounty' to be in {}",
+                    levels.county_names
+                ),
+            ));
+        }
+        // Names are only provided for the settlements of some counties, so only check those
+        // counties and even then this is not fatal.
+        if let (Some(settlement_names), Some(refcounty), Some(refsettlement)) = (
+            &settlement_names,
+            &relation.refcounty,
+            &relation.refsettlement,
+        ) && let Some(county_settlements) = settlement_names.get(refcounty)
+            && !county_settlements.contains_key(refsettlement)
+        {
+            errors.push(Diagnostic::warning(
+                &format!("{name}.refsettlement"),
+                &format!(
+                    "expected '{name}.refsettlement' to be in {}",
+                    levels.settlement_names
+                ),
+            ));
+        }
+    }
+
+    // Relations with the same refsettlement may overlap, e.g. a district and its sub-area: the
+    // ranges of the same street should not partially overlap in them.
+    let mut filters: HashMap<&String, HashMap<String, areas::RelationFiltersDict>> = HashMap::new();
+    for name in &names {
+        let relation: Option<areas::RelationDict> =
+            read_sibling_yaml(ctx, yaml_path, &format!("relation-{name}.yaml"))?;
+        if let Some(value) = relation.and_then(|i| i.filters) {
+            filters.insert(name, value);
+        }
+    }
+    for (index, name) in names.iter().enumerate() {
+        let relation = &relations[*name];
+        let my_filters = match filters.get(name) {
+            Some(value) => value,
+            None => continue,
+        };
+        let mut streets: Vec<&String> = my_filters.keys().collect();
+        streets.sort();
+        for other in &names[..index] {
+            let other_relation = &relations[*other];
+            if relation.refcounty.is_none()
+                || relation.refsettlement.is_none()
+                || relation.refcounty != other_relation.refcounty
+                || relation.refsettlement != other_relation.refsettlement
+            {
+                continue;
+            }
+            let other_filters = match filters.get(other) {
+                Some(value) => value,
+                None => continue,
+            };
+            for street in &streets {
+                let other_numbers = match other_filters.get(*street) {
+                    Some(value) => get_filter_numbers(value),
+                    None => continue,
+                };
+                let my_numbers = get_filter_numbers(&my_filters[*street]);
+                // Disjoint ranges split a street, a superset is a district containing a sub-area.
+                if my_numbers.is_disjoint(&other_numbers)
+                    || my_numbers.is_subset(&other_numbers)
+                    || my_numbers.is_superset(&other_numbers)
+                {
+                    continue;
+                }
+                // Boundaries are sometimes uncertain, so this is not fatal.
+                errors.push(Diagnostic::warning(
+                    name,
+                    &format!(
+                        "expected 'filters.{street}.ranges' in relation-{other}.yaml and relation-{name}.yaml to be either disjoint or contain each other, as both have the same refsettlement"
+                    ),
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Validates relations against the database state, the relation-specific config comes from the
+/// validated yaml file if it's a relation-foo.yaml one.
+fn validate_db(
+    errors: &mut Vec<Diagnostic>,
+    ctx: &context::Context,
+    yaml_path: &str,
+    relation_dict: Option<&areas::RelationDict>,
+) -> anyhow::Result<()> {
+    let mut relations = areas::Relations::new(ctx)?;
+    match relation_dict {
+        Some(relation_dict) => {
+            let file_name = std::path::Path::new(yaml_path)
+                .file_name()
+                .context("no file name")?
+                .to_string_lossy();
+            let relation_name = match file_name
+                .strip_prefix("relation-")
+                .and_then(|i| i.strip_suffix(".yaml"))
+            {
+                Some(value) => value.to_string(),
+                None => return Ok(()),
+            };
+            if !relations.get_names().contains(&relation_name) {
+                errors.push(Diagnostic::new(
+                    "",
+                    &format!("expected '{relation_name}' to be in relations.yaml"),
+                ));
+                return Ok(());
+            }
+            let mut relation = relations.get_relation(&relation_name)?;
+            let mut config = relation.get_config().clone();
+            config.set_dict(relation_dict);
+            relation.set_config(&config);
+            validate_relation_db(errors, "", &mut relation)?;
+        }
+        None => {
+            for relation_name in relations.get_names() {
+                let mut relation = relations.get_relation(&relation_name)?;
+                validate_relation_db(errors, &relation_name, &mut relation)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// A line of a yaml file, with its preceding comments and its nested lines.
+struct YamlNode {
+    comments: Vec<String>,
+    line: String,
+    /// 1-based line number of the line.
+    line_number: usize,
+    children: Vec<YamlNode>,
+    /// Comments after the last nested line, indented like the nested lines.
+    trailing_comments: Vec<String>,
+}
+
+impl YamlNode {
+    /// Gets the content of the line, without the trailing comment.
+    fn get_content(&self) -> &str {
+        split_yaml_comment(&self.line).0.trim_start()
+    }
+
+    /// Gets the key of a 'key: value' line.
+    fn get_key(&self) -> Option<String> {
+        parse_yaml_key_value(self.get_content()).map(|(key, _value)| key)
+    }
+
+    /// Gets the key of a 'key: value' line, as it appears in a yaml path: number keys are
+    /// accepted as well, since serde reports them as strings.
+    fn get_path_key(&self) -> Option<String> {
+        let mapping: serde_yaml::Mapping = serde_yaml::from_str(self.get_content()).ok()?;
+        match mapping.into_iter().next()?.0 {
+            serde_yaml::Value::String(key) => Some(key),
+            serde_yaml::Value::Number(key) => Some(key.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Gets the value of a '- value' line.
+    fn get_item(&self) -> Option<serde_yaml::Value> {
+        let item = self.get_content().strip_prefix('-')?;
+        serde_yaml::from_str(item.trim()).ok()
+    }
+
+    /// Gets the 1-based column of the content of the line.
+    fn get_column(&self) -> usize {
+        self.line.len() - self.line.trim_start().len() + 1
+    }
+
+    /// Replaces the content of the line, keeping the indent and the trailing comment.
+    fn set_content(&mut self, content: &str) {
+        let indent = &self.line[..self.line.len() - self.line.trim_start().len()];
+        let comment = split_yaml_comment(&self.line).1;
+        self.line = format!("{indent}{content}{comment}");
+    }
+}
+
+/// Determines if a yaml line is a comment or an empty line.
+fn is_yaml_comment(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.is_empty() || trimmed.starts_with('#')
+}
+
+/// Gets the indent of a yaml line, sequence items are more indented than their key.
+fn get_yaml_indent(line: &str) -> usize {
+    let trimmed = line.trim_start();
+    let indent = (line.len() - trimmed.len()) * 2;
+    if trimmed.starts_with('-') {
+        return indent + 1;
+    }
+    indent
+}
+
+/// Splits a yaml line into its content and its trailing comment (with the leading whitespace).
+fn split_yaml_comment(line: &str) -> (&str, &str) {
+    let mut quote: Option<char> = None;
+    let mut prev = ' ';
+    for (index, ch) in line.char_indices() {
+        match quote {
+            Some(value) => {
+                if ch == value {
+                    quote = None;
+                }
+            }
+            None => {
+                if ch == '\'' || ch == '"' {
+                    quote = Some(ch);
+                } else if ch == '#' && prev.is_whitespace() {
+                    let content = line[..index].trim_end();
+                    return (content, &line[content.len()..]);
+                }
+            }
+        }
+        prev = ch;
+    }
+    (line, "")
+}
+
+/// Parses the content of a 'key: value' yaml line.
+fn parse_yaml_key_value(content: &str) -> Option<(String, serde_yaml::Value)> {
+    let mapping: serde_yaml::Mapping = serde_yaml::from_str(content).ok()?;
+    let (key, value) = mapping.into_iter().next()?;
+    Some((key.as_str()?.to_string(), value))
+}
+
+/// Parses yaml lines into a tree: a line owns the more indented lines after it. A comment belongs
+/// to the next line, unless it's indented like the nested lines of the previous line.
+fn parse_yaml_nodes(
+    lines: &[&str],
+    pos: &mut usize,
+    parent_indent: Option<usize>,
+) -> (Vec<YamlNode>, Vec<String>) {
+    let mut nodes: Vec<YamlNode> = Vec::new();
+    loop {
+        let start = *pos;
+        while *pos < lines.len() && is_yaml_comment(lines[*pos]) {
+            *pos += 1;
+        }
+        let indent = lines.get(*pos).map(|line| get_yaml_indent(line));
+        let nested = match (indent, parent_indent) {
+            (Some(indent), Some(parent_indent)) => indent > parent_indent,
+            (indent, _) => indent.is_some(),
+        };
+        if !nested {
+            let mut end = start;
+            while end < *pos {
+                let trailing = match parent_indent {
+                    Some(parent_indent) => {
+                        !lines[end].trim().is_empty() && get_yaml_indent(lines[end]) > parent_indent
+                    }
+                    None => true,
+                };
+                if !trailing {
+                    break;
+                }
+                end += 1;
+            }
+            *pos = end;
+            let trailing_comments = lines[start..end].iter().map(|i| i.to_string()).collect();
+            return (nodes, trailing_comments);
+        }
+
+        let comments = lines[start..*pos].iter().map(|i| i.to_string()).collect();
+        let line = lines[*pos].to_string();
+        *pos += 1;
+        let line_number = *pos;
+        let (children, trailing_comments) = parse_yaml_nodes(lines, pos, indent);
+        nodes.push(YamlNode {
+            comments,
+            line,
+            line_number,
+            children,
+            trailing_comments,
+        });
+    }
+}
+
+/// Writes a yaml tree back to lines.
+fn write_yaml_nodes(nodes: &[YamlNode], ret: &mut Vec<String>) {
+    for node in nodes {
+        ret.extend(node.comments.iter().cloned());
+        ret.push(node.line.clone());
+        write_yaml_nodes(&node.children, ret);
+        ret.extend(node.trailing_comments.iter().cloned());
+    }
+}
+
+/// Quotes a yaml string.
+fn quote_yaml(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Quotes a yaml key, unless it can be a plain string.
+fn quote_yaml_key(key: &str) -> String {
+    let special = key.contains(|ch: char| ":#'\"[]{},&*!|>%@`".contains(ch))
+        || key.starts_with(['-', '?', ' '])
+        || key.ends_with(' ');
+    if !special
+        && serde_yaml::from_str::<serde_yaml::Value>(key).ok()
+            == Some(serde_yaml::Value::String(key.into()))
+    {
+        return key.into();
+    }
+    quote_yaml(key)
+}
+
+/// Gets a list of strings from a yaml value.
+fn get_yaml_strings(value: &serde_yaml::Value) -> Option<Vec<String>> {
+    value
+        .as_sequence()?
+        .iter()
+        .map(|i| i.as_str().map(|i| i.to_string()))
+        .collect()
+}
+
+/// Formats a list of strings as a yaml flow sequence.
+fn format_yaml_strings(items: &[String]) -> String {
+    let items: Vec<String> = items.iter().map(|i| quote_yaml(i)).collect();
+    format!("[{}]", items.join(", "))
+}
+
+/// Gets the sort key of a house number: numerical part first.
+fn get_house_number_sort_key(house_number: &str) -> (i32, String) {
+    (
+        util::split_house_number(house_number).0,
+        house_number.to_string(),
+    )
+}
+
+/// Sorts and deduplicates a house number list.
+fn sort_house_numbers(house_numbers: &mut Vec<String>) {
+    house_numbers.sort_by_cached_key(|i| get_house_number_sort_key(i));
+    house_numbers.dedup();
+}
+
+/// Sorts 'key: value' nodes by their street name key.
+fn sort_yaml_nodes_by_street(nodes: &mut [YamlNode]) {
+    nodes.sort_by_cached_key(|i| util::get_sort_key(&i.get_key().unwrap_or_default()));
+}
+
+/// Formats a block sequence of 'invalid' items: sorted, deduplicated, comments kept.
+fn format_invalid_items(items: Vec<YamlNode>) -> Vec<YamlNode> {
+    let mut items: Vec<(Option<String>, YamlNode)> = items
+        .into_iter()
+        .map(|mut item| {
+            let value = item
+                .get_item()
+                .and_then(|i| i.as_str().map(|i| i.to_string()));
+            if let Some(ref value) = value {
+                item.set_content(&format!("- {}", quote_yaml(value)));
+            }
+            (value, item)
+        })
+        .collect();
+    items.sort_by_cached_key(|(value, item)| match value {
+        Some(value) => get_house_number_sort_key(value),
+        None => (i32::MAX, item.line.clone()),
+    });
+
+    let mut ret: Vec<(Option<String>, YamlNode)> = Vec::new();
+    for (value, item) in items {
+        if let Some((last_value, last_item)) = ret.last_mut()
+            && value.is_some()
+            && value == *last_value
+        {
+            // Duplicate: drop it, but keep its comments.
+            last_item.comments.extend(item.comments);
+            let comment = split_yaml_comment(&item.line).1.trim_start();
+            if !comment.is_empty() {
+                let indent = &item.line[..item.line.len() - item.line.trim_start().len()];
+                last_item.comments.push(format!("{indent}{comment}"));
+            }
+            continue;
+        }
+        ret.push((value, item));
+    }
+    ret.into_iter().map(|(_value, item)| item).collect()
+}
+
+/// Formats a '- {start: ..., end: ...}' range line. Returns the sort key of the range: odd ranges
+/// first, then by start.
+fn format_range_item(item: &mut YamlNode) -> (bool, i32) {
+    let mapping = match item.get_item() {
+        Some(serde_yaml::Value::Mapping(value)) => value,
+        _ => return (true, i32::MAX),
+    };
+    let mut start: Option<String> = None;
+    let mut end: Option<String> = None;
+    let mut refsettlement: Option<String> = None;
+    let mut except: Option<Vec<String>> = None;
+    for (key, value) in &mapping {
+        match (key.as_str(), value) {
+            (Some("start"), serde_yaml::Value::String(value)) => start = Some(value.clone()),
+            (Some("end"), serde_yaml::Value::String(value)) => end = Some(value.clone()),
+            (Some("refsettlement"), serde_yaml::Value::String(value)) => {
+                refsettlement = Some(value.clone())
+            }
+            (Some("except"), value) => match get_yaml_strings(value) {
+                Some(value) => except = Some(value),
+                None => return (true, i32::MAX),
+            },
+            _ => return (true, i32::MAX),
+        }
+    }
+    let (start, end) = match (start, end) {
+        (Some(start), Some(end)) => (start, end),
+        _ => return (true, i32::MAX),
+    };
+
+    let mut content = format!(
+        "- {{start: {}, end: {}",
+        quote_yaml(&start),
+        quote_yaml(&end)
+    );
+    if let Some(refsettlement) = refsettlement {
+        content += &format!(", refsettlement: {}", quote_yaml(&refsettlement));
+    }
+    if let Some(mut except) = except {
+        sort_house_numbers(&mut except);
+        content += &format!(", except: {}", format_yaml_strings(&except));
+    }
+    content += "}";
+    item.set_content(&content);
+
+    let start = util::split_house_number(&start).0;
+    (start % 2 == 0, start)
+}
+
+/// Formats the filters of a single street.
+fn format_street_filter(street: &mut YamlNode) {
+    if let Some((key, serde_yaml::Value::Null)) = parse_yaml_key_value(street.get_content()) {
+        street.set_content(&format!("{}:", quote_yaml_key(&key)));
+    }
+
+    for child in street.children.iter_mut() {
+        let (key, value) = match parse_yaml_key_value(child.get_content()) {
+            Some(value) => value,
+            None => continue,
+        };
+        match key.as_str() {
+            "invalid" => {
+                if let Some(mut invalid) = get_yaml_strings(&value) {
+                    sort_house_numbers(&mut invalid);
+                    child.set_content(&format!("invalid: {}", format_yaml_strings(&invalid)));
+                }
+                let items = std::mem::take(&mut child.children);
+                child.children = format_invalid_items(items);
+            }
+            "ranges" => {
+                let mut items: Vec<((bool, i32), YamlNode)> = std::mem::take(&mut child.children)
+                    .into_iter()
+                    .map(|mut item| (format_range_item(&mut item), item))
+                    .collect();
+                items.sort_by_key(|(key, _item)| *key);
+                child.children = items.into_iter().map(|(_key, item)| item).collect();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Formats the keys of a relation.
+fn format_relation_nodes(nodes: &mut [YamlNode]) {
+    for node in nodes.iter_mut() {
+        let (key, value) = match parse_yaml_key_value(node.get_content()) {
+            Some(value) => value,
+            None => continue,
+        };
+        match key.as_str() {
+            "alias" => {
+                if let Some(alias) = get_yaml_strings(&value) {
+                    node.set_content(&format!("alias: {}", format_yaml_strings(&alias)));
+                }
+            }
+            "filters" => {
+                sort_yaml_nodes_by_street(&mut node.children);
+                for street in node.children.iter_mut() {
+                    format_street_filter(street);
+                }
+            }
+            "refcounty" | "refsettlement" => {
+                if let serde_yaml::Value::String(value) = value {
+                    node.set_content(&format!("{key}: \"{value}\""));
+                }
+            }
+            "refstreets" => {
+                sort_yaml_nodes_by_street(&mut node.children);
+                for refstreet in node.children.iter_mut() {
+                    if let Some((osm_name, serde_yaml::Value::String(ref_name))) =
+                        parse_yaml_key_value(refstreet.get_content())
+                    {
+                        refstreet.set_content(&format!(
+                            "{}: {}",
+                            quote_yaml(&osm_name),
+                            quote_yaml(&ref_name)
+                        ));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Formats a relations.yaml or a relation-foo.yaml file into its canonical form: streets are
+/// sorted, 'invalid' lists are sorted and deduplicated, odd ranges come first, strings are quoted
+/// consistently. Comments are preserved.
+fn format_yaml(yaml_path: &str, data: &str) -> String {
+    let lines: Vec<&str> = data.lines().map(|i| i.trim_end()).collect();
+    let mut pos = 0;
+    let (mut nodes, trailing_comments) = parse_yaml_nodes(&lines, &mut pos, None);
+    if yaml_path.ends_with("relations.yaml") {
+        for relation in nodes.iter_mut() {
+            format_relation_nodes(&mut relation.children);
+        }
+    } else {
+        format_relation_nodes(&mut nodes);
+    }
+
+    let mut ret: Vec<String> = Vec::new();
+    write_yaml_nodes(&nodes, &mut ret);
+    ret.extend(trailing_comments);
+    ret.iter().map(|i| format!("{i}\n")).collect()
+}
+
+/// Removes 'invalid' items of streets from a relation yaml file. Returns the changed lines: line
+/// number -> new content, None for removed lines.
+fn remove_yaml_invalids(
+    data: &str,
+    redundant: &[areas::RedundantInvalids],
+) -> BTreeMap<usize, Option<String>> {
+    let lines: Vec<&str> = data.lines().map(|i| i.trim_end()).collect();
+    let mut pos = 0;
+    let (mut nodes, _trailing_comments) = parse_yaml_nodes(&lines, &mut pos, None);
+    let mut ret: BTreeMap<usize, Option<String>> = BTreeMap::new();
+    let filters = match nodes
+        .iter_mut()
+        .find(|i| i.get_key().as_deref() == Some("filters"))
+    {
+        Some(value) => value,
+        None => return ret,
+    };
+    for street in filters.children.iter_mut() {
+        let street_name = street.get_key().unwrap_or_default();
+        let housenumbers = match redundant.iter().find(|i| i.street_name == street_name) {
+            Some(value) => &value.housenumbers,
+            None => continue,
+        };
+        for child in street.children.iter_mut() {
+            let value = match parse_yaml_key_value(child.get_content()) {
+                Some((key, value)) if key == "invalid" => value,
+                _ => continue,
+            };
+            if let Some(invalid) = get_yaml_strings(&value)
+                && !invalid.is_empty()
+            {
+                // Flow sequence.
+                let kept: Vec<String> = invalid
+                    .into_iter()
+                    .filter(|i| !housenumbers.contains(i))
+                    .collect();
+                if kept.is_empty() {
+                    ret.insert(child.line_number, None);
+                } else {
+                    child.set_content(&format!("invalid: {}", format_yaml_strings(&kept)));
+                    ret.insert(child.line_number, Some(child.line.clone()));
+                }
+                continue;
+            }
+
+            // Block sequence.
+            let mut kept = 0;
+            for item in &child.children {
+                let value = item
+                    .get_item()
+                    .and_then(|i| i.as_str().map(|i| i.to_string()));
+                match value {
+                    Some(value) if housenumbers.contains(&value) => {
+                        ret.insert(item.line_number, None);
+                    }
+                    _ => kept += 1,
+                }
+            }
+            if kept == 0 {
+                ret.insert(child.line_number, None);
+            }
+        }
+    }
+    ret
+}
+
+/// Makes a unified diff of a file from its changed lines, with 3 lines of context.
+fn make_unified_diff(path: &str, data: &str, changes: &BTreeMap<usize, Option<String>>) -> String {
+    let lines: Vec<&str> = data.lines().collect();
+    let context = 3;
+    // Group the changes into hunks, 1-based inclusive line number ranges.
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for line_number in changes.keys() {
+        let start = line_number.saturating_sub(context).max(1);
+        let end = (line_number + context).min(lines.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 + 1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut ret = format!("--- a/{path}\n+++ b/{path}\n");
+    let mut offset: i64 = 0;
+    for (start, end) in hunks {
+        let mut body: Vec<String> = Vec::new();
+        let mut removed = 0;
+        for line_number in start..=end {
+            let line = lines[line_number - 1];
+            match changes.get(&line_number) {
+                Some(change) => {
+                    body.push(format!("-{line}"));
+                    match change {
+                        Some(value) => body.push(format!("+{value}")),
+                        None => removed += 1,
+                    }
+                }
+                None => body.push(format!(" {line}")),
+            }
+        }
+        let old_len = end - start + 1;
+        let new_len = old_len - removed;
+        let new_start = start as i64 + offset;
+        ret += &format!("@@ -{start},{old_len} +{new_start},{new_len} @@\n");
+        for line in body {
+            ret += &format!("{line}\n");
+        }
+        offset -= removed as i64;
+    }
+    ret
+}
+
+/// Makes a diff which removes the redundant 'invalid' items from the yaml file of a relation.
+/// Returns an empty string if there is nothing to remove.
+pub fn get_redundant_invalids_diff(
+    path: &str,
+    data: &str,
+    redundant: &[areas::RedundantInvalids],
+) -> String {
+    let changes = remove_yaml_invalids(data, redundant);
+    if changes.is_empty() {
+        return "".into();
+    }
+
+    make_unified_diff(path, data, &changes)
+}
+
+/// Finds the node of a yaml path like 'filters.Foo utca.ranges[0]'. If the path is not found
+/// completely, then the node of its longest found prefix is returned.
+fn find_yaml_node<'a>(nodes: &'a [YamlNode], path: &str) -> Option<&'a YamlNode> {
+    if let Some(rest) = path.strip_prefix('[') {
+        let (index, rest) = rest.split_once(']')?;
+        let node = nodes.get(index.parse::<usize>().ok()?)?;
+        let rest = rest.strip_prefix('.').unwrap_or(rest);
+        if rest.is_empty() {
+            return Some(node);
+        }
+        return find_yaml_node(&node.children, rest).or(Some(node));
+    }
+
+    // Keys may contain dots, so look for the longest key which is a prefix of the path.
+    let mut found: Option<(&YamlNode, &str)> = None;
+    for node in nodes {
+        let key = match node.get_path_key() {
+            Some(value) => value,
+            None => continue,
+        };
+        let rest = match path.strip_prefix(key.as_str()) {
+            Some(value) => value,
+            None => continue,
+        };
+        if !(rest.is_empty() || rest.starts_with('.') || rest.starts_with('[')) {
+            continue;
+        }
+        if found.is_none_or(|(_node, found_rest)| rest.len() < found_rest.len()) {
+            found = Some((node, rest));
+        }
+    }
+    let (node, rest) = found?;
+    let rest = rest.strip_prefix('.').unwrap_or(rest);
+    if rest.is_empty() {
+        return Some(node);
+    }
+    find_yaml_node(&node.children, rest).or(Some(node))
+}
+
+/// Validates yaml files and writes the problems with their locations in JSON format.
+fn validate_files_json(
+    stream: &mut dyn Write,
+    ctx: &context::Context,
+    yaml_paths: &[&String],
+    with_db: bool,
+) -> anyhow::Result<()> {
+    let mut diagnostics: Vec<JsonDiagnostic> = Vec::new();
+    for yaml_path in yaml_paths {
+        let errors = match validate_file(ctx, yaml_path, with_db) {
+            Ok(value) => value,
+            Err(err) => {
+                // Not well-formed or not the expected structure: the parser knows the location,
+                // if anything.
+                let location = err
+                    .chain()
+                    .find_map(|i| i.downcast_ref::<serde_yaml::Error>())
+                    .and_then(|i| i.location());
+                diagnostics.push(JsonDiagnostic {
+                    file: yaml_path.to_string(),
+                    line: location.as_ref().map(|i| i.line()),
+                    column: location.as_ref().map(|i| i.column()),
+                    path: "".into(),
+                    severity: Severity::Error.to_string(),
+                    message: format!("{err:#}"),
+                });
+                continue;
+            }
+        };
+
+        let data = ctx.get_file_system().read_to_string(yaml_path)?;
+        let lines: Vec<&str> = data.lines().collect();
+        let mut pos = 0;
+        let (nodes, _trailing_comments) = parse_yaml_nodes(&lines, &mut pos, None);
+        for error in errors {
+            let node = find_yaml_node(&nodes, &error.path);
+            diagnostics.push(JsonDiagnostic {
+                file: yaml_path.to_string(),
+                line: node.map(|i| i.line_number),
+                column: node.map(|i| i.get_column()),
+                path: error.path,
+                severity: error.severity.to_string(),
+                message: error.message,
+            });
+        }
+    }
+
+    stream.write_all(serde_json::to_string_pretty(&diagnostics)?.as_bytes())?;
+    stream.write_all(b"\n")?;
+    let severity = Severity::Error.to_string();
+    if diagnostics.iter().any(|i| i.severity == severity) {
+        return Err(anyhow::Error::new(ReportedError));
+    }
+
+    Ok(())
+}
+
+/// Formats yaml files into their canonical form, or just reports the files which are not in that
+/// form.
+fn format_files(
+    stream: &mut dyn Write,
+    ctx: &context::Context,
+    yaml_paths: &[&String],
+    fix: bool,
+) -> anyhow::Result<()> {
+    let mut unformatted = 0;
+    for yaml_path in yaml_paths {
+        let data = ctx.get_file_system().read_to_string(yaml_path)?;
+        // Don't try to format data which is not well-formed.
+        serde_yaml::from_str::<serde_yaml::Value>(&data)
+            .context(format!("failed to parse {yaml_path}"))?;
+        let formatted = format_yaml(yaml_path, &data);
+        if formatted == data {
+            continue;
+        }
+
+        if fix {
+            ctx.get_file_system()
+                .write_from_string(&formatted, yaml_path)?;
+            stream.write_all(format!("reformatted {yaml_path}\n").as_bytes())?;
+        } else {
+            stream.write_all(format!("would reformat {yaml_path}\n").as_bytes())?;
+            unformatted += 1;
+        }
+    }
+    if unformatted > 0 {
+        return Err(anyhow::anyhow!(
+            "{unformatted} file(s) would be reformatted"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Lists the redundant 'invalid' items of a relation and the diff to remove them.
+fn write_redundant_invalids(
+    stream: &mut dyn Write,
+    ctx: &context::Context,
+    relations: &mut areas::Relations<'_>,
+    yaml_path: &str,
+    relation_name: &str,
+) -> anyhow::Result<()> {
+    let data = ctx.get_file_system().read_to_string(yaml_path)?;
+    let relation_dict: areas::RelationDict =
+        serde_yaml::from_str(&data).context(format!("failed to parse {yaml_path}"))?;
+    let mut relation = relations.get_relation(relation_name)?;
+    let mut config = relation.get_config().clone();
+    config.set_dict(&relation_dict);
+    relation.set_config(&config);
+
+    let redundant = relation.get_redundant_invalids()?;
+    for street in &redundant {
+        let housenumbers = street.housenumbers.join(", ");
+        let line = format!("{relation_name}: {}: {housenumbers}\n", street.street_name);
+        stream.write_all(line.as_bytes())?;
+    }
+    stream.write_all(get_redundant_invalids_diff(yaml_path, &data, &redundant).as_bytes())?;
+    Ok(())
+}
+
+/// Lists the 'invalid' items which are redundant, as ranges already filter them out, with a diff
+/// to remove them. relations.yaml means all relation-foo.yaml files next to it.
+fn redundant_invalids_files(
+    stream: &mut dyn Write,
+    ctx: &context::Context,
+    yaml_paths: &[&String],
+) -> anyhow::Result<()> {
+    let mut relations = areas::Relations::new(ctx)?;
+    for yaml_path in yaml_paths {
+        let path = std::path::Path::new(yaml_path);
+        let file_name = path
+            .file_name()
+            .context("no file name")?
+            .to_string_lossy()
+            .to_string();
+        if file_name == "relations.yaml" {
+            let mut names = relations.get_names();
+            names.sort();
+            for relation_name in names {
+                let sibling = path
+                    .with_file_name(format!("relation-{relation_name}.yaml"))
+                    .to_string_lossy()
+                    .to_string();
+                if !ctx.get_file_system().path_exists(&sibling) {
+                    continue;
+                }
+                write_redundant_invalids(stream, ctx, &mut relations, &sibling, &relation_name)?;
+            }
+            continue;
+        }
+
+        let relation_name = match file_name
+            .strip_prefix("relation-")
+            .and_then(|i| i.strip_suffix(".yaml"))
+        {
+            Some(value) => value.to_string(),
+            None => {
+                return Err(anyhow::anyhow!(
+                    "expected a relation yaml file: {yaml_path}"
+                ));
+            }
+        };
+        if !relations.get_names().contains(&relation_name) {
+            return Err(anyhow::anyhow!(
+                "expected '{relation_name}' to be in relations.yaml"
+            ));
+        }
+        write_redundant_invalids(stream, ctx, &mut relations, yaml_path, &relation_name)?;
+    }
+    Ok(())
+}
+
 /// Similar to plain main(), but with an interface that allows testing.
 pub fn main(argv: &[String], stream: &mut dyn Write, ctx: &context::Context) -> i32 {
     match our_main(argv, stream, ctx) {
         Ok(_) => 0,
         Err(err) => {
-            stream
-                .write_all(format!("{err:?}\n").as_bytes())
-                .expect("write_all() failed");
+            if !err.is::<ReportedError>() {
+                stream
+                    .write_all(format!("{err:?}\n").as_bytes())
+                    .expect("write_all() failed");
+            }
             1
         }
     }
 }
 
-/// Inner main() that is allowed to fail.
-pub fn our_main(
-    argv: &[String],
-    stream: &mut dyn Write,
+/// Validates a single yaml file, returns the found problems.
+fn validate_file(
     ctx: &context::Context,
-) -> anyhow::Result<()> {
-    let yaml_path = &argv[1];
+    yaml_path: &str,
+    with_db: bool,
+) -> anyhow::Result<Vec<Diagnostic>> {
     let data = ctx.get_file_system().read_to_string(yaml_path)?;
-    let mut errors: Vec<String> = Vec::new();
+    let mut errors: Vec<Diagnostic> = Vec::new();
 
     if data.contains('\t') {
         // serde can parse this, but not some of the 3rd-party parsers.
-        errors.push("expected indent with 2 spaces, not with tabs".to_string());
+        errors.push(Diagnostic::new(
+            "",
+            "expected indent with 2 spaces, not with tabs",
+        ));
     }
 
     if yaml_path.ends_with("relations.yaml") {
         let relations_dict: areas::RelationsDict =
             serde_yaml::from_str(&data).context("serde_yaml::from_str() failed")?;
         validate_relations(&mut errors, &relations_dict)?;
+        validate_relations_across(&mut errors, ctx, yaml_path, &relations_dict)?;
+        if with_db {
+            validate_db(&mut errors, ctx, yaml_path, None)?;
+        }
     } else {
         // This will fail if the data is not well-formed (e.g. in case of duplicated keys):
         serde_yaml::from_str::<serde_yaml::Value>(&data)
@@ -266,12 +1435,80 @@ pub fn our_main(
             .context("failed to parse as json")?;
         let parent = "";
         validate_relation(&mut errors, parent, &relation_dict)?;
+        if with_db {
+            validate_db(&mut errors, ctx, yaml_path, Some(&relation_dict))?;
+        }
     }
-    if !errors.is_empty() {
-        for error in errors {
-            stream.write_all(format!("{error}\n").as_bytes())?;
+    Ok(errors)
+}
+
+/// Inner main() that is allowed to fail.
+pub fn our_main(
+    argv: &[String],
+    stream: &mut dyn Write,
+    ctx: &context::Context,
+) -> anyhow::Result<()> {
+    let yaml = clap::Arg::new("yaml")
+        .required(true)
+        .num_args(1..)
+        .help("the yaml files to validate");
+    let with_db = clap::Arg::new("with-db")
+        .long("with-db")
+        .action(clap::ArgAction::SetTrue)
+        .help("also check the relations against the OSM and reference data in the database");
+    let fix = clap::Arg::new("fix")
+        .long("fix")
+        .action(clap::ArgAction::SetTrue)
+        .conflicts_with("check")
+        .help("rewrite the yaml files into their canonical form instead of validating them");
+    let check = clap::Arg::new("check")
+        .long("check")
+        .action(clap::ArgAction::SetTrue)
+        .help("report the yaml files which are not in their canonical form");
+    let format = clap::Arg::new("format")
+        .long("format")
+        .default_value("text")
+        .value_parser(["text", "json"])
+        .help("the output format of the validation problems [text or json; default: text]");
+    let redundant_invalids = clap::Arg::new("redundant-invalids")
+        .long("redundant-invalids")
+        .action(clap::ArgAction::SetTrue)
+        .conflicts_with_all(["fix", "check"])
+        .help("list the 'invalid' items which are already filtered out by ranges, with a diff to remove them");
+    let args = [yaml, with_db, fix, check, format, redundant_invalids];
+    let app = clap::Command::new("osm-gimmisn");
+    let args = app.args(&args).try_get_matches_from(argv)?;
+    let yaml_paths: Vec<&String> = args.get_many::<String>("yaml").unwrap().collect();
+    let with_db = *args.get_one::<bool>("with-db").unwrap();
+    let fix = *args.get_one::<bool>("fix").unwrap();
+    let check = *args.get_one::<bool>("check").unwrap();
+    let format = args.get_one::<String>("format").unwrap();
+    let redundant_invalids = *args.get_one::<bool>("redundant-invalids").unwrap();
+
+    if redundant_invalids {
+        return redundant_invalids_files(stream, ctx, &yaml_paths);
+    }
+
+    if fix || check {
+        return format_files(stream, ctx, &yaml_paths, fix);
+    }
+
+    if format == "json" {
+        return validate_files_json(stream, ctx, &yaml_paths, with_db);
+    }
+
+    for yaml_path in yaml_paths {
+        let errors = validate_file(ctx, yaml_path, with_db)?;
+        for error in &errors {
+            if error.severity == Severity::Warning {
+                stream.write_all(format!("warning: {}\n", error.message).as_bytes())?;
+            } else {
+                stream.write_all(format!("{}\n", error.message).as_bytes())?;
+            }
+        }
+        if errors.iter().any(|i| i.severity == Severity::Error) {
+            return Err(anyhow::anyhow!("failed to validate {}", yaml_path));
         }
-        return Err(anyhow::anyhow!("failed to validate {}", yaml_path));
     }
 
     Ok(())
@@ -382,3 +382,1011 @@ fn test_end_whitespace() {
     let expected = "expected value type for 'filters.Budaörsi út.ranges[0].end' is a digit str\nfailed to validate {0}\n";
     assert_failure_msg(content, expected);
 }
+
+/// Tests that except items and lettered bounds are accepted in ranges.
+#[test]
+fn test_relation_filters_ranges_except_letters() {
+    let content = r#"filters:
+  'Budaörsi út':
+    ranges:
+      - {start: '1', end: '51', except: ['13', '15/A', '17a']}
+      - {start: '2', end: '20/b'}
+"#;
+    assert_success(content);
+}
+
+/// Tests the relation path: bad filters -> ... -> ranges -> except: bad format.
+#[test]
+fn test_relation_filters_ranges_except_bad() {
+    let content = r#"filters:
+  'Budaörsi út':
+    ranges:
+      - {start: '1', end: '51', except: ['13 ']}
+"#;
+    let expected = "expected format for 'filters.Budaörsi út.ranges[0].except[0]' is '42' or '42/B'\nfailed to validate {0}\n";
+    assert_failure_msg(content, expected);
+}
+
+/// Tests the relation path: bad filters -> ... -> ranges -> except: out of range.
+#[test]
+fn test_relation_filters_ranges_except_out_of_range() {
+    let content = r#"filters:
+  'Budaörsi út':
+    ranges:
+      - {start: '1', end: '51', except: ['53']}
+"#;
+    let expected = "expected 'filters.Budaörsi út.ranges[0].except[0]' to be between start and end\nfailed to validate {0}\n";
+    assert_failure_msg(content, expected);
+}
+
+/// Tests the relation path: bad filters -> ... -> ranges -> lettered start/end is swapped.
+#[test]
+fn test_relation_filters_ranges_letters_swap() {
+    let content = r#"filters:
+  'Budaörsi út':
+    ranges:
+      - {start: '20/B', end: '20/A'}
+"#;
+    let expected =
+        "expected end >= start for 'filters.Budaörsi út.ranges[0]'\nfailed to validate {0}\n";
+    assert_failure_msg(content, expected);
+}
+
+/// Runs main() with the given arguments, the last one is a path relative to the root. `files` are
+/// (path, content) pairs for the file system, `sql` initializes the database. In the output, the
+/// absolute path of the last argument is replaced with '{0}' and other paths are made relative.
+/// The context is returned, so the written files can be inspected.
+fn run_main(args: &[&str], files: &[(&str, &str)], sql: &str) -> (i32, String, context::Context) {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let (path, args) = args.split_last().unwrap();
+    let mut argv: Vec<String> = vec!["".into()];
+    argv.extend(args.iter().map(|i| i.to_string()));
+    argv.push(ctx.get_abspath(path));
+    let mut buf: std::io::Cursor<Vec<u8>> = std::io::Cursor::new(Vec::new());
+    let file_list: Vec<_> = files
+        .iter()
+        .map(|(path, content)| {
+            let file = context::tests::TestFileSystem::make_file();
+            file.borrow_mut().write_all(content.as_bytes()).unwrap();
+            (*path, file)
+        })
+        .collect();
+    let file_refs: Vec<_> = file_list.iter().map(|(k, v)| (*k, v)).collect();
+    let files = context::tests::TestFileSystem::make_files(&ctx, &file_refs);
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    {
+        let conn = ctx.get_database_connection().unwrap();
+        conn.execute_batch(sql).unwrap();
+    }
+
+    let ret = main(&argv, &mut buf, &ctx);
+
+    let output = String::from_utf8(buf.into_inner())
+        .unwrap()
+        .replace(&ctx.get_abspath(path), "{0}")
+        .replace(&ctx.get_abspath(""), "");
+    (ret, output, ctx)
+}
+
+/// Runs main() with --with-db on a given input (path, content), with a given database state.
+fn validate_with_db(path: &str, content: &str, sql: &str) -> (i32, String) {
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "osmrelation": 42,
+                "refcounty": "0",
+                "refsettlement": "0",
+            },
+            "otherrelation": {
+                "osmrelation": 43,
+                "refcounty": "0",
+                "refsettlement": "0",
+            },
+        },
+        "relation-myrelation.yaml": {
+            "filters": {
+                "Nincs utca": {
+                    "invalid": ["1"],
+                },
+            },
+        },
+        "relation-otherrelation.yaml": {
+            "filters": {
+                "Nincs utca": {
+                    "invalid": ["1"],
+                },
+            },
+        },
+    });
+    let yamls_cache = yamls_cache.to_string();
+    let files = [("data/yamls.cache", yamls_cache.as_str()), (path, content)];
+    let (ret, output, _ctx) = run_main(&["--with-db", path], &files, sql);
+    (ret, output)
+}
+
+/// Tests main(): the --with-db relation path.
+#[test]
+fn test_relation_with_db() {
+    let content = r#"refstreets:
+  'Kossuth u.': 'Kossuth utca'
+filters:
+  'Fő utca':
+    # '3' is out of range, not deleted from the reference.
+    invalid: ['3', '5']
+    ranges:
+      - {start: '1', end: '1'}
+  'Nincs utca':
+    invalid: ['1']
+"#;
+    let sql = "insert into mtimes (page, last_modified) values ('streets/myrelation', '0');
+        insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('myrelation', '1', 'Fő utca', '', '', '', '', 'way');
+        insert into ref_streets (county_code, settlement_code, street) values ('0', '0', 'Fő utca');
+        insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Fő utca', '1', '');
+        insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Fő utca', '3', '');";
+
+    let (ret, output) = validate_with_db("data/relation-myrelation.yaml", content, sql);
+
+    assert_eq!(ret, 1);
+    let expected = r#"expected OSM street name for 'refstreets.Kossuth u.'
+expected reference street name, which is not an OSM street name, for value 'Kossuth utca' in 'refstreets'
+expected OSM street name for 'filters.Nincs utca'
+expected '5' in 'filters.Fő utca.invalid' to match a reference house number
+failed to validate {0}
+"#;
+    assert_eq!(output, expected);
+}
+
+/// Tests main(): the --with-db relation path, when there is no OSM data for the relation yet.
+#[test]
+fn test_relation_with_db_no_osm_data() {
+    let content = r#"filters:
+  'Nincs utca':
+    invalid: ['1']
+"#;
+
+    let (ret, output) = validate_with_db("data/relation-myrelation.yaml", content, "");
+
+    assert_eq!(ret, 0);
+    assert_eq!(output, "");
+}
+
+/// Tests main(): the --with-db relation path, when the relation is not in relations.yaml.
+#[test]
+fn test_relation_with_db_unknown() {
+    let content = "source: survey\n";
+
+    let (ret, output) = validate_with_db("data/relation-unknown.yaml", content, "");
+
+    assert_eq!(ret, 1);
+    assert_eq!(
+        output,
+        "expected 'unknown' to be in relations.yaml\nfailed to validate {0}\n"
+    );
+}
+
+/// Tests main(): the --with-db path, when the file is not a relation one.
+#[test]
+fn test_relation_with_db_not_relation() {
+    let content = "source: survey\n";
+
+    let (ret, output) = validate_with_db("data/myrelation.yaml", content, "");
+
+    assert_eq!(ret, 0);
+    assert_eq!(output, "");
+}
+
+/// Tests main(): the --with-db relations path.
+#[test]
+fn test_relations_with_db() {
+    let content = r#"myrelation:
+    osmrelation: 42
+    refcounty: "0"
+    refsettlement: "0"
+"#;
+    // otherrelation has no OSM data, so that is not checked.
+    let sql = "insert into mtimes (page, last_modified) values ('streets/myrelation', '0');";
+
+    let (ret, output) = validate_with_db("data/relations.yaml", content, sql);
+
+    assert_eq!(ret, 1);
+    let expected = r#"expected OSM street name for 'myrelation.filters.Nincs utca'
+failed to validate {0}
+"#;
+    assert_eq!(output, expected);
+}
+
+/// Tests format_yaml(): the relation case.
+#[test]
+fn test_format_yaml_relation() {
+    let content = r#"# comment at the top
+missing-streets: 'yes'
+alias: ["b", 'a']
+refstreets:
+  'Zeta utca': "Zeta u."
+  # commented out
+  Alfa utca: 'Alfa u.' # inline comment
+  'Kossuth #1': 42
+filters:
+  Zeta utca:
+    invalid: [ '3c', '10b', '3c']
+    ranges:
+      - {start: "2", end: '10'}
+      - {end: '9', start: '1', except: ['7', '5'], refsettlement: '012'}
+    # trailing comment of Zeta utca
+
+  'Alfa utca':  # inline comment
+    invalid:
+      - '5' # five
+      - '3'
+      - '5' # five again
+      - 42
+    ranges:
+      - {start: '1', end: '9', foo: 'bar'}
+  '123':
+    invalid: [1]
+    valid: ['1']
+    1: foo
+  Beta utca:
+    invalid:
+      foo: bar
+    ranges:
+      - {start: 1, end: '9'}
+      - {start: '1', except: [1]}
+      - {start: '1'}
+      - 'foo'
+  Gamma utca: {}
+# end of file
+"#;
+    let expected = r#"# comment at the top
+missing-streets: 'yes'
+alias: ['b', 'a']
+refstreets:
+  # commented out
+  'Alfa utca': 'Alfa u.' # inline comment
+  'Kossuth #1': 42
+  'Zeta utca': 'Zeta u.'
+filters:
+  '123':
+    invalid: [1]
+    valid: ['1']
+    1: foo
+
+  Alfa utca:  # inline comment
+    invalid:
+      - '3'
+      # five again
+      - '5' # five
+      - 42
+    ranges:
+      - {start: '1', end: '9', foo: 'bar'}
+  Beta utca:
+    invalid:
+      foo: bar
+    ranges:
+      - {start: 1, end: '9'}
+      - {start: '1', except: [1]}
+      - {start: '1'}
+      - 'foo'
+  Gamma utca: {}
+  Zeta utca:
+    invalid: ['3c', '10b']
+    ranges:
+      - {start: '1', end: '9', refsettlement: '012', except: ['5', '7']}
+      - {start: '2', end: '10'}
+    # trailing comment of Zeta utca
+# end of file
+"#;
+
+    assert_eq!(
+        format_yaml("data/relation-myrelation.yaml", content),
+        expected
+    );
+}
+
+/// Tests format_yaml(): the relations case.
+#[test]
+fn test_format_yaml_relations() {
+    let content = r#"# budapest
+b:
+    osmrelation: 2
+    refcounty: '01'
+    refsettlement: "011"
+    alias: ["x"]
+a:
+    osmrelation: 1
+    refcounty: 1
+    refsettlement: '012'
+    alias: 1
+c:
+    - x
+"#;
+    let expected = r#"# budapest
+b:
+    osmrelation: 2
+    refcounty: "01"
+    refsettlement: "011"
+    alias: ['x']
+a:
+    osmrelation: 1
+    refcounty: 1
+    refsettlement: "012"
+    alias: 1
+c:
+    - x
+"#;
+
+    assert_eq!(format_yaml("data/relations.yaml", content), expected);
+}
+
+/// Runs main() with the given extra arguments on a given relation file content.
+fn format_with_main(args: &[&str], content: &str) -> (i32, String, String) {
+    let path = "data/relation-myrelation.yaml";
+    let mut args = args.to_vec();
+    args.push(path);
+    let (ret, output, ctx) = run_main(&args, &[(path, content)], "");
+    let written = ctx
+        .get_file_system()
+        .read_to_string(&ctx.get_abspath(path))
+        .unwrap();
+    (ret, output, written)
+}
+
+/// Tests main(): the --fix case.
+#[test]
+fn test_main_fix() {
+    let content = r#"filters:
+  B utca:
+    invalid: ['2']
+  A utca:
+    invalid: ['1']
+"#;
+
+    let (ret, output, written) = format_with_main(&["--fix"], content);
+
+    assert_eq!(ret, 0);
+    assert_eq!(output, "reformatted {0}\n");
+    let expected = r#"filters:
+  A utca:
+    invalid: ['1']
+  B utca:
+    invalid: ['2']
+"#;
+    assert_eq!(written, expected);
+}
+
+/// Tests main(): the --check case.
+#[test]
+fn test_main_check() {
+    let content = r#"filters:
+  B utca:
+    invalid: ['2']
+  A utca:
+    invalid: ['1']
+"#;
+
+    let (ret, output, written) = format_with_main(&["--check"], content);
+
+    assert_eq!(ret, 1);
+    assert_eq!(
+        output,
+        "would reformat {0}\n1 file(s) would be reformatted\n"
+    );
+    // Not modified.
+    assert_eq!(written, content);
+}
+
+/// Tests main(): the --check case, when the file is already formatted.
+#[test]
+fn test_main_check_formatted() {
+    let content = r#"filters:
+  A utca:
+    invalid: ['1']
+"#;
+
+    let (ret, output, _written) = format_with_main(&["--check"], content);
+
+    assert_eq!(ret, 0);
+    assert_eq!(output, "");
+}
+
+/// Tests main(): the --check case, when the file is not well-formed.
+#[test]
+fn test_main_check_bad_yaml() {
+    let content = "filters: [\n";
+
+    let (ret, output, _written) = format_with_main(&["--check"], content);
+
+    assert_eq!(ret, 1);
+    assert_eq!(output.starts_with("failed to parse {0}"), true);
+}
+
+/// Runs main() with --format json on a given input (path, content).
+fn validate_json(path: &str, content: &str) -> (i32, serde_json::Value) {
+    let (ret, output, _ctx) = run_main(&["--format", "json", path], &[(path, content)], "");
+    (ret, serde_json::from_str(&output).unwrap())
+}
+
+/// Tests main(): the --format json case, relation file.
+#[test]
+fn test_main_json() {
+    let content = r#"# comment
+filters:
+  1. utca:
+    ranges:
+      - {start: '9', end: '1'}
+      - {start: '1', end: '9', except: ['11']}
+    invalid: ['1', 'x']
+"#;
+
+    let (ret, output) = validate_json("data/relation-myrelation.yaml", content);
+
+    assert_eq!(ret, 1);
+    let expected = serde_json::json!([
+        {
+            "file": "{0}",
+            "line": 5,
+            "column": 7,
+            "path": "filters.1. utca.ranges[0]",
+            "severity": "error",
+            "message": "expected end >= start for 'filters.1. utca.ranges[0]'",
+        },
+        {
+            "file": "{0}",
+            "line": 6,
+            "column": 7,
+            "path": "filters.1. utca.ranges[1].except[0]",
+            "severity": "error",
+            "message": "expected 'filters.1. utca.ranges[1].except[0]' to be between start and end",
+        },
+        {
+            "file": "{0}",
+            "line": 7,
+            "column": 5,
+            "path": "filters.1. utca.invalid[1]",
+            "severity": "error",
+            "message": "expected format for 'filters.1. utca.invalid[1]' is '42', '42a' or '42/1'",
+        },
+    ]);
+    assert_eq!(output, expected);
+}
+
+/// Tests main(): the --format json case, relations file.
+#[test]
+fn test_main_json_relations() {
+    let content = r#"gazdagret:
+    osmrelation: 42
+    refsettlement: "011"
+"#;
+
+    let (ret, output) = validate_json("data/relations.yaml", content);
+
+    assert_eq!(ret, 1);
+    let expected = serde_json::json!([
+        {
+            "file": "{0}",
+            "line": 1,
+            "column": 1,
+            "path": "gazdagret",
+            "severity": "error",
+            "message": "missing key 'gazdagret.refcounty'",
+        },
+    ]);
+    assert_eq!(output, expected);
+}
+
+/// Tests main(): the --format json case, problem without a location.
+#[test]
+fn test_main_json_no_location() {
+    let content = "source:\tsurvey\n";
+
+    let (ret, output) = validate_json("data/relation-myrelation.yaml", content);
+
+    assert_eq!(ret, 1);
+    let expected = serde_json::json!([
+        {
+            "file": "{0}",
+            "line": null,
+            "column": null,
+            "path": "",
+            "severity": "error",
+            "message": "expected indent with 2 spaces, not with tabs",
+        },
+    ]);
+    assert_eq!(output, expected);
+}
+
+/// Tests main(): the --format json case, when the file is not well-formed.
+#[test]
+fn test_main_json_parse_error() {
+    let content = r#"filters:
+  A:
+    invalid: ['1']
+  A:
+    invalid: ['2']
+"#;
+
+    let (ret, output) = validate_json("data/relation-myrelation.yaml", content);
+
+    assert_eq!(ret, 1);
+    let diagnostics = output.as_array().unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    // serde_yaml reports the start of the mapping which has the duplicated key.
+    assert_eq!(diagnostics[0]["line"], 2);
+    assert_eq!(diagnostics[0]["column"], 3);
+    assert_eq!(diagnostics[0]["path"], "");
+}
+
+/// Tests main(): the --format json case, when there are no problems.
+#[test]
+fn test_main_json_valid() {
+    let content = r#"filters:
+  A:
+    invalid: ['1']
+"#;
+
+    let (ret, output) = validate_json("data/relation-myrelation.yaml", content);
+
+    assert_eq!(ret, 0);
+    assert_eq!(output, serde_json::json!([]));
+}
+
+/// Tests the Display impl of ReportedError.
+#[test]
+fn test_reported_error_display() {
+    assert_eq!(ReportedError.to_string(), "validation failed");
+}
+
+/// Tests find_yaml_node().
+#[test]
+fn test_find_yaml_node() {
+    let content = r#"A:
+  - y: 1
+  x: 1
+AB:
+  y: 1
+A.B:
+  z: 1
+1:
+  x: 1
+true:
+  x: 1
+"#;
+    let lines: Vec<&str> = content.lines().collect();
+    let mut pos = 0;
+    let (nodes, _trailing_comments) = parse_yaml_nodes(&lines, &mut pos, None);
+
+    let line_number = |path: &str| find_yaml_node(&nodes, path).map(|i| i.line_number);
+
+    // AB is a better match than A.
+    assert_eq!(line_number("AB.y"), Some(5));
+    // A.B is a better match than A.
+    assert_eq!(line_number("A.B.z"), Some(7));
+    // Not found: the longest found prefix.
+    assert_eq!(line_number("A.y"), Some(1));
+    assert_eq!(line_number("A[2]"), Some(1));
+    // Number keys are found as well.
+    assert_eq!(line_number("1.x"), Some(9));
+    // Bool keys are not.
+    assert_eq!(line_number("true.x"), None);
+    assert_eq!(line_number("nope"), None);
+    assert_eq!(line_number("[x]"), None);
+}
+
+/// Runs main() on data/relations.yaml, with the given sibling files.
+fn validate_relations_with(
+    format: &str,
+    content: &str,
+    siblings: &[(&str, &str)],
+) -> (i32, String) {
+    let path = "data/relations.yaml";
+    let sibling_paths: Vec<String> = siblings
+        .iter()
+        .map(|(name, _)| format!("data/{name}"))
+        .collect();
+    let mut files = vec![(path, content)];
+    for (sibling_path, (_, sibling)) in sibling_paths.iter().zip(siblings) {
+        files.push((sibling_path, sibling));
+    }
+    let (ret, output, _ctx) = run_main(&["--format", format, path], &files, "");
+    (ret, output)
+}
+
+/// Tests main(): duplicate osmrelation values and aliases across relations.
+#[test]
+fn test_relations_duplicates() {
+    let content = r#"bar:
+    osmrelation: 42
+    refcounty: "01"
+    refsettlement: "011"
+    alias: ['baz', 'foo']
+foo:
+    osmrelation: 42
+    refcounty: "01"
+    refsettlement: "011"
+    alias: ['baz', 'qux']
+"#;
+
+    let (ret, output) = validate_relations_with("text", content, &[]);
+
+    assert_eq!(ret, 1);
+    let expected = r#"expected unique value for 'bar.alias[1]', already used by 'foo'
+expected unique value for 'foo.osmrelation', already used by 'bar'
+expected unique value for 'foo.alias[0]', already used by 'bar'
+failed to validate {0}
+"#;
+    assert_eq!(output, expected);
+}
+
+/// Tests main(): refcounty and refsettlement codes not in the names files.
+#[test]
+fn test_relations_unknown_codes() {
+    let content = r#"bar:
+    osmrelation: 42
+    refcounty: "01"
+    refsettlement: "012"
+foo:
+    osmrelation: 43
+    refcounty: "02"
+    refsettlement: "011"
+"#;
+    let county_names = "'01': 'Budapest'\n";
+    let settlement_names = "'01':\n  '011': 'Újbuda'\n";
+    let siblings = [
+        ("refcounty-names.yaml", county_names),
+        ("refsettlement-names.yaml", settlement_names),
+    ];
+
+    let (ret, output) = validate_relations_with("text", content, &siblings);
+
+    assert_eq!(ret, 1);
+    // foo.refsettlement is not checked, there are no settlement names for its county.
+    let expected = r#"warning: expected 'bar.refsettlement' to be in refsettlement-names.yaml
+expected 'foo.refcounty' to be in refcounty-names.yaml
+failed to validate {0}
+"#;
+    assert_eq!(output, expected);
+}
+
+/// Tests main(): warnings alone don't fail the validation.
+#[test]
+fn test_relations_warnings_only() {
+    let content = r#"bar:
+    osmrelation: 42
+    refcounty: "01"
+    refsettlement: "012"
+"#;
+    let siblings = [("refsettlement-names.yaml", "'01':\n  '011': 'Újbuda'\n")];
+
+    let (ret, output) = validate_relations_with("text", content, &siblings);
+
+    assert_eq!(ret, 0);
+    assert_eq!(
+        output,
+        "warning: expected 'bar.refsettlement' to be in refsettlement-names.yaml\n"
+    );
+
+    let (ret, output) = validate_relations_with("json", content, &siblings);
+
+    assert_eq!(ret, 0);
+    let output: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let expected = serde_json::json!([
+        {
+            "file": "{0}",
+            "line": 4,
+            "column": 5,
+            "path": "bar.refsettlement",
+            "severity": "warning",
+            "message": "expected 'bar.refsettlement' to be in refsettlement-names.yaml",
+        },
+    ]);
+    assert_eq!(output, expected);
+}
+
+/// Tests main(): partially overlapping ranges of the same street in relations of the same
+/// refsettlement.
+#[test]
+fn test_relations_overlapping_filters() {
+    let content = r#"bare:
+    osmrelation: 45
+    refcounty: "01"
+    refsettlement: "011"
+district:
+    osmrelation: 42
+    refcounty: "01"
+    refsettlement: "011"
+other:
+    osmrelation: 43
+    refcounty: "01"
+    refsettlement: "012"
+subarea:
+    osmrelation: 44
+    refcounty: "01"
+    refsettlement: "011"
+"#;
+    // 'A utca' is contained, 'B utca' is disjoint, 'C utca' overlaps partially, 'D utca' is only
+    // in one relation, 'E utca' is disjoint due to the except, 'F utca' has a bad bound.
+    let district = r#"filters:
+  A utca:
+    ranges:
+      - {start: '1', end: '99'}
+  B utca:
+    ranges:
+      - {start: '1', end: '9'}
+  C utca:
+    ranges:
+      - {start: '1', end: '9'}
+  E utca:
+    ranges:
+      - {start: '1', end: '9', except: ['9', '7/A']}
+  F utca:
+    ranges:
+      - {start: '1', end: 'x'}
+"#;
+    let other = r#"filters:
+  C utca:
+    ranges:
+      - {start: '1', end: '99'}
+"#;
+    let subarea = r#"filters:
+  A utca:
+    ranges:
+      - {start: '1', end: '9'}
+  B utca:
+    interpolation: all
+    ranges:
+      - {start: '10', end: '20'}
+  C utca:
+    ranges:
+      - {start: '5', end: '15'}
+  D utca:
+    ranges:
+      - {start: '1', end: '9'}
+  E utca:
+    ranges:
+      - {start: '9', end: '11'}
+  F utca:
+    ranges:
+      - {start: '1', end: '9'}
+"#;
+    let siblings = [
+        ("relation-district.yaml", district),
+        ("relation-other.yaml", other),
+        ("relation-subarea.yaml", subarea),
+    ];
+
+    let (ret, output) = validate_relations_with("text", content, &siblings);
+
+    assert_eq!(ret, 0);
+    let expected = r#"warning: expected 'filters.C utca.ranges' in relation-district.yaml and relation-subarea.yaml to be either disjoint or contain each other, as both have the same refsettlement
+"#;
+    assert_eq!(output, expected);
+}
+
+/// Tests main(): a sibling yaml file which fails to parse.
+#[test]
+fn test_relations_bad_sibling() {
+    let content = r#"bar:
+    osmrelation: 42
+    refcounty: "01"
+    refsettlement: "011"
+"#;
+    let siblings = [("relation-bar.yaml", "filters: [\n")];
+
+    let (ret, output) = validate_relations_with("text", content, &siblings);
+
+    assert_eq!(ret, 1);
+    assert!(output.starts_with("failed to parse "));
+}
+
+/// Tests the refcounty check in the overlap check: relations without a refcounty are skipped.
+#[test]
+fn test_relations_overlapping_filters_no_refcounty() {
+    let content = r#"bar:
+    osmrelation: 42
+    refsettlement: "011"
+foo:
+    osmrelation: 43
+    refsettlement: "011"
+"#;
+    let filters = "filters:\n  A utca:\n    ranges:\n      - {start: '1', end: '9'}\n";
+    let other_filters = "filters:\n  A utca:\n    ranges:\n      - {start: '5', end: '15'}\n";
+    let siblings = [
+        ("relation-bar.yaml", filters),
+        ("relation-foo.yaml", other_filters),
+    ];
+
+    let (ret, output) = validate_relations_with("text", content, &siblings);
+
+    assert_eq!(ret, 1);
+    let mut lines: Vec<&str> = output.lines().collect();
+    lines.sort();
+    let expected = [
+        "failed to validate {0}",
+        "missing key 'bar.refcounty'",
+        "missing key 'foo.refcounty'",
+    ];
+    assert_eq!(lines, expected);
+}
+
+/// Tests get_redundant_invalids_diff().
+#[test]
+fn test_get_redundant_invalids_diff() {
+    // A is a partial flow list, B is a full flow list, F is a partial block list, G is a full
+    // block list, H is not redundant.
+    let data = r#"filters:
+  A utca:
+    # comment
+    invalid: ['1', '11']
+    ranges:
+      - {start: '1', end: '9'}
+  B utca:
+    invalid: ['11']
+    ranges:
+      - {start: '1', end: '9'}
+  C utca:
+    interpolation: all
+  D utca:
+    interpolation: all
+  E utca:
+    interpolation: all
+  F utca:
+    invalid:
+      - '3'
+      - '13'  # out
+    ranges:
+      - {start: '1', end: '9'}
+  G utca:
+    invalid:
+      - '13'
+    ranges:
+      - {start: '1', end: '9'}
+  H utca:
+    invalid: ['13']
+refstreets:
+  'X': 'Y'
+"#;
+    let redundant = vec![
+        areas::RedundantInvalids {
+            street_name: "A utca".into(),
+            housenumbers: vec!["11".into()],
+        },
+        areas::RedundantInvalids {
+            street_name: "B utca".into(),
+            housenumbers: vec!["11".into()],
+        },
+        areas::RedundantInvalids {
+            street_name: "F utca".into(),
+            housenumbers: vec!["13".into()],
+        },
+        areas::RedundantInvalids {
+            street_name: "G utca".into(),
+            housenumbers: vec!["13".into()],
+        },
+    ];
+
+    let diff = get_redundant_invalids_diff("data/relation-myrelation.yaml", data, &redundant);
+
+    let expected = r#"--- a/data/relation-myrelation.yaml
++++ b/data/relation-myrelation.yaml
+@@ -1,11 +1,10 @@
+ filters:
+   A utca:
+     # comment
+-    invalid: ['1', '11']
++    invalid: ['1']
+     ranges:
+       - {start: '1', end: '9'}
+   B utca:
+-    invalid: ['11']
+     ranges:
+       - {start: '1', end: '9'}
+   C utca:
+@@ -17,12 +16,9 @@
+   F utca:
+     invalid:
+       - '3'
+-      - '13'  # out
+     ranges:
+       - {start: '1', end: '9'}
+   G utca:
+-    invalid:
+-      - '13'
+     ranges:
+       - {start: '1', end: '9'}
+   H utca:
+"#;
+    assert_eq!(diff, expected);
+}
+
+/// Tests get_redundant_invalids_diff(): no filters, so nothing to remove.
+#[test]
+fn test_get_redundant_invalids_diff_no_filters() {
+    let redundant = vec![areas::RedundantInvalids {
+        street_name: "A utca".into(),
+        housenumbers: vec!["11".into()],
+    }];
+
+    let diff = get_redundant_invalids_diff(
+        "data/relation-myrelation.yaml",
+        "source: survey\n",
+        &redundant,
+    );
+
+    assert_eq!(diff, "");
+}
+
+/// Runs main() with --redundant-invalids on the given yaml path, with data/relation-myrelation.yaml
+/// having redundant invalid items.
+fn redundant_invalids_with_main(path: &str) -> (i32, String) {
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+            },
+            "nofile": {
+                "refcounty": "0",
+                "refsettlement": "0",
+            },
+        },
+    });
+    let yamls_cache = yamls_cache.to_string();
+    let files = [
+        ("data/yamls.cache", yamls_cache.as_str()),
+        (
+            "data/relations.yaml",
+            "myrelation:\n  refcounty: '0'\n  refsettlement: '0'\n",
+        ),
+        (
+            "data/relation-myrelation.yaml",
+            "filters:\n  A utca:\n    invalid: ['3', '11']\n    ranges:\n      - {start: '1', end: '9'}\n",
+        ),
+    ];
+    let (ret, output, _ctx) = run_main(&["--redundant-invalids", path], &files, "");
+    (ret, output)
+}
+
+/// Tests main(): the --redundant-invalids case.
+#[test]
+fn test_main_redundant_invalids() {
+    let expected = r#"myrelation: A utca: 11
+--- a/data/relation-myrelation.yaml
++++ b/data/relation-myrelation.yaml
+@@ -1,5 +1,5 @@
+ filters:
+   A utca:
+-    invalid: ['3', '11']
++    invalid: ['3']
+     ranges:
+       - {start: '1', end: '9'}
+"#;
+
+    let (ret, output) = redundant_invalids_with_main("data/relation-myrelation.yaml");
+
+    assert_eq!(ret, 0);
+    assert_eq!(
+        output,
+        expected.replace("data/relation-myrelation.yaml", "{0}")
+    );
+
+    // relations.yaml: all relations which have a yaml file.
+    let (ret, output) = redundant_invalids_with_main("data/relations.yaml");
+
+    assert_eq!(ret, 0);
+    assert_eq!(output, expected);
+}
+
+/// Tests main(): the --redundant-invalids case, bad paths.
+#[test]
+fn test_main_redundant_invalids_bad_path() {
+    let (ret, output) = redundant_invalids_with_main("data/relation-unknown.yaml");
+
+    assert_eq!(ret, 1);
+    assert!(output.starts_with("expected 'unknown' to be in relations.yaml"));
+
+    let (ret, output) = redundant_invalids_with_main("data/refcounty-names.yaml");
+
+    assert_eq!(ret, 1);
+    assert!(output.starts_with("expected a relation yaml file: "));
+}
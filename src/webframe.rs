@@ -720,6 +720,54 @@ Only zip codes with house numbers in OSM are considered."#,
     Ok(doc)
 }
 
+/// Expected request_uri: e.g. /osm/housenumber-stats/whole-country/cron-runs.
+fn handle_stats_cron_runs(
+    ctx: &context::Context,
+    relations: &mut areas::Relations<'_>,
+) -> anyhow::Result<yattag::Doc> {
+    let doc = yattag::Doc::new();
+    let function = "";
+    let r_name = "";
+    let r_osmid = 0;
+    let toolbar = get_toolbar(ctx, Some(relations), function, r_name, r_osmid)?;
+    doc.append_value(toolbar.get_value());
+
+    let mut table: Vec<Vec<yattag::Doc>> = vec![vec![
+        yattag::Doc::from_text(&tr("Start")),
+        yattag::Doc::from_text(&tr("Duration")),
+        yattag::Doc::from_text(&tr("Mode")),
+        yattag::Doc::from_text(&tr("Filters")),
+        yattag::Doc::from_text(&tr("Stages")),
+        yattag::Doc::from_text(&tr("Failed relations")),
+        yattag::Doc::from_text(&tr("Peak memory")),
+        yattag::Doc::from_text(&tr("Error")),
+    ]];
+    for run in cron::get_cron_runs(ctx)? {
+        let stages: Vec<String> = run
+            .stages
+            .iter()
+            .map(|stage| {
+                let duration = time::Duration::seconds_f64(stage.seconds);
+                format!("{}: {}", stage.name, cron::format_duration(duration))
+            })
+            .collect();
+        table.push(vec![
+            yattag::Doc::from_text(&format_timestamp(&run.start)?),
+            yattag::Doc::from_text(&cron::format_duration(run.end - run.start)),
+            yattag::Doc::from_text(&run.mode),
+            yattag::Doc::from_text(&run.filters),
+            yattag::Doc::from_text(&stages.join(", ")),
+            yattag::Doc::from_text(&run.failed_relations.join(", ")),
+            yattag::Doc::from_text(&run.vm_peak),
+            yattag::Doc::from_text(&run.error),
+        ]);
+    }
+    doc.append_value(util::html_table_from_list(&table).get_value());
+
+    doc.append_value(get_footer(/*last_updated=*/ "").get_value());
+    Ok(doc)
+}
+
 /// Gets the update date of the whole country.
 fn get_whole_county_last_modified(ctx: &context::Context) -> anyhow::Result<String> {
     let format = tr("{0} (osm), {1} (areas)");
@@ -962,6 +1010,10 @@ pub fn handle_stats(
             .context("handle_stats_housenumberless() failed");
     }
 
+    if request_uri.ends_with("/cron-runs") {
+        return handle_stats_cron_runs(ctx, relations).context("handle_stats_cron_runs() failed");
+    }
+
     let doc = yattag::Doc::new();
     let function = "";
     let r_name = "";
@@ -1069,6 +1121,7 @@ pub fn handle_stats(
             tr("Invalid addr:city values history"),
             "stats-invalid-addr-cities",
         ),
+        (tr("Cron runs"), "cron-runs"),
     ];
 
     {
@@ -1098,6 +1151,17 @@ pub fn handle_stats(
                 a.text(title);
                 continue;
             }
+            if identifier == "cron-runs" {
+                let a = li.tag(
+                    "a",
+                    &[(
+                        "href",
+                        &format!("{prefix}/housenumber-stats/whole-country/cron-runs"),
+                    )],
+                );
+                a.text(title);
+                continue;
+            }
             if identifier == "housenumberless-settlements" {
                 let a = li.tag(
                     "a",
@@ -1121,6 +1185,7 @@ pub fn handle_stats(
         if identifier == "cityprogress"
             || identifier == "zipprogress"
             || identifier == "housenumberless-settlements"
+            || identifier == "cron-runs"
         {
             continue;
         }
@@ -442,3 +442,35 @@ fn test_handle_stats_housenumberless() {
     assert!(!result.contains("node(1)"));
     assert!(result.contains("node(2)"));
 }
+
+/// Tests handle_stats_cron_runs().
+#[test]
+fn test_handle_stats_cron_runs() {
+    let mut test_wsgi = wsgi::tests::TestWsgi::new();
+    {
+        let conn = test_wsgi.get_ctx().get_database_connection().unwrap();
+        conn.execute_batch(
+            r#"insert into cron_runs (start, end, mode, filters, stages, failed_relations, vm_peak, error) values ('1589068800000000000', '1589072525000000000', 'relations', 'refcounty=01', '[{"name":"update_osm_json","seconds":3600.5}]', '["streets/myrelation"]', '123456 kB', '');"#,
+        )
+        .unwrap();
+    }
+
+    let root = test_wsgi.get_dom_for_path("/housenumber-stats/whole-country/cron-runs");
+
+    let mut cells = wsgi::tests::TestWsgi::find_all(&root, "body/table/tr/td");
+    // The node set is not ordered.
+    cells.sort();
+    assert_eq!(
+        cells,
+        vec![
+            "",
+            "123456 kB",
+            "1:02:05",
+            "2020-05-10 00:00",
+            "refcounty=01",
+            "relations",
+            "streets/myrelation",
+            "update_osm_json: 1:00:00",
+        ]
+    );
+}
@@ -14,9 +14,11 @@ use crate::areas;
 use crate::cache;
 use crate::context;
 use crate::i18n::translate as tr;
+use crate::notify;
 use crate::overpass_query;
 use crate::stats;
 use crate::util;
+use crate::validator;
 use crate::webframe;
 use crate::wsgi_additional;
 use crate::wsgi_json;
@@ -243,7 +245,7 @@ fn missing_housenumbers_view_lints(
     {
         let conn = ctx.get_database_connection()?;
         let mut stmt = conn
-        .prepare("select street_name, source, housenumber, reason, object_id, object_type from relation_lints where relation_name = ?1")?;
+        .prepare("select street_name, source, housenumber, reason, object_id, object_type, other_street from relation_lints where relation_name = ?1")?;
         let mut lints = stmt.query([relation.get_name()])?;
         {
             let cells: Vec<yattag::Doc> = vec![
@@ -264,17 +266,40 @@ fn missing_housenumbers_view_lints(
             let source_string = match source {
                 areas::RelationLintSource::Range => tr("street ranges"),
                 areas::RelationLintSource::Invalid => tr("invalid housenumbers"),
+                areas::RelationLintSource::Conscriptionnumber => tr("conscription numbers"),
+                areas::RelationLintSource::LetterRange => tr("letter ranges"),
+                areas::RelationLintSource::Street => tr("street names"),
+                areas::RelationLintSource::Address => tr("addresses"),
+                areas::RelationLintSource::Place => tr("places"),
             };
             let housenumber: String = lint.get(2).unwrap();
             let reason =
                 areas::RelationLintReason::try_from(lint.get::<_, String>(3).unwrap().as_str())?;
             let id: String = lint.get(4).unwrap();
             let object_type: String = lint.get(5).unwrap();
-            let reason_string = match reason {
+            let other_street: String = lint.get(6).unwrap();
+            let mut reason_string = match reason {
                 areas::RelationLintReason::CreatedInOsm => tr("created in OSM"),
                 areas::RelationLintReason::DeletedFromRef => tr("deleted from reference"),
                 areas::RelationLintReason::OutOfRange => tr("out of range"),
+                areas::RelationLintReason::ConscriptionnumberMismatch => {
+                    tr("conscription number matches, street number does not")
+                }
+                areas::RelationLintReason::Malformed => tr("malformed"),
+                areas::RelationLintReason::MisattributedStreet => {
+                    tr("probably belongs to the other street")
+                }
+                areas::RelationLintReason::Duplicate => tr("duplicate, consider merging"),
+                areas::RelationLintReason::PlaceIsStreet => {
+                    tr("addr:place is a street, use addr:street")
+                }
+                areas::RelationLintReason::StreetIsPlace => {
+                    tr("addr:street is not a street, but a place, use addr:place")
+                }
             };
+            if !other_street.is_empty() {
+                reason_string += &format!(" ({other_street})");
+            }
             cells.push(yattag::Doc::from_text(&street));
             cells.push(yattag::Doc::from_text(&source_string));
             cells.push(yattag::Doc::from_text(&housenumber));
@@ -312,6 +337,246 @@ fn missing_housenumbers_view_lints(
     Ok(doc)
 }
 
+/// Expected request uri: /osm/missing-housenumbers/ormezo/view-postcodes.
+fn missing_housenumbers_view_postcodes(
+    ctx: &context::Context,
+    relation: &areas::Relation<'_>,
+) -> anyhow::Result<yattag::Doc> {
+    let doc = yattag::Doc::new();
+    let mismatches = relation.get_postcode_mismatches()?;
+
+    let mut table: Vec<Vec<yattag::Doc>> = vec![vec![
+        yattag::Doc::from_text(&tr("Street")),
+        yattag::Doc::from_text(&tr("Housenumber")),
+        yattag::Doc::from_text(&tr("Postcode")),
+        yattag::Doc::from_text(&tr("Expected postcode")),
+        yattag::Doc::from_text(&tr("Identifier")),
+        yattag::Doc::from_text(&tr("Type")),
+    ]];
+    for mismatch in &mismatches {
+        let cell = yattag::Doc::new();
+        {
+            let href = format!(
+                "https://www.openstreetmap.org/{}/{}",
+                mismatch.object_type, mismatch.id
+            );
+            let a = cell.tag("a", &[("href", &href), ("target", "_blank")]);
+            a.text(&mismatch.id.to_string());
+        }
+        table.push(vec![
+            yattag::Doc::from_text(&mismatch.street_name),
+            yattag::Doc::from_text(&mismatch.housenumber),
+            yattag::Doc::from_text(&mismatch.postcode),
+            yattag::Doc::from_text(&mismatch.expected_postcodes.join(", ")),
+            cell,
+            yattag::Doc::from_text(&mismatch.object_type),
+        ]);
+    }
+    {
+        let p = doc.tag("p", &[]);
+        p.text(
+            &tr("The below {0} house numbers have a postcode which differs from the expected one.")
+                .replace("{0}", &mismatches.len().to_string()),
+        );
+        doc.stag("br");
+        let prefix = ctx.get_ini().get_uri_prefix();
+        let relation_name = relation.get_name();
+        {
+            let a = doc.tag(
+                "a",
+                &[(
+                    "href",
+                    &format!("{prefix}/missing-housenumbers/{relation_name}/view-postcodes-turbo"),
+                )],
+            );
+            a.text(&tr("Overpass turbo query for the below house numbers"));
+        }
+        doc.stag("br");
+        {
+            let a = doc.tag(
+                "a",
+                &[(
+                    "href",
+                    &format!("{prefix}/missing-housenumbers/{relation_name}/view-postcodes.json"),
+                )],
+            );
+            a.text(&tr("JSON format"));
+        }
+    }
+    doc.append_value(util::html_table_from_list(&table).get_value());
+
+    Ok(doc)
+}
+
+/// Expected request uri: /osm/missing-housenumbers/ormezo/view-orphan-streets.
+fn missing_housenumbers_view_orphan_streets(
+    ctx: &context::Context,
+    relation: &areas::Relation<'_>,
+) -> anyhow::Result<yattag::Doc> {
+    let doc = yattag::Doc::new();
+    let orphan_streets = relation.get_orphan_streets()?;
+
+    let mut table: Vec<Vec<yattag::Doc>> = vec![vec![
+        yattag::Doc::from_text(&tr("Street")),
+        yattag::Doc::from_text(&tr("Objects")),
+        yattag::Doc::from_text(&tr("Closest streets")),
+    ]];
+    for orphan_street in &orphan_streets {
+        let cell = yattag::Doc::new();
+        for (index, object) in orphan_street.objects.iter().enumerate() {
+            if index > 0 {
+                cell.text(", ");
+            }
+            let href = format!(
+                "https://www.openstreetmap.org/{}/{}",
+                object.object_type, object.id
+            );
+            let a = cell.tag("a", &[("href", &href), ("target", "_blank")]);
+            a.text(&object.housenumber);
+        }
+        table.push(vec![
+            yattag::Doc::from_text(&orphan_street.street_name),
+            cell,
+            yattag::Doc::from_text(&orphan_street.closest_streets.join(", ")),
+        ]);
+    }
+    {
+        let p = doc.tag("p", &[]);
+        p.text(
+            &tr("The below {0} addr:street values have no matching street in OSM.")
+                .replace("{0}", &orphan_streets.len().to_string()),
+        );
+        doc.stag("br");
+        let prefix = ctx.get_ini().get_uri_prefix();
+        let relation_name = relation.get_name();
+        {
+            let a = doc.tag(
+                "a",
+                &[(
+                    "href",
+                    &format!(
+                        "{prefix}/missing-housenumbers/{relation_name}/view-orphan-streets-turbo"
+                    ),
+                )],
+            );
+            a.text(&tr("Overpass turbo query for the below house numbers"));
+        }
+        doc.stag("br");
+        {
+            let a = doc.tag(
+                "a",
+                &[(
+                    "href",
+                    &format!(
+                        "{prefix}/missing-housenumbers/{relation_name}/view-orphan-streets.json"
+                    ),
+                )],
+            );
+            a.text(&tr("JSON format"));
+        }
+    }
+    doc.append_value(util::html_table_from_list(&table).get_value());
+
+    Ok(doc)
+}
+
+/// Expected request uri: /osm/missing-housenumbers/ormezo/view-proposed-filters.
+fn missing_housenumbers_view_proposed_filters(
+    relation: &mut areas::Relation<'_>,
+) -> anyhow::Result<yattag::Doc> {
+    let doc = yattag::Doc::new();
+    let filters = relation.get_proposed_filters()?;
+    if filters.is_empty() {
+        let p = doc.tag("p", &[]);
+        p.text(&tr("No filters to propose."));
+        return Ok(doc);
+    }
+
+    let mut table: Vec<Vec<yattag::Doc>> = vec![vec![
+        yattag::Doc::from_text(&tr("Street")),
+        yattag::Doc::from_text(&tr("Ranges")),
+        yattag::Doc::from_text(&tr("Filtered out")),
+    ]];
+    for filter in &filters {
+        let ranges: Vec<String> = filter
+            .ranges
+            .iter()
+            .map(|(start, end)| format!("{start}-{end}"))
+            .collect();
+        table.push(vec![
+            yattag::Doc::from_text(&filter.street_name),
+            yattag::Doc::from_text(&ranges.join(", ")),
+            yattag::Doc::from_text(&filter.filtered_out.join(", ")),
+        ]);
+    }
+    {
+        let p = doc.tag("p", &[]);
+        p.text(
+            &tr("The below {0} streets have no ranges yet, ranges inferred from the OSM house numbers would filter out the listed reference house numbers.")
+                .replace("{0}", &filters.len().to_string()),
+        );
+    }
+    doc.append_value(util::html_table_from_list(&table).get_value());
+    {
+        let p = doc.tag("p", &[]);
+        p.text(&tr("Snippet to merge into the relation's yaml file:"));
+    }
+    let pre = doc.tag("pre", &[]);
+    pre.text(&areas::format_proposed_filters_yaml(&filters));
+
+    Ok(doc)
+}
+
+/// Expected request uri: /osm/missing-housenumbers/ormezo/view-redundant-invalids.
+fn missing_housenumbers_view_redundant_invalids(
+    ctx: &context::Context,
+    relation: &areas::Relation<'_>,
+) -> anyhow::Result<yattag::Doc> {
+    let doc = yattag::Doc::new();
+    let redundant = relation.get_redundant_invalids()?;
+    if redundant.is_empty() {
+        let p = doc.tag("p", &[]);
+        p.text(&tr("No redundant invalid items."));
+        return Ok(doc);
+    }
+
+    let mut table: Vec<Vec<yattag::Doc>> = vec![vec![
+        yattag::Doc::from_text(&tr("Street")),
+        yattag::Doc::from_text(&tr("Invalid items")),
+    ]];
+    for street in &redundant {
+        table.push(vec![
+            yattag::Doc::from_text(&street.street_name),
+            yattag::Doc::from_text(&street.housenumbers.join(", ")),
+        ]);
+    }
+    {
+        let p = doc.tag("p", &[]);
+        p.text(
+            &tr("The below {0} streets have invalid items which are already filtered out by the ranges of the street.")
+                .replace("{0}", &redundant.len().to_string()),
+        );
+    }
+    doc.append_value(util::html_table_from_list(&table).get_value());
+
+    let path = format!("data/relation-{}.yaml", relation.get_name());
+    let abspath = ctx.get_abspath(&path);
+    if !ctx.get_file_system().path_exists(&abspath) {
+        return Ok(doc);
+    }
+    let data = ctx.get_file_system().read_to_string(&abspath)?;
+    {
+        let p = doc.tag("p", &[]);
+        p.text(&tr("Diff to remove them from the relation's yaml file:"));
+    }
+    let pre = doc.tag("pre", &[]);
+    pre.text(&validator::get_redundant_invalids_diff(
+        &path, &data, &redundant,
+    ));
+
+    Ok(doc)
+}
+
 /// The actual HTML part of missing_housenumbers_view_res().
 fn missing_housenumbers_view_res_html(
     ctx: &context::Context,
@@ -415,9 +680,67 @@ fn missing_housenumbers_view_res_html(
             );
             a.text(&tr("View lints"));
         }
+        doc.stag("br");
+        {
+            let a = doc.tag(
+                "a",
+                &[(
+                    "href",
+                    &format!("{prefix}/missing-housenumbers/{relation_name}/view-postcodes"),
+                )],
+            );
+            a.text(&tr("View postcodes"));
+        }
+        doc.stag("br");
+        {
+            let a = doc.tag(
+                "a",
+                &[(
+                    "href",
+                    &format!("{prefix}/missing-housenumbers/{relation_name}/view-orphan-streets"),
+                )],
+            );
+            a.text(&tr("View orphan streets"));
+        }
+        doc.stag("br");
+        {
+            let a = doc.tag(
+                "a",
+                &[(
+                    "href",
+                    &format!("{prefix}/missing-housenumbers/{relation_name}/view-proposed-filters"),
+                )],
+            );
+            a.text(&tr("View proposed filters"));
+        }
+        doc.stag("br");
+        {
+            let a = doc.tag(
+                "a",
+                &[(
+                    "href",
+                    &format!(
+                        "{prefix}/missing-housenumbers/{relation_name}/view-redundant-invalids"
+                    ),
+                )],
+            );
+            a.text(&tr("View redundant invalids"));
+        }
     }
 
     doc.append_value(util::html_table_from_list(&table).get_value());
+    let (interpolated_count, interpolated_table) =
+        relation.get_interpolated_housenumbers_table()?;
+    if interpolated_count > 0 {
+        {
+            let p = doc.tag("p", &[("id", "interpolated")]);
+            p.text(
+                &tr("The below {0} house numbers are only covered by addr:interpolation ways.")
+                    .replace("{0}", &interpolated_count.to_string()),
+            );
+        }
+        doc.append_value(util::html_table_from_list(&interpolated_table).get_value());
+    }
     if let Ok((osm_invalids, ref_invalids)) = relation.get_invalid_refstreets() {
         doc.append_value(
             util::invalid_refstreets_to_html(&osm_invalids, &ref_invalids).get_value(),
@@ -506,6 +829,17 @@ fn missing_streets_view_result(
             ));
         }
         p.stag("br", &[]);
+        {
+            let a = p.tag(
+                "a",
+                &[(
+                    "href",
+                    &format!("{prefix}/missing-streets/{relation_name}/view-suggestions"),
+                )],
+            );
+            a.text(&tr("Suggested mappings between OSM and reference names"));
+        }
+        p.stag("br", &[]);
         {
             let a = doc.tag(
                 "a",
@@ -710,6 +1044,7 @@ fn handle_missing_housenumbers(
                 .iter()
                 .map(|i| i.get_osm_name().into())
                 .collect();
+            let profile = ctx.get_profile();
             let conn = ctx.get_database_connection()?;
             let mut lst: Vec<String> = Vec::new();
             let mut stmt = conn.prepare(
@@ -726,18 +1061,32 @@ fn handle_missing_housenumbers(
                     ])?;
                     while let Some(row) = rows.next()? {
                         let housenumber: String = row.get(0).unwrap();
-                        let mut comment: String = row.get(1).unwrap();
-                        let suffix =
-                            areas::Relation::get_ref_suffix(if comment.is_empty() { 0 } else { 1 });
-                        if comment == " " {
-                            comment = "".into();
-                        }
+                        let comment: String = row.get(1).unwrap();
+                        let suffix = profile.get_ref_suffix(&comment);
+                        let comment = profile.get_display_comment(&comment);
                         lst.push(street.clone() + "\t" + &housenumber + suffix + "\t" + &comment);
                     }
                 }
             }
             pre.text(&lst.join("\n"));
         }
+    } else if action == "view-postcodes" {
+        doc.append_value(missing_housenumbers_view_postcodes(ctx, &relation)?.get_value());
+    } else if action == "view-postcodes-turbo" {
+        let query =
+            areas::make_turbo_query_for_postcode_mismatches(&relation.get_postcode_mismatches()?);
+        let pre = doc.tag("pre", &[]);
+        pre.text(&query);
+    } else if action == "view-orphan-streets" {
+        doc.append_value(missing_housenumbers_view_orphan_streets(ctx, &relation)?.get_value());
+    } else if action == "view-orphan-streets-turbo" {
+        let query = areas::make_turbo_query_for_orphan_streets(&relation.get_orphan_streets()?);
+        let pre = doc.tag("pre", &[]);
+        pre.text(&query);
+    } else if action == "view-proposed-filters" {
+        doc.append_value(missing_housenumbers_view_proposed_filters(&mut relation)?.get_value());
+    } else if action == "view-redundant-invalids" {
+        doc.append_value(missing_housenumbers_view_redundant_invalids(ctx, &relation)?.get_value());
     } else if action == "view-lints" {
         doc.append_value(
             missing_housenumbers_view_lints(ctx, &mut relation)
@@ -783,7 +1132,38 @@ fn missing_streets_view_turbo(
     Ok(doc)
 }
 
-/// Expected request_uri: e.g. /osm/missing-streets/ujbuda/view-[result|query].
+/// Expected request_uri: e.g. /osm/missing-streets/ormezo/view-suggestions.
+fn missing_streets_view_suggestions(
+    relations: &mut areas::Relations<'_>,
+    request_uri: &str,
+) -> anyhow::Result<yattag::Doc> {
+    let mut tokens = request_uri.split('/');
+    tokens.next_back();
+    let relation_name = tokens.next_back().context("no relation_name")?;
+
+    let doc = yattag::Doc::new();
+    let relation = relations.get_relation(relation_name)?;
+    let suggestions = relation.get_refstreet_suggestions()?;
+    if suggestions.is_empty() {
+        let p = doc.tag("p", &[]);
+        p.text(&tr(
+            "No suggestions: no similar missing and additional street names.",
+        ));
+        return Ok(doc);
+    }
+
+    {
+        let p = doc.tag("p", &[]);
+        p.text(&tr(
+            "The below mappings pair similar missing and additional street names. Verify them before adding them to the relation file:",
+        ));
+    }
+    let pre = doc.tag("pre", &[]);
+    pre.text(&util::format_refstreets_yaml(&suggestions));
+    Ok(doc)
+}
+
+/// Expected request_uri: e.g. /osm/missing-streets/ujbuda/view-[result|query|suggestions].
 fn handle_missing_streets(
     ctx: &context::Context,
     relations: &mut areas::Relations<'_>,
@@ -803,6 +1183,8 @@ fn handle_missing_streets(
 
     if action == "view-turbo" {
         doc.append_value(missing_streets_view_turbo(relations, request_uri)?.get_value());
+    } else if action == "view-suggestions" {
+        doc.append_value(missing_streets_view_suggestions(relations, request_uri)?.get_value());
     } else if action == "view-query" {
         let pre = doc.tag("pre", &[]);
         let reference = ctx.get_ini().get_reference_street_path()?;
@@ -1723,7 +2105,12 @@ pub fn application(request: &rouille::Request, ctx: &context::Context) -> rouill
     match our_application(request, ctx).context("our_application() failed") {
         // Compress.
         Ok(value) => rouille::content_encoding::apply(request, value),
-        Err(err) => webframe::handle_error(request, &format!("{err:?}")),
+        Err(err) => {
+            let message = format!("{} {}: {err:?}", request.method(), request.raw_url());
+            // Don't block the response on the network, cron sends this.
+            notify::queue(ctx, notify::Event::Web, &message);
+            webframe::handle_error(request, &format!("{err:?}"))
+        }
     }
 }
 
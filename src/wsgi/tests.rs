@@ -573,6 +573,218 @@ fn test_per_relation_lints_out_of_range() {
     );
 }
 
+/// Tests the per-relation lints page, the conscription number case.
+#[test]
+fn test_per_relation_lints_conscriptionnumber() {
+    let mut test_wsgi = TestWsgi::new();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+                "osmrelation": 42,
+            },
+        },
+        "relation-myrelation.yaml": {
+            "conscriptionnumber": true,
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &test_wsgi.ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    test_wsgi.ctx.set_file_system(&file_system);
+    {
+        let conn = test_wsgi.get_ctx().get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Fő utca', '5', '');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '3', 'Fő utca', '42', '', '', '', '5', '', '', '', '', '', 'way');
+             insert into mtimes (page, last_modified) values ('housenumbers/myrelation', '0');",
+        )
+        .unwrap();
+    }
+
+    let root = test_wsgi.get_dom_for_path("/missing-housenumbers/myrelation/view-lints");
+
+    assert_eq!(
+        TestWsgi::find_all(
+            &root,
+            "body/table/tr/td/div[@data-value='conscriptionnumber-mismatch']"
+        )
+        .len(),
+        1
+    );
+}
+
+/// Tests the per-relation lints page, the malformed letter range case.
+#[test]
+fn test_per_relation_lints_letter_range() {
+    let mut test_wsgi = TestWsgi::new();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+                "osmrelation": 42,
+            },
+        },
+        "relation-myrelation.yaml": {
+            "housenumber-letters": true,
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &test_wsgi.ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    test_wsgi.ctx.set_file_system(&file_system);
+    {
+        let conn = test_wsgi.get_ctx().get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Fő utca', '5', '');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '3', 'Fő utca', '5/C-5/A', '', '', '', '', '', '', '', '', '', 'way');
+             insert into mtimes (page, last_modified) values ('housenumbers/myrelation', '0');",
+        )
+        .unwrap();
+    }
+
+    let root = test_wsgi.get_dom_for_path("/missing-housenumbers/myrelation/view-lints");
+
+    assert_eq!(
+        TestWsgi::find_all(&root, "body/table/tr/td/div[@data-value='malformed']").len(),
+        1
+    );
+}
+
+/// Tests the per-relation lints page, the misattributed street case.
+#[test]
+fn test_per_relation_lints_misattributed_street() {
+    let mut test_wsgi = TestWsgi::new();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+                "osmrelation": 42,
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &test_wsgi.ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    test_wsgi.ctx.set_file_system(&file_system);
+    {
+        let conn = test_wsgi.get_ctx().get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Fő utca', '1', '');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Kossuth utca', '3', '');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '1', 'Fő utca', '1', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '2', 'Fő utca', '3', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('myrelation', '1', 'Kossuth utca', '', '', '', '', '');
+             insert into mtimes (page, last_modified) values ('housenumbers/myrelation', '0');",
+        )
+        .unwrap();
+    }
+
+    let root = test_wsgi.get_dom_for_path("/missing-housenumbers/myrelation/view-lints");
+
+    let results = TestWsgi::find_all(
+        &root,
+        "body/table/tr/td/div[@data-value='misattributed-street']",
+    );
+    assert_eq!(
+        results,
+        ["probably belongs to the other street (Kossuth utca)"]
+    );
+}
+
+/// Tests the per-relation lints page, the duplicate address case.
+#[test]
+fn test_per_relation_lints_duplicate() {
+    let mut test_wsgi = TestWsgi::new();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+                "osmrelation": 42,
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &test_wsgi.ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    test_wsgi.ctx.set_file_system(&file_system);
+    {
+        let conn = test_wsgi.get_ctx().get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '1', 'Fő utca', '12/A', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '2', 'Fő utca', '12a', '', '', '', '', '', '', '', '', '', 'way');
+             insert into mtimes (page, last_modified) values ('housenumbers/myrelation', '0');",
+        )
+        .unwrap();
+    }
+
+    let root = test_wsgi.get_dom_for_path("/missing-housenumbers/myrelation/view-lints");
+
+    assert_eq!(
+        TestWsgi::find_all(&root, "body/table/tr/td/div[@data-value='duplicate']").len(),
+        2
+    );
+}
+
+/// Tests the per-relation lints page, the addr:place vs addr:street case.
+#[test]
+fn test_per_relation_lints_place() {
+    let mut test_wsgi = TestWsgi::new();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+                "osmrelation": 42,
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &test_wsgi.ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    test_wsgi.ctx.set_file_system(&file_system);
+    {
+        let conn = test_wsgi.get_ctx().get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into ref_streets (county_code, settlement_code, street) values ('0', '0', 'Kert');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '1', '', '1', '', 'Kert', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '2', 'Kert', '2', '', '', '', '', '', '', '', '', '', 'node');
+             insert into mtimes (page, last_modified) values ('housenumbers/myrelation', '0');",
+        )
+        .unwrap();
+    }
+
+    let root = test_wsgi.get_dom_for_path("/missing-housenumbers/myrelation/view-lints");
+
+    assert_eq!(
+        TestWsgi::find_all(&root, "body/table/tr/td/div[@data-value='street-is-place']").len(),
+        1
+    );
+    assert_eq!(
+        TestWsgi::find_all(&root, "body/table/tr/td/div[@data-value='place-is-street']").len(),
+        1
+    );
+}
+
 /// Tests the missing house numbers page.
 #[test]
 fn test_missing_housenumbers() {
@@ -652,6 +864,326 @@ fn test_missing_housenumbers() {
     );
 }
 
+/// Tests the postcode mismatches page and its turbo query.
+#[test]
+fn test_missing_housenumbers_view_postcodes() {
+    let mut test_wsgi = TestWsgi::new();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+                "osmrelation": 42,
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &test_wsgi.ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    test_wsgi.ctx.set_file_system(&file_system);
+    {
+        let conn = test_wsgi.ctx.get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment, postcode) values ('0', '0', 'Fő utca', '1', '', '1111');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '2', 'Fő utca', '3', '1121', '', '', '', '', '', '', '', '', 'way');",
+        )
+        .unwrap();
+    }
+
+    let root = test_wsgi.get_dom_for_path("/missing-housenumbers/myrelation/view-postcodes");
+
+    let results = TestWsgi::find_all(&root, "body/table/tr");
+    // Header and 1 mismatch.
+    assert_eq!(results.len(), 2);
+    let results = TestWsgi::find_all(&root, "body/table/tr[2]/td[4]");
+    assert_eq!(results, vec!["1111"]);
+
+    let root = test_wsgi.get_dom_for_path("/missing-housenumbers/myrelation/view-postcodes-turbo");
+
+    let results = TestWsgi::find_all(&root, "body/pre");
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].contains("way(2);"), true);
+}
+
+/// Tests the orphan streets page and its turbo query.
+#[test]
+fn test_missing_housenumbers_view_orphan_streets() {
+    let mut test_wsgi = TestWsgi::new();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+                "osmrelation": 42,
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &test_wsgi.ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    test_wsgi.ctx.set_file_system(&file_system);
+    {
+        let conn = test_wsgi.ctx.get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('myrelation', '1', 'Fő utca', '', '', '', '', 'way');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '2', 'Fö utca', '3', '', '', '', '', '', '', '', '', '', 'way');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '3', 'Fö utca', '5', '', '', '', '', '', '', '', '', '', 'node');",
+        )
+        .unwrap();
+    }
+
+    let root = test_wsgi.get_dom_for_path("/missing-housenumbers/myrelation/view-orphan-streets");
+
+    let results = TestWsgi::find_all(&root, "body/table/tr");
+    // Header and 1 orphan street.
+    assert_eq!(results.len(), 2);
+    let mut results = TestWsgi::find_all(&root, "body/table/tr[2]/td[2]/a");
+    results.sort();
+    assert_eq!(results, vec!["3", "5"]);
+    let results = TestWsgi::find_all(&root, "body/table/tr[2]/td[3]");
+    assert_eq!(results, vec!["Fő utca"]);
+
+    let root =
+        test_wsgi.get_dom_for_path("/missing-housenumbers/myrelation/view-orphan-streets-turbo");
+
+    let results = TestWsgi::find_all(&root, "body/pre");
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].contains("way(2);"), true);
+}
+
+/// Tests the missing house numbers page, the view-proposed-filters case.
+#[test]
+fn test_missing_housenumbers_view_proposed_filters() {
+    let mut test_wsgi = TestWsgi::new();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+                "osmrelation": 42,
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &test_wsgi.ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    test_wsgi.ctx.set_file_system(&file_system);
+    {
+        let conn = test_wsgi.ctx.get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('myrelation', '1', 'Fő utca', '', '', '', '', 'way');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '1', 'Fő utca', '1', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '2', 'Fő utca', '3', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '3', 'Fő utca', '2', '', '', '', '', '', '', '', '', '', 'node');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Fő utca', '1', '');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Fő utca', '5', '');",
+        )
+        .unwrap();
+    }
+
+    let root = test_wsgi.get_dom_for_path("/missing-housenumbers/myrelation/view-proposed-filters");
+
+    let results = TestWsgi::find_all(&root, "body/table/tr");
+    // Header and 1 street.
+    assert_eq!(results.len(), 2);
+    let results = TestWsgi::find_all(&root, "body/table/tr[2]/td[2]");
+    assert_eq!(results, vec!["1-3, 2-2"]);
+    let results = TestWsgi::find_all(&root, "body/table/tr[2]/td[3]");
+    assert_eq!(results, vec!["5"]);
+    let results = TestWsgi::find_all(&root, "body/pre");
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].contains("# filters out: 5"), true);
+}
+
+/// Tests the missing house numbers page, the view-proposed-filters case with nothing to propose.
+#[test]
+fn test_missing_housenumbers_view_proposed_filters_empty() {
+    let mut test_wsgi = TestWsgi::new();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+                "osmrelation": 42,
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &test_wsgi.ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    test_wsgi.ctx.set_file_system(&file_system);
+
+    let root = test_wsgi.get_dom_for_path("/missing-housenumbers/myrelation/view-proposed-filters");
+
+    let results = TestWsgi::find_all(&root, "body/table");
+    assert_eq!(results.is_empty(), true);
+    let results = TestWsgi::find_all(&root, "body/p");
+    assert_eq!(
+        results.contains(&"No filters to propose.".to_string()),
+        true
+    );
+}
+
+/// Sets up a relation with a redundant invalid item, optionally with its yaml file.
+fn make_redundant_invalids_wsgi(with_yaml: bool) -> TestWsgi {
+    let mut test_wsgi = TestWsgi::new();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+                "osmrelation": 42,
+            },
+        },
+        "relation-myrelation.yaml": {
+            "filters": {
+                "Fő utca": {
+                    "invalid": ["3", "11"],
+                    "ranges": [{"start": "1", "end": "9"}],
+                },
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let relation_yaml = context::tests::TestFileSystem::make_file();
+    relation_yaml
+        .borrow_mut()
+        .write_all("filters:\n  Fő utca:\n    invalid: ['3', '11']\n    ranges:\n      - {start: '1', end: '9'}\n".as_bytes())
+        .unwrap();
+    let mut file_list = vec![("data/yamls.cache", &yamls_cache_value)];
+    if with_yaml {
+        file_list.push(("data/relation-myrelation.yaml", &relation_yaml));
+    }
+    let files = context::tests::TestFileSystem::make_files(&test_wsgi.ctx, &file_list);
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    test_wsgi.ctx.set_file_system(&file_system);
+    test_wsgi
+}
+
+/// Tests the missing house numbers page, the view-redundant-invalids case.
+#[test]
+fn test_missing_housenumbers_view_redundant_invalids() {
+    let mut test_wsgi = make_redundant_invalids_wsgi(/*with_yaml=*/ true);
+
+    let root =
+        test_wsgi.get_dom_for_path("/missing-housenumbers/myrelation/view-redundant-invalids");
+
+    let results = TestWsgi::find_all(&root, "body/table/tr");
+    // Header and 1 street.
+    assert_eq!(results.len(), 2);
+    let results = TestWsgi::find_all(&root, "body/table/tr[2]/td[1]");
+    assert_eq!(results, vec!["Fő utca"]);
+    let results = TestWsgi::find_all(&root, "body/table/tr[2]/td[2]");
+    assert_eq!(results, vec!["11"]);
+    let results = TestWsgi::find_all(&root, "body/pre");
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].contains("+    invalid: ['3']"), true);
+}
+
+/// Tests the missing house numbers page, the view-redundant-invalids case without a yaml file.
+#[test]
+fn test_missing_housenumbers_view_redundant_invalids_no_yaml() {
+    let mut test_wsgi = make_redundant_invalids_wsgi(/*with_yaml=*/ false);
+
+    let root =
+        test_wsgi.get_dom_for_path("/missing-housenumbers/myrelation/view-redundant-invalids");
+
+    let results = TestWsgi::find_all(&root, "body/table/tr");
+    assert_eq!(results.len(), 2);
+    let results = TestWsgi::find_all(&root, "body/pre");
+    assert_eq!(results.is_empty(), true);
+}
+
+/// Tests the missing house numbers page, the view-redundant-invalids case with nothing to remove.
+#[test]
+fn test_missing_housenumbers_view_redundant_invalids_empty() {
+    let mut test_wsgi = TestWsgi::new();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+                "osmrelation": 42,
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &test_wsgi.ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    test_wsgi.ctx.set_file_system(&file_system);
+
+    let root =
+        test_wsgi.get_dom_for_path("/missing-housenumbers/myrelation/view-redundant-invalids");
+
+    let results = TestWsgi::find_all(&root, "body/table");
+    assert_eq!(results.is_empty(), true);
+    let results = TestWsgi::find_all(&root, "body/p");
+    assert_eq!(
+        results.contains(&"No redundant invalid items.".to_string()),
+        true
+    );
+}
+
+/// Tests the missing house numbers page, the addr:interpolation case.
+#[test]
+fn test_missing_housenumbers_interpolation() {
+    let mut test_wsgi = TestWsgi::new();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+                "osmrelation": 42,
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &test_wsgi.ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    test_wsgi.ctx.set_file_system(&file_system);
+    {
+        let conn = test_wsgi.ctx.get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Fő utca', '1', '');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Fő utca', '3', '');
+             insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment) values ('0', '0', 'Fő utca', '5', '');
+             insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('myrelation', '1', 'Fő utca', '', '', '', '', '');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '2', 'Fő utca', '1', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_interpolations (relation, osm_id, street, interpolation, start_number, end_number) values ('myrelation', '3', 'Fő utca', 'odd', '1', '5');
+             insert into mtimes (page, last_modified) values ('streets/myrelation', '0');
+             insert into mtimes (page, last_modified) values ('housenumbers/myrelation', '0');",
+        )
+        .unwrap();
+    }
+
+    let root = test_wsgi.get_dom_for_path("/missing-housenumbers/myrelation/view-result");
+
+    let results = TestWsgi::find_all(&root, "body/p[@id='interpolated']");
+    assert_eq!(results.len(), 1);
+    let results = TestWsgi::find_all(&root, "body/table");
+    assert_eq!(results.len(), 2);
+    let results = TestWsgi::find_all(&root, "body/table[2]/tr[2]/td[2]");
+    assert_eq!(results, vec!["3, 5"]);
+}
+
 /// Tests the missing house numbers page: the output for a non-existing relation.
 #[test]
 fn test_missing_housenumbers_no_such_relation() {
@@ -1787,6 +2319,68 @@ fn test_missing_streets_view_turbo() {
     assert_eq!(results[0].contains("OSM Name 2"), false);
 }
 
+/// Tests the missing streets page: the view-suggestions output.
+#[test]
+fn test_missing_streets_view_suggestions() {
+    let mut test_wsgi = TestWsgi::new();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "gazdagret": {
+                "osmrelation": 42,
+                "refcounty": "01",
+                "refsettlement": "011",
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &test_wsgi.ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    test_wsgi.ctx.set_file_system(&file_system);
+    {
+        let conn = test_wsgi.ctx.get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into ref_streets (county_code, settlement_code, street) values ('01', '011', 'Rézsü utca');
+             insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('gazdagret', '1', 'Rézsű utca', '', '', '', '', 'way');",
+        )
+        .unwrap();
+    }
+
+    let root = test_wsgi.get_dom_for_path("/missing-streets/gazdagret/view-suggestions");
+
+    let results = TestWsgi::find_all(&root, "body/pre");
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0], "refstreets:\n  'Rézsű utca': 'Rézsü utca'\n");
+}
+
+/// Tests the missing streets page: the view-suggestions output, when there are no suggestions.
+#[test]
+fn test_missing_streets_view_suggestions_empty() {
+    let mut test_wsgi = TestWsgi::new();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "gazdagret": {
+                "osmrelation": 42,
+                "refcounty": "01",
+                "refsettlement": "011",
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        &test_wsgi.ctx,
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    test_wsgi.ctx.set_file_system(&file_system);
+
+    let root = test_wsgi.get_dom_for_path("/missing-streets/gazdagret/view-suggestions");
+
+    assert_eq!(TestWsgi::find_all(&root, "body/pre").is_empty(), true);
+}
+
 /// Tests handle_main(): if the output is well-formed.
 #[test]
 fn test_main_well_formed() {
@@ -2014,6 +2608,52 @@ fn test_application_error() {
     assert_eq!(output.contains("TestError"), true);
 }
 
+/// Tests application(): the error handling case, when failure notifications are enabled.
+#[test]
+fn test_application_error_notify() {
+    let mut ctx = context::tests::make_test_context().unwrap();
+    let unit = context::tests::TestUnit::new();
+    let unit_rc: Rc<dyn context::Unit> = Rc::new(unit);
+    ctx.set_unit(&unit_rc);
+    // No routes: the notification is only queued, not sent.
+    let network = context::tests::TestNetwork::new(&[]);
+    let network_rc: Rc<dyn context::Network> = Rc::new(network);
+    ctx.set_network(network_rc);
+    let wsgi_ini = context::tests::TestFileSystem::make_file();
+    wsgi_ini
+        .borrow_mut()
+        .write_all(b"[wsgi]\nreference_housenumbers = ''\nreference_street = ''\nreference_citycounts = ''\nreference_zipcounts = ''\nnotify_webhook_url = 'https://example.com/hook'\n")
+        .unwrap();
+    let css = context::tests::TestFileSystem::make_file();
+    let files = context::tests::TestFileSystem::make_files(
+        &ctx,
+        &[
+            ("workdir/wsgi.ini", &wsgi_ini),
+            ("target/browser/osm.min.css", &css),
+        ],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    ctx.set_file_system(&file_system);
+    ctx.update_ini().unwrap();
+    let bytes: Vec<u8> = Vec::new();
+
+    let abspath: String = "/".into();
+    let rouille_headers: Vec<(String, String)> = Vec::new();
+    let request = rouille::Request::fake_http("GET", abspath, rouille_headers, bytes);
+    let response = application(&request, &ctx);
+
+    assert_eq!(response.status_code, 500);
+    let conn = ctx.get_database_connection().unwrap();
+    let pending: String = conn
+        .query_row(
+            "select pending from notifications where kind = 'web'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert!(pending.starts_with("GET /: "));
+}
+
 /// Tests /osm/webhooks/: /osm/webhooks/github.
 #[test]
 fn test_webhooks_github() {
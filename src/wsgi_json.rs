@@ -13,7 +13,9 @@
 use crate::areas;
 use crate::cache;
 use crate::context;
+use crate::cron;
 use crate::overpass_query;
+use crate::stats;
 use crate::webframe;
 use anyhow::Context;
 use std::collections::HashMap;
@@ -30,6 +32,8 @@ fn streets_update_result_json(
     let relation = relations
         .get_relation(relation_name)
         .context("get_relation() failed")?;
+    // On-demand updates count as visits, so `cron --priority visits` prioritizes the relation, too.
+    stats::add_relation_visit(ctx, relation_name)?;
     let mut ret: HashMap<String, String> = HashMap::new();
     let query = relation.get_osm_streets_json_query()?;
     match overpass_query::overpass_query(ctx, &query) {
@@ -52,6 +56,7 @@ fn street_housenumbers_update_result_json(
     tokens.next_back();
     let relation_name = tokens.next_back().context("short tokens")?;
     let relation = relations.get_relation(relation_name)?;
+    stats::add_relation_visit(ctx, relation_name)?;
     let mut ret: HashMap<String, String> = HashMap::new();
     let query = relation.get_osm_housenumbers_json_query()?;
     match overpass_query::overpass_query_with_retry(ctx, &query) {
@@ -80,6 +85,42 @@ fn missing_housenumbers_view_result_json(
     cache::get_missing_housenumbers_json(&mut relation)
 }
 
+/// Expected request_uri: e.g. /osm/missing-housenumbers/ormezo/view-postcodes.json.
+fn missing_housenumbers_view_postcodes_json(
+    relations: &mut areas::Relations<'_>,
+    request_uri: &str,
+) -> anyhow::Result<String> {
+    let mut tokens = request_uri.split('/');
+    tokens.next_back();
+    let relation_name = tokens.next_back().context("short tokens")?;
+    let relation = relations.get_relation(relation_name)?;
+    Ok(serde_json::to_string(&relation.get_postcode_mismatches()?)?)
+}
+
+/// Expected request_uri: e.g. /osm/missing-housenumbers/ormezo/view-duplicates.json.
+fn missing_housenumbers_view_duplicates_json(
+    relations: &mut areas::Relations<'_>,
+    request_uri: &str,
+) -> anyhow::Result<String> {
+    let mut tokens = request_uri.split('/');
+    tokens.next_back();
+    let relation_name = tokens.next_back().context("short tokens")?;
+    let relation = relations.get_relation(relation_name)?;
+    Ok(serde_json::to_string(&relation.get_duplicate_addresses()?)?)
+}
+
+/// Expected request_uri: e.g. /osm/missing-housenumbers/ormezo/view-orphan-streets.json.
+fn missing_housenumbers_view_orphan_streets_json(
+    relations: &mut areas::Relations<'_>,
+    request_uri: &str,
+) -> anyhow::Result<String> {
+    let mut tokens = request_uri.split('/');
+    tokens.next_back();
+    let relation_name = tokens.next_back().context("short tokens")?;
+    let relation = relations.get_relation(relation_name)?;
+    Ok(serde_json::to_string(&relation.get_orphan_streets()?)?)
+}
+
 /// Expected request_uri: e.g. /osm/missing-housenumbers/ormezo/geojson.json.
 fn missing_housenumbers_geojson(
     ctx: &context::Context,
@@ -244,6 +285,12 @@ pub fn our_application_json(
             output = missing_housenumbers_geojson(ctx, relations, request_uri)?;
             // Allow tools like geojson.io to fetch this from their own origin.
             headers.push(("Access-Control-Allow-Origin".into(), "*".into()));
+        } else if request_uri.ends_with("/view-postcodes.json") {
+            output = missing_housenumbers_view_postcodes_json(relations, request_uri)?;
+        } else if request_uri.ends_with("/view-duplicates.json") {
+            output = missing_housenumbers_view_duplicates_json(relations, request_uri)?;
+        } else if request_uri.ends_with("/view-orphan-streets.json") {
+            output = missing_housenumbers_view_orphan_streets_json(relations, request_uri)?;
         } else {
             // Assume request_uri ends with view-result.json.
             output = missing_housenumbers_view_result_json(relations, request_uri)?;
@@ -252,6 +299,8 @@ pub fn our_application_json(
         == format!("{prefix}/lints/whole-country/invalid-addr-cities/update-result.json")
     {
         output = webframe::handle_invalid_addr_cities_update_json(ctx)?;
+    } else if request_uri == format!("{prefix}/housenumber-stats/whole-country/cron-runs.json") {
+        output = serde_json::to_string(&cron::get_cron_runs(ctx)?)?;
     } else if request_uri == format!("{prefix}/api/relations.json") {
         let conn = ctx.get_database_connection()?;
         let mut stmt = conn.prepare("select json from stats_jsons where category = 'relations'")?;
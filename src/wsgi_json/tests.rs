@@ -15,6 +15,7 @@ use std::rc::Rc;
 
 use crate::areas;
 use crate::context;
+use crate::stats;
 use crate::util;
 use crate::wsgi;
 
@@ -70,6 +71,9 @@ fn test_json_streets_update_result() {
             .len(),
         4
     );
+    // The on-demand update counts as a visit.
+    let visits = stats::get_relation_visits(ctx).unwrap();
+    assert_eq!(visits["myrelation"], 1);
 }
 
 /// Tests streets_update_result_json(): if the update-result json output on error is well-formed.
@@ -333,6 +337,130 @@ fn test_missing_housenumbers_view_result_json() {
     assert_eq!(ongoing_street.house_numbers.len(), 4);
 }
 
+/// Tests the postcode mismatches, the json output.
+#[test]
+fn test_missing_housenumbers_view_postcodes_json() {
+    let mut test_wsgi = wsgi::tests::TestWsgi::new();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        test_wsgi.get_ctx(),
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    test_wsgi.get_ctx().set_file_system(&file_system);
+    {
+        let conn = test_wsgi.get_ctx().get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into ref_housenumbers (county_code, settlement_code, street, housenumber, comment, postcode) values ('0', '0', 'Fő utca', '1', '', '1111');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '2', 'Fő utca', '3', '9999', '', '', '', '', '', '', '', '', 'way');",
+        )
+        .unwrap();
+    }
+
+    let result =
+        test_wsgi.get_json_for_path("/missing-housenumbers/myrelation/view-postcodes.json");
+
+    let mismatches = result.as_array().unwrap();
+    assert_eq!(mismatches.len(), 1);
+    assert_eq!(mismatches[0]["postcode"], "9999");
+    assert_eq!(
+        mismatches[0]["expected-postcodes"],
+        serde_json::json!(["1111"])
+    );
+}
+
+/// Tests the duplicate addresses, the json output.
+#[test]
+fn test_missing_housenumbers_view_duplicates_json() {
+    let mut test_wsgi = wsgi::tests::TestWsgi::new();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        test_wsgi.get_ctx(),
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    test_wsgi.get_ctx().set_file_system(&file_system);
+    {
+        let conn = test_wsgi.get_ctx().get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '1', 'Fő utca', '3', '', '', '', '', '', '', '', '', '', 'node');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '2', 'Fő utca', '3', '', '', '', '', '', '', '', '', '', 'way');",
+        )
+        .unwrap();
+    }
+
+    let result =
+        test_wsgi.get_json_for_path("/missing-housenumbers/myrelation/view-duplicates.json");
+
+    let duplicates = result.as_array().unwrap();
+    assert_eq!(duplicates.len(), 1);
+    assert_eq!(duplicates[0]["street-name"], "Fő utca");
+    assert_eq!(
+        duplicates[0]["objects"],
+        serde_json::json!([
+            {"id": 1, "object-type": "node", "housenumber": "3"},
+            {"id": 2, "object-type": "way", "housenumber": "3"},
+        ])
+    );
+}
+
+/// Tests the orphan streets, the json output.
+#[test]
+fn test_missing_housenumbers_view_orphan_streets_json() {
+    let mut test_wsgi = wsgi::tests::TestWsgi::new();
+    let yamls_cache = serde_json::json!({
+        "relations.yaml": {
+            "myrelation": {
+                "refcounty": "0",
+                "refsettlement": "0",
+            },
+        },
+    });
+    let yamls_cache_value = context::tests::TestFileSystem::write_json_to_file(&yamls_cache);
+    let files = context::tests::TestFileSystem::make_files(
+        test_wsgi.get_ctx(),
+        &[("data/yamls.cache", &yamls_cache_value)],
+    );
+    let file_system = context::tests::TestFileSystem::from_files(&files);
+    test_wsgi.get_ctx().set_file_system(&file_system);
+    {
+        let conn = test_wsgi.get_ctx().get_database_connection().unwrap();
+        conn.execute_batch(
+            "insert into osm_streets (relation, osm_id, name, highway, service, surface, leisure, osm_type) values ('myrelation', '1', 'Fő utca', '', '', '', '', 'way');
+             insert into osm_housenumbers (relation, osm_id, street, housenumber, postcode, place, housename, conscriptionnumber, flats, floor, door, unit, name, osm_type) values ('myrelation', '2', 'Fö utca', '3', '', '', '', '', '', '', '', '', '', 'way');",
+        )
+        .unwrap();
+    }
+
+    let result =
+        test_wsgi.get_json_for_path("/missing-housenumbers/myrelation/view-orphan-streets.json");
+
+    assert_eq!(
+        result,
+        serde_json::json!([{
+            "street-name": "Fö utca",
+            "objects": [{"id": 2, "object-type": "way", "housenumber": "3"}],
+            "closest-streets": ["Fő utca"],
+        }])
+    );
+}
+
 /// Tests missing_housenumbers_geojson(): the geojson output from overpass.
 #[test]
 fn test_missing_housenumbers_geojson() {
@@ -627,3 +755,30 @@ fn test_our_application_json_api_relations_none() {
     let relations: Vec<u64> = serde_json::from_value(result).unwrap();
     assert_eq!(relations.len(), 0);
 }
+
+/// Tests the cron runs part of our_application_json().
+#[test]
+fn test_our_application_json_cron_runs() {
+    let mut test_wsgi = wsgi::tests::TestWsgi::new();
+    {
+        let conn = test_wsgi.get_ctx().get_database_connection().unwrap();
+        conn.execute_batch(
+            r#"insert into cron_runs (start, end, mode, filters, stages, failed_relations, vm_peak, error) values ('1589068800000000000', '1589072525000000000', 'relations', '', '[]', '[]', '', 'myerror');
+               insert into cron_runs (start, end, mode, filters, stages, failed_relations, vm_peak, error) values ('1589155200000000000', '1589155260000000000', 'stats', '', '[{"name":"update_stats","seconds":60.0}]', '[]', '123456 kB', '');"#,
+        )
+        .unwrap();
+    }
+
+    let result = test_wsgi.get_json_for_path("/housenumber-stats/whole-country/cron-runs.json");
+
+    let runs = result.as_array().unwrap();
+    assert_eq!(runs.len(), 2);
+    // The latest run comes first.
+    assert_eq!(runs[0]["start"], "2020-05-11T00:00:00Z");
+    assert_eq!(runs[0]["end"], "2020-05-11T00:01:00Z");
+    assert_eq!(runs[0]["mode"], "stats");
+    assert_eq!(runs[0]["stages"][0]["name"], "update_stats");
+    assert_eq!(runs[0]["stages"][0]["seconds"], 60.0);
+    assert_eq!(runs[0]["vm_peak"], "123456 kB");
+    assert_eq!(runs[1]["error"], "myerror");
+}